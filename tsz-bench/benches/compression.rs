@@ -120,7 +120,7 @@ impl IntoCompressBits for TestRowDelta {
         encode_delta_i32(self.v16, out);
         encode_delta_i64(self.v32, out);
 
-        if self.v64 >= i128::MIN as i128 && self.v64 <= i128::MAX as i128 {
+        if self.v64 >= i64::MIN as i128 && self.v64 <= i64::MAX as i128 {
             encode_delta_i64(self.v64 as i64, out);
         } else {
             unimplemented!()
@@ -244,8 +244,7 @@ impl Compress for TestRow {
     }
 
     fn into_delta(self, prev_row: &Self) -> Self::Delta {
-        let r = self - *prev_row;
-        r
+        self - *prev_row
     }
 
     fn into_deltadelta(self, prev_prev_row: &Self, prev_row: &Self) -> Self::Delta {
@@ -257,7 +256,7 @@ impl Decompress for TestRow {
     type Full = TestRow;
     type Delta = TestRowDelta;
 
-    fn from_full<'a>(bits: &'a BitBufferSlice) -> Result<(Self, &'a BitBufferSlice), &'static str> {
+    fn from_full(bits: &BitBufferSlice) -> Result<(Self, &BitBufferSlice), &'static str> {
         TestRow::from_bits(bits).map_err(|_| "failed to unmarshal full row")
     }
 
@@ -287,8 +286,7 @@ impl Decompress for TestRow {
 fn compress(values: Vec<TestRow>) -> BitBuffer {
     let mut compressor = Compressor::new(256);
     values.into_iter().for_each(|row| compressor.compress(row));
-    let compressed = compressor.finish();
-    compressed
+    compressor.finish()
 }
 
 fn criterion_benchmark(c: &mut Criterion) {
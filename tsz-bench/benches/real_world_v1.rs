@@ -89,7 +89,6 @@ fn criterion_benchmark(c: &mut Criterion) {
     let decompress_iter = keys_decompressor
         .decompress::<PartitionedTimeKey>()
         .zip(values_decompressor.decompress::<XyzValue>())
-        .into_iter()
         .map(|(k, v)| (k.unwrap(), v.unwrap()));
 
     let rows = decompress_iter.clone().collect::<Vec<_>>();
@@ -97,26 +96,23 @@ fn criterion_benchmark(c: &mut Criterion) {
     let mut infinite_iter = std::iter::repeat(decompress_iter).flatten();
     c.bench_function("decompress xyz 10k", |b| {
         b.iter(|| {
-            black_box(for _ in 0..10_000 {
-                let foo = infinite_iter.next().unwrap();
-                black_box(foo);
-            })
+            for _ in 0..10_000 {
+                black_box(infinite_iter.next().unwrap());
+            }
         })
     });
     c.bench_function("decompress xyz 100k", |b| {
         b.iter(|| {
-            black_box(for _ in 0..100_000 {
-                let foo = infinite_iter.next().unwrap();
-                black_box(foo);
-            })
+            for _ in 0..100_000 {
+                black_box(infinite_iter.next().unwrap());
+            }
         })
     });
     c.bench_function("decompress xyz 1M", |b| {
         b.iter(|| {
-            black_box(for _ in 0..1_000_000 {
-                let foo = infinite_iter.next().unwrap();
-                black_box(foo);
-            })
+            for _ in 0..1_000_000 {
+                black_box(infinite_iter.next().unwrap());
+            }
         })
     });
 
@@ -125,33 +121,33 @@ fn criterion_benchmark(c: &mut Criterion) {
     let mut values_compressor = Compressor::<XyzValue>::new(1_000_000);
     c.bench_function("compress xyz 10k", |b| {
         b.iter(|| {
-            black_box(for _ in 0..10_000 {
+            for _ in 0..10_000 {
                 let row = infinite_iter.next().unwrap();
                 keys_compressor.compress(row.0);
                 values_compressor.compress(row.1);
-            })
+            }
         })
     });
     let mut keys_compressor = Compressor::<PartitionedTimeKey>::new(1_000_000);
     let mut values_compressor = Compressor::<XyzValue>::new(1_000_000);
     c.bench_function("compress xyz 100k", |b| {
         b.iter(|| {
-            black_box(for _ in 0..100_000 {
+            for _ in 0..100_000 {
                 let row = infinite_iter.next().unwrap();
                 keys_compressor.compress(row.0);
                 values_compressor.compress(row.1);
-            })
+            }
         })
     });
     let mut keys_compressor = Compressor::<PartitionedTimeKey>::new(1_000_000);
     let mut values_compressor = Compressor::<XyzValue>::new(1_000_000);
     c.bench_function("compress xyz 1M", |b| {
         b.iter(|| {
-            black_box(for _ in 0..1_000_000 {
+            for _ in 0..1_000_000 {
                 let row = infinite_iter.next().unwrap();
                 keys_compressor.compress(row.0);
                 values_compressor.compress(row.1);
-            })
+            }
         })
     });
 }
@@ -1,6 +1,6 @@
 extern crate alloc;
 
-use std::mem::size_of;
+use std::mem::{size_of, size_of_val};
 
 use criterion::{black_box, criterion_group, criterion_main, Criterion};
 use lz4_flex::{compress_prepend_size, decompress_size_prepended};
@@ -8,7 +8,7 @@ use polars::prelude::*;
 use tsz_compress::prelude::*;
 
 #[derive(Copy, Clone, CompressV2, DecompressV2)]
-#[repr(packed)]
+#[repr(C, packed)]
 pub struct TxyzValue {
     t: i64,
     x: i32,
@@ -147,16 +147,32 @@ fn criterion_benchmark(c: &mut Criterion) {
         });
     });
 
+    // Split the rows into frames of a 251-byte BLE notification, where the first rows of each frame weigh the most
+    let mut packetizer = Packetizer::<compress::TxyzValueCompressorImpl>::new(251);
+    let mut frames = Vec::new();
+    let mut frame_count = 0;
+    for i in 0..ts.len() {
+        let row = TxyzValue {
+            t: ts[i],
+            x: x[i],
+            y: y[i],
+            z: z[i],
+        };
+        frame_count += packetizer.compress_into(row, &mut frames).unwrap() as usize;
+    }
+    frame_count += packetizer.finish_into(&mut frames) as usize;
+    println!(
+        "TSZ 251-byte frames: {} frames, {} bytes, {:.2}x",
+        frame_count,
+        frames.len(),
+        original_size as f64 / frames.len() as f64
+    );
+
     // Concatenate all columns into a single column, the LZ4 compress for comparison
-    let ts = unsafe {
-        core::slice::from_raw_parts(ts.as_ptr() as *const u8, ts.len() * size_of::<i64>())
-    };
-    let x =
-        unsafe { core::slice::from_raw_parts(x.as_ptr() as *const u8, x.len() * size_of::<i32>()) };
-    let y =
-        unsafe { core::slice::from_raw_parts(y.as_ptr() as *const u8, y.len() * size_of::<i32>()) };
-    let z =
-        unsafe { core::slice::from_raw_parts(z.as_ptr() as *const u8, z.len() * size_of::<i32>()) };
+    let ts = unsafe { core::slice::from_raw_parts(ts.as_ptr() as *const u8, size_of_val(ts)) };
+    let x = unsafe { core::slice::from_raw_parts(x.as_ptr() as *const u8, size_of_val(x)) };
+    let y = unsafe { core::slice::from_raw_parts(y.as_ptr() as *const u8, size_of_val(y)) };
+    let z = unsafe { core::slice::from_raw_parts(z.as_ptr() as *const u8, size_of_val(z)) };
     let mut col_major_bytes = Vec::with_capacity(ts.len() + x.len() + y.len() + z.len());
     col_major_bytes.extend_from_slice(ts);
     col_major_bytes.extend_from_slice(x);
@@ -143,7 +143,7 @@ impl<T: Compress> Compressor<T> {
     pub fn len(&self) -> usize {
         // Round up to the nearest byte
         let num_bits = self.output.len();
-        (num_bits + 7) / 8
+        num_bits.div_ceil(8)
     }
 
     ///
@@ -408,7 +408,8 @@ mod tests {
                 encode_delta_i32(self.v16, out);
                 encode_delta_i64(self.v32, out);
 
-                if self.v64 < i128::MIN as i128 && self.v64 > i128::MAX as i128 {
+                #[allow(clippy::absurd_extreme_comparisons, clippy::impossible_comparisons)]
+                if self.v64 < i128::MIN && self.v64 > i128::MAX {
                     unimplemented!()
                 }
                 encode_delta_i64(self.v64 as i64, out);
@@ -530,8 +531,7 @@ mod tests {
             }
 
             fn into_delta(self, prev_row: &Self) -> Self::Delta {
-                let r = self - *prev_row;
-                r
+                self - *prev_row
             }
 
             fn into_deltadelta(self, prev_prev_row: &Self, prev_row: &Self) -> Self::Delta {
@@ -543,9 +543,7 @@ mod tests {
             type Full = TestRow;
             type Delta = TestRowDelta;
 
-            fn from_full<'a>(
-                bits: &'a BitBufferSlice,
-            ) -> Result<(Self, &'a BitBufferSlice), &'static str> {
+            fn from_full(bits: &BitBufferSlice) -> Result<(Self, &BitBufferSlice), &'static str> {
                 TestRow::from_bits(bits).map_err(|_| "failed to unmarshal full row")
             }
 
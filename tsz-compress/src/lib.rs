@@ -4,16 +4,16 @@
 //! This crate implements a specialized compression algorithm for time-series data that meets the following requirements:
 //!
 //! 0. Timestamps are integral values (signed or unsigned).
-//!   a. Data points may have one or more timestamps (SoC time, UTC time, etc.)
+//!    a. Data points may have one or more timestamps (SoC time, UTC time, etc.)
 //! 1. Data point values are integral values (signed or unsigned).
-//!   a. Separate value columns may have variable bit-widths, but all values in a column must have the same bit-width.
+//!    a. Separate value columns may have variable bit-widths, but all values in a column must have the same bit-width.
 //! 2. Data points are sorted by timestamp.
 //! 3. Data points are sampled at a regular interval, but not required to have exactly the same interval.
 //! 4. Data points are compressed in a lossless manner.
 //! 5. Data points are compressed as a stream, without requiring the entire time-series to be available at once.
 //! 6. Compressed payloads are small and can be transmitted over a BLE network.
-//!   a. Typical payloads are 100-251 byte packets.
-//!   b. Payloads will be precisely at most MTU-sized packets that not require fragmentation.
+//!    a. Typical payloads are 100-251 byte packets.
+//!    b. Payloads will be precisely at most MTU-sized packets that not require fragmentation.
 //!
 //! ## Targetted Use Case
 //!
@@ -37,13 +37,19 @@
 //! Terminology from Gorilla and here will be used interchangeably. block = packet = payload, point = data point, value = data value, timestamp = timestamp.
 //!
 //! The primary difference between Gorilla and this specialization is that all values are integral values that can be treated similarly to timestamps except with a different bit-widths.
-//! Floating point values are not encoded and must be converted to integral values before encoding.
+//! Floating point values are not delta encoded. The V2 derives XOR each `f32` or `f64` value with the previous value as Gorilla does, with windows of nibbles rather than bits.
 //!
 //! ### Timestamps
 //!
 //! Timestamps are integral values, and are encoded as a delta from the previous timestamp. The timestamp ticks are assumed to be
 //! in ticks of milliseconds, in ticks of microseconds, or in 32768 Hz clock ticks.
 //!
+//! Sensors sampling at a fixed rate produce timestamps `start + k * period` with rare jitter. The V2 derives take a
+//! `#[tsz(timestamp(period = ...))]` attribute for such a column, in the ticks of the column: `period = 1000` for
+//! 1 Hz in milliseconds, `period = 1000000` for 1 Hz in microseconds, or `period = 32768` for 1 Hz in 32768 Hz ticks.
+//! The first timestamp and the period are stored once per frame, followed by only the residuals against the grid,
+//! and nothing more when every timestamp is on the grid.
+//!
 //! The first timestamp in the block is an absolute timestamp. The first timestamp in the block is a 64-bit unsigned integer encoded as a variable length quantity (VLQ).
 //! Bytes of preceding zeros are omitted, and the first non-zero byte is encoded with the high bit set to 1. The remaining bytes are most-significant bits not-encoded are set to 0.
 //!
@@ -159,7 +165,7 @@
 //!
 
 #![cfg_attr(all(not(feature = "std"), not(test)), no_std)]
-#![cfg_attr(test, allow(unused_imports))]
+#![cfg_attr(test, allow(unused_imports, clippy::bool_assert_comparison))]
 
 use core::sync::atomic::{AtomicBool, Ordering};
 
@@ -181,7 +187,7 @@ pub mod prelude {
 }
 
 // A static variable to flag initialization once.
-static mut INITED: AtomicBool = AtomicBool::new(false);
+static INITED: AtomicBool = AtomicBool::new(false);
 
 ///
 /// Initialize (the Cortex-M heap) to prepare encoding.
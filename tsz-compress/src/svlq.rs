@@ -20,7 +20,7 @@ pub struct SvlqRef<'a>(pub &'a BitBufferSlice);
 /// When constructing a Svlq:
 /// * The first bit is 1 if the value is negative, 0 if positive.
 /// * Take the minimum trailing bits of the magnitude of the input value,
-///     starting from the MSB that is set.
+///   starting from the MSB that is set.
 /// * At least one Svlq byte is always produced.
 ///
 /// When constructing an signed:
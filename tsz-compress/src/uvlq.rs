@@ -18,7 +18,7 @@ pub struct UvlqRef<'a>(pub &'a BitBufferSlice);
 ///
 /// When constructing a Uvlq:
 /// * Take the minimum trailing bits of the input value,
-/// starting from the MSB that is set.
+///   starting from the MSB that is set.
 /// * At least one Uvlq byte is always produced.
 ///
 /// When constructing an unsigned:
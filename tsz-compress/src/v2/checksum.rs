@@ -0,0 +1,103 @@
+use crate::prelude::*;
+
+///
+/// CRC-16/CCITT-FALSE: polynomial 0x1021, initial value 0xFFFF, no reflection.
+///
+pub fn crc16(bytes: &[u8]) -> u16 {
+    let mut crc: u16 = 0xFFFF;
+    for byte in bytes {
+        crc ^= (*byte as u16) << 8;
+        for _ in 0..8 {
+            crc = if crc & 0x8000 != 0 {
+                (crc << 1) ^ 0x1021
+            } else {
+                crc << 1
+            };
+        }
+    }
+    crc
+}
+
+///
+/// CRC-32/ISO-HDLC (as used by Ethernet and zlib): reflected polynomial 0xEDB88320,
+/// initial value and final xor 0xFFFFFFFF.
+///
+pub fn crc32(bytes: &[u8]) -> u32 {
+    let mut crc: u32 = 0xFFFF_FFFF;
+    for byte in bytes {
+        crc ^= *byte as u32;
+        for _ in 0..8 {
+            crc = if crc & 1 != 0 {
+                (crc >> 1) ^ 0xEDB8_8320
+            } else {
+                crc >> 1
+            };
+        }
+    }
+    !crc
+}
+
+///
+/// Verifies a big-endian CRC-16 trailer, returning the bytes it covers.
+///
+pub fn verify_crc16(bytes: &[u8]) -> Result<&[u8], CodingError> {
+    if bytes.len() < 2 {
+        return Err(CodingError::Empty);
+    }
+    let (body, trailer) = bytes.split_at(bytes.len() - 2);
+    if crc16(body).to_be_bytes() != trailer {
+        return Err(CodingError::ChecksumMismatch);
+    }
+    Ok(body)
+}
+
+///
+/// Verifies a big-endian CRC-32 trailer, returning the bytes it covers.
+///
+pub fn verify_crc32(bytes: &[u8]) -> Result<&[u8], CodingError> {
+    if bytes.len() < 4 {
+        return Err(CodingError::Empty);
+    }
+    let (body, trailer) = bytes.split_at(bytes.len() - 4);
+    if crc32(body).to_be_bytes() != trailer {
+        return Err(CodingError::ChecksumMismatch);
+    }
+    Ok(body)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn check_values() {
+        assert_eq!(crc16(b"123456789"), 0x29B1);
+        assert_eq!(crc32(b"123456789"), 0xCBF4_3926);
+        assert_eq!(crc16(b""), 0xFFFF);
+        assert_eq!(crc32(b""), 0);
+    }
+
+    #[test]
+    fn verify_trailers() {
+        let mut bytes = b"123456789".to_vec();
+        bytes.extend(crc16(&bytes).to_be_bytes());
+        assert_eq!(verify_crc16(&bytes).unwrap(), b"123456789");
+        bytes[3] ^= 0x10;
+        assert!(matches!(
+            verify_crc16(&bytes),
+            Err(CodingError::ChecksumMismatch)
+        ));
+
+        let mut bytes = b"123456789".to_vec();
+        bytes.extend(crc32(&bytes).to_be_bytes());
+        assert_eq!(verify_crc32(&bytes).unwrap(), b"123456789");
+        bytes[3] ^= 0x01;
+        assert!(matches!(
+            verify_crc32(&bytes),
+            Err(CodingError::ChecksumMismatch)
+        ));
+
+        assert!(matches!(verify_crc16(&[0]), Err(CodingError::Empty)));
+        assert!(matches!(verify_crc32(&[0; 3]), Err(CodingError::Empty)));
+    }
+}
@@ -2,6 +2,11 @@ pub mod headers {
     pub const START_OF_COLUMN: u8 = 0b1001;
     pub const FIRST_ROW: u8 = 0b0110;
     pub const SECOND_ROW: u8 = FIRST_ROW;
+    pub const DELTA_DELTA_FIRST_ROW: u8 = 0b0101;
+    /// The first row of a column of deltas that are the XOR of each value with the previous value.
+    pub const TRANSITIONS_FIRST_ROW: u8 = 0b0111;
+    /// The first row of a column of delta-delta words that are the XOR of each value with the previous value.
+    pub const DELTA_DELTA_TRANSITIONS_FIRST_ROW: u8 = 0b0100;
 
     // DELTA ENCODING
     pub const THREE_BITS_TEN_SAMPLES: u8 = 0b1111;
@@ -11,4 +16,73 @@ pub mod headers {
     pub const SIXTEEN_BITS_TWO_SAMPLES: u8 = 0b1000;
     pub const THIRTY_TWO_BITS_ONE_SAMPLE: u8 = 0b1011;
     pub const SIXTY_FOUR_BITS_ONE_SAMPLE: u8 = 0b1101;
+    /// A run of zero deltas followed by the run length, reusing a delta-delta tag that is invalid in delta encoding.
+    pub const ZERO_RUN: u8 = 0b0000;
+
+    // DELTA-DELTA ENCODING
+    pub const DELTA_DELTA_ZERO: u8 = 0b0000;
+    pub const DELTA_DELTA_SIXTY_FOUR_BITS: u8 = 0b0001;
+    pub const DELTA_DELTA_FIVE_BITS: u8 = 0b0010;
+    pub const DELTA_DELTA_NINE_BITS: u8 = 0b0100;
+    pub const DELTA_DELTA_SIXTEEN_BITS: u8 = 0b0110;
+    pub const DELTA_DELTA_THIRTY_TWO_BITS: u8 = 0b0111;
+    /// A run of zero delta-deltas followed by the run length, reusing a delta tag that is invalid in delta-delta encoding.
+    pub const DELTA_DELTA_ZERO_RUN: u8 = 0b1111;
+
+    // XOR ENCODING
+    pub const XOR_FIRST_ROW: u8 = 0b0011;
+    pub const XOR_ZERO: u8 = 0b0000;
+    pub const XOR_SAME_WINDOW: u8 = 0b0001;
+    pub const XOR_NEW_WINDOW: u8 = 0b0010;
+
+    // TIMESTAMP GRID ENCODING
+    /// The first timestamp and period of a grid, followed by the residuals of the later timestamps.
+    pub const GRID: u8 = 0b0010;
+    /// The first timestamp and period of a grid that every timestamp of the frame is on.
+    pub const EXACT_GRID: u8 = 0b0001;
+
+    // DICTIONARY ENCODING
+    /// The distinct values of the column in the frame, followed by the index of each value into them.
+    pub const DICTIONARY: u8 = 0b1000;
+
+    // FRAME OF REFERENCE ENCODING
+    /// The bit-width and minimum of the column in the frame, followed by each value less the minimum packed at the bit-width.
+    pub const FRAME_OF_REFERENCE: u8 = 0b1100;
+}
+
+pub mod frame {
+    /// The first bytes of a frame header.
+    pub const MAGIC: [u8; 2] = *b"TZ";
+    /// The version of the frame format following the header.
+    ///
+    /// Version 2 writes the first value and first delta of each column as signed VLQs.
+    pub const VERSION: u8 = 2;
+    /// The bytes of a frame header preceding the type codes: magic, version and column count.
+    pub const PREFIX_LEN: usize = 4;
+    /// The bytes of the schema hash following the type codes.
+    pub const SCHEMA_HASH_LEN: usize = 4;
+
+    // BODY FLAGS
+    /// Precedes a body stored as is by a `#[tsz(lz)]` struct.
+    pub const RAW: u8 = 0x00;
+    /// Precedes a body packed into an LZ4 block by a `#[tsz(lz)]` struct.
+    pub const PACKED: u8 = 0x01;
+
+    // COLUMN TYPE CODES
+    pub const I8: u8 = 0x01;
+    pub const I16: u8 = 0x02;
+    pub const I32: u8 = 0x03;
+    pub const I64: u8 = 0x04;
+    pub const I128: u8 = 0x05;
+    pub const U8: u8 = 0x11;
+    pub const U16: u8 = 0x12;
+    pub const U32: u8 = 0x13;
+    pub const U64: u8 = 0x14;
+    pub const F32: u8 = 0x23;
+    pub const F64: u8 = 0x24;
+    pub const BOOL: u8 = 0x31;
+    /// Set in the type code of a `#[tsz(flags)]` column.
+    pub const FLAGS: u8 = 0x40;
+    /// Set in the type code of an `Option` column.
+    pub const NULLABLE: u8 = 0x80;
 }
@@ -0,0 +1,745 @@
+use core::marker::PhantomData;
+
+use crate::prelude::*;
+use crate::v2::consts::headers;
+use crate::v2::decode::{
+    is_delta_delta_tag, is_zero_run_tag, read_delta_delta, read_signed_vlq, read_wide_delta_delta,
+    read_zero_run, XorDecoder,
+};
+use crate::v2::dictionary::DictionaryReader;
+use crate::v2::grid::read_grid;
+use crate::v2::reference::ReferenceReader;
+use crate::v2::seek::{
+    read_count, read_fixed, read_offset, skip_checkpoint, CheckpointWidths, SeekCursor,
+};
+use crate::v2::validity::read_run;
+
+///
+/// A lazy decoder of a single column, yielding one value at a time
+/// from a cursor into the compressed bytes.
+///
+/// Values are accumulated in 128 bits with wrapping arithmetic and truncated to the
+/// column type, which matches the modular arithmetic of the column decoders.
+///
+#[derive(Debug, Clone)]
+pub struct ColumnCursor<'a, T> {
+    iter: HalfIter<'a>,
+    row: usize,
+    taken: usize,
+    delta_delta: bool,
+    transitions: bool,
+    xor: Option<XorDecoder>,
+    reference: Option<ReferenceReader<'a>>,
+    finished: bool,
+    value: i128,
+    prev_delta: i128,
+    pending: [i64; 10],
+    pending_len: usize,
+    pending_idx: usize,
+    zero_run: usize,
+    _column: PhantomData<T>,
+}
+
+impl<'a, T: ColumnValue<i128>> ColumnCursor<'a, T> {
+    ///
+    /// Creates a cursor at the start of the column at the iterator, after the start of column tag.
+    ///
+    /// The iterator is advanced past the column to the start of the next column,
+    /// and the number of values in the column is returned with the cursor.
+    ///
+    pub fn new(iter: &mut HalfIter<'a>) -> Result<(Self, usize), CodingError> {
+        let cursor = Self::at(iter.clone());
+
+        // Scan the column to find the start of the next column
+        let mut scan = cursor.clone();
+        let mut rows = 0;
+        for value in scan.by_ref() {
+            value?;
+            rows += 1;
+        }
+        *iter = scan.iter;
+
+        Ok((cursor, rows))
+    }
+
+    /// A cursor at the start of the column at the iterator, without scanning the column.
+    fn at(iter: HalfIter<'a>) -> Self {
+        Self {
+            iter,
+            row: 0,
+            taken: 0,
+            delta_delta: false,
+            transitions: false,
+            xor: None,
+            reference: None,
+            finished: false,
+            value: 0,
+            prev_delta: 0,
+            pending: [0; 10],
+            pending_len: 0,
+            pending_idx: 0,
+            zero_run: 0,
+            _column: PhantomData,
+        }
+    }
+
+    ///
+    /// Skips the next `n` values, stepping over runs of zeros and packed values without decoding each.
+    /// Skipping past the end of the column is an error.
+    ///
+    pub fn skip_rows(&mut self, mut n: usize) -> Result<(), CodingError> {
+        while n > 0 {
+            if let Some(reference) = self.reference.as_mut() {
+                reference.skip_rows(n)?;
+                self.taken += n;
+                return Ok(());
+            }
+            if self.zero_run > 0 {
+                let skipped = n.min(self.zero_run);
+                self.zero_run -= skipped;
+                if self.delta_delta && !self.transitions {
+                    self.value = self
+                        .value
+                        .wrapping_add(self.prev_delta.wrapping_mul(skipped as i128));
+                }
+                self.taken += skipped;
+                n -= skipped;
+                continue;
+            }
+            self.next().ok_or(CodingError::NotEnoughBits)??;
+            n -= 1;
+        }
+        Ok(())
+    }
+
+    /// The bit-width of the column.
+    const BITS: usize = core::mem::size_of::<T>() * 8;
+
+    /// Adds a delta to the value, or toggles the bits of the value by the delta.
+    fn apply(&mut self, delta: i128) {
+        self.value = if self.transitions {
+            self.value ^ delta
+        } else {
+            self.value.wrapping_add(delta)
+        };
+    }
+
+    /// Reads the zigzag encoded deltas of a delta word into the pending values.
+    fn read_deltas(&mut self, tag: u8) -> Result<(), CodingError> {
+        let (count, bit_width, padding, nibbles) = match tag {
+            headers::THREE_BITS_TEN_SAMPLES => (10, 3, 2, 8),
+            headers::SIX_BITS_FIVE_SAMPLES => (5, 6, 2, 8),
+            headers::EIGHT_BITS_FOUR_SAMPLES => (4, 8, 0, 8),
+            headers::TEN_BITS_THREE_SAMPLES => (3, 10, 2, 8),
+            headers::SIXTEEN_BITS_TWO_SAMPLES => (2, 16, 0, 8),
+            headers::THIRTY_TWO_BITS_ONE_SAMPLE => (1, 32, 0, 8),
+            headers::SIXTY_FOUR_BITS_ONE_SAMPLE => (1, 64, 0, 16),
+            _ => return Err(CodingError::InvalidBits),
+        };
+
+        let mut word: u64 = 0;
+        for _ in 0..nibbles {
+            let half = self.iter.next().ok_or(CodingError::NotEnoughBits)?;
+            word <<= 4;
+            word |= half as u64;
+        }
+
+        let mask = u64::MAX >> (64 - bit_width);
+        let shift = nibbles * 4 - padding - bit_width;
+        for i in 0..count {
+            let delta = (word >> (shift - bit_width * i)) & mask;
+            self.pending[i] = (delta >> 1) as i64 ^ -((delta & 1) as i64);
+        }
+        self.pending_len = count;
+        self.pending_idx = 0;
+        Ok(())
+    }
+
+    /// Decodes the next value, or None at the end of the column.
+    fn next_value(&mut self) -> Result<Option<i128>, CodingError> {
+        // Pending deltas of the last delta word
+        if self.pending_idx < self.pending_len {
+            let delta = self.pending[self.pending_idx];
+            self.pending_idx += 1;
+            self.apply(delta as i128);
+            return Ok(Some(self.value));
+        }
+
+        // Pending values of the last run of zero deltas or delta-deltas
+        if self.zero_run > 0 {
+            self.zero_run -= 1;
+            if self.delta_delta && !self.transitions {
+                self.value = self.value.wrapping_add(self.prev_delta);
+            }
+            return Ok(Some(self.value));
+        }
+
+        // Values packed against the minimum of the frame
+        if let Some(reference) = self.reference.as_mut() {
+            return reference.next().transpose();
+        }
+
+        // Floating point bits XOR the previous value
+        if let Some(xor) = self.xor.as_mut() {
+            let tag = match self.iter.next() {
+                None | Some(headers::START_OF_COLUMN) => return Ok(None),
+                Some(tag) => tag,
+            };
+            self.value = xor.read(tag, &mut self.iter)? as i128;
+            self.row += 1;
+            return Ok(Some(self.value));
+        }
+
+        match self.row {
+            0 => {
+                // First value as a signed VLQ
+                self.delta_delta = match self.iter.next() {
+                    None | Some(headers::START_OF_COLUMN) => return Ok(None),
+                    Some(headers::XOR_FIRST_ROW) if T::XOR => {
+                        let (xor, value) = XorDecoder::new(Self::BITS, &mut self.iter)?;
+                        self.xor = Some(xor);
+                        self.value = value as i128;
+                        self.row += 1;
+                        return Ok(Some(self.value));
+                    }
+                    Some(headers::FRAME_OF_REFERENCE) if Self::BITS < 128 && !T::XOR => {
+                        self.reference = Some(ReferenceReader::new(&mut self.iter, Self::BITS)?);
+                        return self.next_value();
+                    }
+                    Some(headers::FIRST_ROW) if Self::BITS < 128 && !T::XOR => false,
+                    Some(headers::DELTA_DELTA_FIRST_ROW) if !T::XOR => true,
+                    Some(headers::TRANSITIONS_FIRST_ROW) if Self::BITS < 128 && !T::XOR => {
+                        self.transitions = true;
+                        false
+                    }
+                    Some(headers::DELTA_DELTA_TRANSITIONS_FIRST_ROW)
+                        if Self::BITS < 128 && !T::XOR =>
+                    {
+                        self.transitions = true;
+                        true
+                    }
+                    _ => return Err(CodingError::InvalidBits),
+                };
+                self.value = read_signed_vlq(&mut self.iter)?;
+            }
+            1 => {
+                // First delta as a signed VLQ
+                match self.iter.next() {
+                    None | Some(headers::START_OF_COLUMN) => return Ok(None),
+                    Some(headers::SECOND_ROW) => {}
+                    _ => return Err(CodingError::InvalidBits),
+                }
+                self.prev_delta = read_signed_vlq(&mut self.iter)?;
+                self.apply(self.prev_delta);
+            }
+            _ => {
+                let tag = match self.iter.next() {
+                    None | Some(headers::START_OF_COLUMN) => return Ok(None),
+                    Some(tag) => tag,
+                };
+
+                // A run of zero deltas repeats the value, a run of zero delta-deltas repeats the delta
+                if is_zero_run_tag(tag, self.delta_delta) {
+                    let remaining = (u32::MAX as usize).saturating_sub(self.row);
+                    self.zero_run = read_zero_run(&mut self.iter, remaining)?;
+                    return self.next_value();
+                }
+
+                // Only words of the encoding marked by the first row may follow
+                if is_delta_delta_tag(tag) != self.delta_delta {
+                    return Err(CodingError::InvalidBits);
+                }
+
+                if self.delta_delta {
+                    let delta_delta = if Self::BITS == 128 {
+                        read_wide_delta_delta(tag, &mut self.iter)?
+                    } else {
+                        read_delta_delta(tag, &mut self.iter)? as i128
+                    };
+                    if self.transitions {
+                        self.apply(delta_delta);
+                    } else {
+                        self.prev_delta = self.prev_delta.wrapping_add(delta_delta);
+                        self.value = self.value.wrapping_add(self.prev_delta);
+                    }
+                } else {
+                    self.read_deltas(tag)?;
+                    self.pending_idx = 1;
+                    self.apply(self.pending[0] as i128);
+                }
+            }
+        }
+
+        self.row += 1;
+        Ok(Some(self.value))
+    }
+}
+
+impl<'a, T: ColumnValue<i128>> Iterator for ColumnCursor<'a, T> {
+    type Item = Result<T, CodingError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.finished {
+            return None;
+        }
+
+        match self.next_value() {
+            Ok(Some(value)) if T::is_valid(value) => {
+                self.taken += 1;
+                Some(Ok(T::from_bits(value)))
+            }
+            Ok(Some(_)) => {
+                self.finished = true;
+                Some(Err(CodingError::InvalidBits))
+            }
+            Ok(None) => {
+                self.finished = true;
+                None
+            }
+            Err(e) => {
+                self.finished = true;
+                Some(Err(e))
+            }
+        }
+    }
+}
+
+impl<'a, T: ColumnValue<i128>> SeekCursor<'a> for ColumnCursor<'a, T> {
+    fn read_checkpoint(
+        body: &'a [u8],
+        checkpoints: &mut HalfIter<'_>,
+        widths: CheckpointWidths,
+        _row: usize,
+        _rows: usize,
+    ) -> Result<Self, CodingError> {
+        let start = read_offset(checkpoints, widths)?;
+        let taken = read_count(checkpoints, widths)?;
+        let word = read_offset(checkpoints, widths)?;
+        let nibbles = Self::BITS / 4;
+        let value = read_fixed(checkpoints, nibbles)? as i128;
+        let prev = read_fixed(checkpoints, if T::XOR { 2 } else { nibbles })? as i128;
+
+        let mut cursor = Self::at(HalfIter::at(body, start));
+        if taken < 2 {
+            cursor.skip_rows(taken)?;
+            return Ok(cursor);
+        }
+
+        // The first value marks the encoding of the column
+        cursor.next().ok_or(CodingError::NotEnoughBits)??;
+        if cursor.reference.is_some() {
+            cursor.skip_rows(taken - 1)?;
+            return Ok(cursor);
+        }
+
+        // Resume at the word of the row, after the last two values or the last value and its window
+        cursor.value = value;
+        cursor.prev_delta = value.wrapping_sub(prev);
+        if let Some(xor) = cursor.xor.as_mut() {
+            let (leading, meaningful) = (prev as usize >> 4, (prev as usize & 0xf) + 1);
+            xor.resume(value as u64, leading, meaningful)?;
+        }
+        cursor.iter = HalfIter::at(body, word);
+        cursor.row = taken;
+        cursor.taken = taken;
+        Ok(cursor)
+    }
+}
+
+///
+/// A lazy decoder of an `Option` column, reading the validity stream
+/// alongside a cursor over the present values.
+///
+#[derive(Debug, Clone)]
+pub struct NullableCursor<'a, T> {
+    validity: HalfIter<'a>,
+    values: Option<ColumnCursor<'a, T>>,
+    rows: usize,
+    present: bool,
+    run: u64,
+}
+
+impl<'a, T: ColumnValue<i128>> NullableCursor<'a, T> {
+    ///
+    /// Creates a cursor over the `rows` rows of the column at the iterator, after the start of column tag.
+    /// A column that is not `present` in the frame has no values and takes no bits.
+    ///
+    /// The iterator is advanced past the column to the start of the next column,
+    /// and the number of rows in the column is returned with the cursor.
+    ///
+    pub fn new(
+        iter: &mut HalfIter<'a>,
+        rows: usize,
+        present: bool,
+    ) -> Result<(Self, usize), CodingError> {
+        if !present {
+            let cursor = Self {
+                validity: HalfIter::new(&[]),
+                values: None,
+                rows,
+                present: false,
+                run: rows as u64,
+            };
+            return Ok((cursor, rows));
+        }
+
+        // Scan the runs to find the start of the values
+        let validity = iter.clone();
+        let mut covered = 0;
+        let mut present_count = 0;
+        let mut present = false;
+        while covered < rows {
+            let run = read_run(iter)?;
+            if run > (rows - covered) as u64 {
+                return Err(CodingError::InvalidBits);
+            }
+            present = !present;
+            if present {
+                present_count += run as usize;
+            }
+            covered += run as usize;
+        }
+
+        let (values, values_count) = ColumnCursor::new(iter)?;
+        if values_count != present_count {
+            return Err(CodingError::InvalidBits);
+        }
+
+        let cursor = Self {
+            validity,
+            values: Some(values),
+            rows,
+            present: false,
+            run: 0,
+        };
+        Ok((cursor, rows))
+    }
+
+    ///
+    /// Skips the next `n` rows, reading the validity runs and skipping the present values among them.
+    /// Skipping past the end of the column is an error.
+    ///
+    pub fn skip_rows(&mut self, mut n: usize) -> Result<(), CodingError> {
+        self.rows = self.rows.checked_sub(n).ok_or(CodingError::NotEnoughBits)?;
+        let mut present = 0;
+        while n > 0 {
+            while self.run == 0 {
+                self.run = read_run(&mut self.validity)?;
+                self.present = !self.present;
+            }
+            let skipped = self.run.min(n as u64) as usize;
+            self.run -= skipped as u64;
+            if self.present {
+                present += skipped;
+            }
+            n -= skipped;
+        }
+        match self.values.as_mut() {
+            Some(values) => values.skip_rows(present),
+            None => Ok(()),
+        }
+    }
+}
+
+impl<'a, T: ColumnValue<i128>> Iterator for NullableCursor<'a, T> {
+    type Item = Result<Option<T>, CodingError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.rows == 0 {
+            return None;
+        }
+        self.rows -= 1;
+
+        // Runs alternate between present and missing values
+        while self.run == 0 {
+            match read_run(&mut self.validity) {
+                Ok(run) => self.run = run,
+                Err(e) => {
+                    self.rows = 0;
+                    return Some(Err(e));
+                }
+            }
+            self.present = !self.present;
+        }
+        self.run -= 1;
+
+        if !self.present {
+            return Some(Ok(None));
+        }
+        match self.values.as_mut()?.next()? {
+            Ok(value) => Some(Ok(Some(value))),
+            Err(e) => {
+                self.rows = 0;
+                Some(Err(e))
+            }
+        }
+    }
+}
+
+impl<'a, T: ColumnValue<i128>> SeekCursor<'a> for NullableCursor<'a, T> {
+    fn read_checkpoint(
+        body: &'a [u8],
+        checkpoints: &mut HalfIter<'_>,
+        widths: CheckpointWidths,
+        row: usize,
+        rows: usize,
+    ) -> Result<Self, CodingError> {
+        let rows = rows.saturating_sub(row);
+        match checkpoints.next() {
+            // A column that is not in the frame has no values
+            Some(0) => {
+                skip_checkpoint(checkpoints, widths.nullable::<T>() - 1)?;
+                Ok(Self {
+                    validity: HalfIter::new(&[]),
+                    values: None,
+                    rows,
+                    present: false,
+                    run: rows as u64,
+                })
+            }
+            Some(1) => {
+                // Read the run of the row, less the rows of the run before it
+                let mut validity = HalfIter::at(body, read_offset(checkpoints, widths)?);
+                let consumed = read_count(checkpoints, widths)? as u64;
+                let present = match checkpoints.next() {
+                    Some(0) => false,
+                    Some(1) => true,
+                    Some(_) => return Err(CodingError::InvalidBits),
+                    None => return Err(CodingError::NotEnoughBits),
+                };
+                let run = read_run(&mut validity)?
+                    .checked_sub(consumed)
+                    .ok_or(CodingError::InvalidBits)?;
+                let values = ColumnCursor::read_checkpoint(body, checkpoints, widths, row, rows)?;
+                Ok(Self {
+                    validity,
+                    values: Some(values),
+                    rows,
+                    present,
+                    run,
+                })
+            }
+            Some(_) => Err(CodingError::InvalidBits),
+            None => Err(CodingError::NotEnoughBits),
+        }
+    }
+}
+
+///
+/// A lazy decoder of a timestamp column, adding a cursor over the residuals
+/// to the grid of the first timestamp and the period.
+///
+#[derive(Debug, Clone)]
+pub struct GridCursor<'a, T> {
+    residuals: Option<ColumnCursor<'a, T>>,
+    first: i128,
+    period: i128,
+    row: usize,
+    rows: usize,
+}
+
+impl<'a, T: ColumnValue<i128> + Into<i128>> GridCursor<'a, T> {
+    ///
+    /// Creates a cursor over the timestamp column at the iterator, after the start of column tag.
+    /// A column on an exact grid has the `rows` rows of the frame.
+    ///
+    /// The iterator is advanced past the column to the start of the next column,
+    /// and the number of values in the column is returned with the cursor.
+    ///
+    pub fn new(iter: &mut HalfIter<'a>, rows: usize) -> Result<(Self, usize), CodingError> {
+        let mut cursor = Self {
+            residuals: None,
+            first: 0,
+            period: 0,
+            row: 0,
+            rows: 0,
+        };
+        let Some((first, period, exact)) = read_grid(iter)? else {
+            return Ok((cursor, 0));
+        };
+        cursor.first = first;
+        cursor.period = period;
+        cursor.rows = if exact {
+            rows
+        } else {
+            let (residuals, residual_count) = ColumnCursor::new(iter)?;
+            cursor.residuals = Some(residuals);
+            residual_count + 1
+        };
+        let rows = cursor.rows;
+        Ok((cursor, rows))
+    }
+
+    ///
+    /// Skips the next `n` timestamps, skipping their residuals.
+    /// Skipping past the end of the column is an error.
+    ///
+    pub fn skip_rows(&mut self, n: usize) -> Result<(), CodingError> {
+        if n > self.rows - self.row {
+            return Err(CodingError::NotEnoughBits);
+        }
+        if let Some(residuals) = self.residuals.as_mut() {
+            // The first timestamp has no residual
+            let skipped = if self.row == 0 {
+                n.saturating_sub(1)
+            } else {
+                n
+            };
+            residuals.skip_rows(skipped)?;
+        }
+        self.row += n;
+        Ok(())
+    }
+}
+
+impl<'a, T: ColumnValue<i128> + Into<i128>> Iterator for GridCursor<'a, T> {
+    type Item = Result<T, CodingError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.row == self.rows {
+            return None;
+        }
+        let k = self.row;
+        self.row += 1;
+
+        // The first timestamp is on the grid, and the later timestamps may have a residual
+        let residual = match self.residuals.as_mut().filter(|_| k > 0) {
+            Some(residuals) => match residuals.next()? {
+                Ok(residual) => residual.into(),
+                Err(e) => {
+                    self.row = self.rows;
+                    return Some(Err(e));
+                }
+            },
+            None => 0,
+        };
+        let nominal = self
+            .first
+            .wrapping_add((k as i128).wrapping_mul(self.period));
+        Some(Ok(T::from_bits(nominal.wrapping_add(residual))))
+    }
+}
+
+impl<'a, T: ColumnValue<i128> + Into<i128>> SeekCursor<'a> for GridCursor<'a, T> {
+    fn read_checkpoint(
+        body: &'a [u8],
+        checkpoints: &mut HalfIter<'_>,
+        widths: CheckpointWidths,
+        row: usize,
+        rows: usize,
+    ) -> Result<Self, CodingError> {
+        let mut iter = HalfIter::at(body, read_offset(checkpoints, widths)?);
+        let mut cursor = Self {
+            residuals: None,
+            first: 0,
+            period: 0,
+            row,
+            rows,
+        };
+        let Some((first, period, exact)) = read_grid(&mut iter)? else {
+            skip_checkpoint(checkpoints, widths.column::<T>())?;
+            cursor.rows = 0;
+            return Ok(cursor);
+        };
+        cursor.first = first;
+        cursor.period = period;
+        if exact {
+            skip_checkpoint(checkpoints, widths.column::<T>())?;
+        } else {
+            cursor.residuals = Some(ColumnCursor::read_checkpoint(body, checkpoints, widths, row, rows)?);
+        }
+        Ok(cursor)
+    }
+}
+
+///
+/// A lazy decoder of a `#[tsz(dictionary)]` column, reading either the packed indices
+/// into the dictionary or the deltas of the column when the dictionary was abandoned.
+///
+#[derive(Debug, Clone)]
+pub struct DictionaryCursor<'a, T> {
+    values: Option<ColumnCursor<'a, T>>,
+    indices: Option<DictionaryReader<'a>>,
+}
+
+impl<'a, T: ColumnValue<i128>> DictionaryCursor<'a, T> {
+    ///
+    /// Creates a cursor at the start of the column at the iterator, after the start of column tag.
+    ///
+    /// The iterator is advanced past the column to the start of the next column,
+    /// and the number of values in the column is returned with the cursor.
+    ///
+    pub fn new(iter: &mut HalfIter<'a>) -> Result<(Self, usize), CodingError> {
+        let mut peek = iter.clone();
+        if peek.next() != Some(headers::DICTIONARY) {
+            let (values, rows) = ColumnCursor::new(iter)?;
+            let cursor = Self {
+                values: Some(values),
+                indices: None,
+            };
+            return Ok((cursor, rows));
+        }
+
+        let indices = DictionaryReader::new(&mut peek, core::mem::size_of::<T>() * 8)?;
+        *iter = peek;
+        let rows = indices.remaining();
+        let cursor = Self {
+            values: None,
+            indices: Some(indices),
+        };
+        Ok((cursor, rows))
+    }
+
+    ///
+    /// Skips the next `n` values, stepping over the packed indices without reading each.
+    /// Skipping past the end of the column is an error.
+    ///
+    pub fn skip_rows(&mut self, n: usize) -> Result<(), CodingError> {
+        match (self.values.as_mut(), self.indices.as_mut()) {
+            (Some(values), _) => values.skip_rows(n),
+            (None, Some(indices)) => indices.skip_rows(n),
+            (None, None) if n == 0 => Ok(()),
+            (None, None) => Err(CodingError::NotEnoughBits),
+        }
+    }
+}
+
+impl<'a, T: ColumnValue<i128>> Iterator for DictionaryCursor<'a, T> {
+    type Item = Result<T, CodingError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if let Some(values) = self.values.as_mut() {
+            return values.next();
+        }
+        let value = self.indices.as_mut()?.next()?;
+        Some(value.map(T::from_bits))
+    }
+}
+
+impl<'a, T: ColumnValue<i128>> SeekCursor<'a> for DictionaryCursor<'a, T> {
+    fn read_checkpoint(
+        body: &'a [u8],
+        checkpoints: &mut HalfIter<'_>,
+        widths: CheckpointWidths,
+        row: usize,
+        rows: usize,
+    ) -> Result<Self, CodingError> {
+        let mut peek = HalfIter::at(body, read_offset(checkpoints, widths)?);
+        if peek.next() != Some(headers::DICTIONARY) {
+            let values = ColumnCursor::read_checkpoint(body, checkpoints, widths, row, rows)?;
+            return Ok(Self {
+                values: Some(values),
+                indices: None,
+            });
+        }
+
+        // The indices are packed at a fixed width, so the reader steps over the rows before the checkpoint
+        skip_checkpoint(checkpoints, widths.column::<T>())?;
+        let mut indices = DictionaryReader::new(&mut peek, core::mem::size_of::<T>() * 8)?;
+        indices.skip_rows(row)?;
+        Ok(Self {
+            values: None,
+            indices: Some(indices),
+        })
+    }
+}
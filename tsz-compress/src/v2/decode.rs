@@ -1,9 +1,12 @@
 use crate::prelude::*;
-use crate::v2::consts::headers;
+use crate::v2::consts::{frame, headers};
+use crate::v2::reference::ReferenceReader;
+use crate::v2::validity::read_run;
 use alloc::vec::Vec;
 ///
 /// An iterator over nibbles in the slice of bytes.
 ///
+#[derive(Debug, Clone)]
 pub struct HalfIter<'it> {
     buf: &'it [u8],
     upper: bool,
@@ -21,6 +24,24 @@ impl<'it> HalfIter<'it> {
             idx: 0,
         }
     }
+
+    ///
+    /// Create a HalfIter from the nibble at the offset in the slice.
+    ///
+    pub fn at(buf: &'it [u8], nibble: usize) -> Self {
+        Self {
+            buf,
+            upper: nibble.is_multiple_of(2),
+            idx: nibble / 2,
+        }
+    }
+
+    ///
+    /// The offset of the next nibble in the slice.
+    ///
+    pub fn offset(&self) -> usize {
+        self.idx * 2 + !self.upper as usize
+    }
 }
 
 impl<'it> Iterator for HalfIter<'it> {
@@ -46,6 +67,14 @@ impl<'it> Iterator for HalfIter<'it> {
             Some(value)
         }
     }
+
+    ///
+    /// Skip `n` nibbles without reading them.
+    ///
+    fn nth(&mut self, n: usize) -> Option<Self::Item> {
+        *self = Self::at(self.buf, self.offset().saturating_add(n));
+        self.next()
+    }
 }
 
 ///
@@ -110,25 +139,630 @@ pub fn read_full_i8(buf: &[u8; 1]) -> i8 {
 }
 
 ///
-/// Decodes 8-bit integers according to the delta encoding scheme.
+/// Delta-delta tags have the high bit clear, delta tags have the high bit set.
 ///
-/// This function reads the HalfIter in chunks, decodes the chunks according to the delta encoding scheme,
-/// and writes the decoded values to the Vec<i8>.
+#[inline(always)]
+pub(crate) fn is_delta_delta_tag(tag: u8) -> bool {
+    tag & 0b1000 == 0
+}
+
 ///
-pub fn decode_i8(iter: &mut HalfIter<'_>, output: &mut Vec<i8>) -> Result<(), CodingError> {
+/// Whether the tag is a run of zeros in the delta or delta-delta encoding of the column.
+///
+#[inline(always)]
+pub(crate) fn is_zero_run_tag(tag: u8, delta_delta: bool) -> bool {
+    if delta_delta {
+        tag == headers::DELTA_DELTA_ZERO_RUN
+    } else {
+        tag == headers::ZERO_RUN
+    }
+}
+
+/// Reads the bits of a value of a column of the bit-width, most significant nibble first.
+pub(crate) fn read_value_bits(iter: &mut HalfIter<'_>, bits: usize) -> Result<i128, CodingError> {
+    let mut word: u128 = 0;
+    for _ in 0..bits / 4 {
+        let half = iter.next().ok_or(CodingError::NotEnoughBits)?;
+        word = (word << 4) | half as u128;
+    }
+    Ok(word as i128)
+}
+
+///
+/// Reads a signed value written as a nibble-aligned VLQ of its zigzag encoding.
+///
+pub(crate) fn read_signed_vlq(iter: &mut HalfIter<'_>) -> Result<i128, CodingError> {
+    let mut zigzag: u128 = 0;
+    loop {
+        let half = iter.next().ok_or(CodingError::NotEnoughBits)?;
+        if zigzag > u128::MAX >> 3 {
+            return Err(CodingError::InvalidBits);
+        }
+        zigzag = (zigzag << 3) | (half & 0b111) as u128;
+        if half & 0b1000 == 0 {
+            return Ok((zigzag >> 1) as i128 ^ -((zigzag & 1) as i128));
+        }
+    }
+}
+
+///
+/// Unpacks values of at most 64 bits from nibbles, most significant bit first.
+///
+#[derive(Debug, Clone)]
+pub(crate) struct BitUnpacker<'a> {
+    iter: HalfIter<'a>,
+    acc: u128,
+    acc_bits: usize,
+}
+
+impl<'a> BitUnpacker<'a> {
+    /// Unpacks from the nibbles at the iterator.
+    pub(crate) fn new(iter: HalfIter<'a>) -> Self {
+        Self {
+            iter,
+            acc: 0,
+            acc_bits: 0,
+        }
+    }
+
+    /// Reads the next value of the bit-width.
+    pub(crate) fn read(&mut self, bits: usize) -> Result<u64, CodingError> {
+        if bits == 0 {
+            return Ok(0);
+        }
+        while self.acc_bits < bits {
+            let half = self.iter.next().ok_or(CodingError::NotEnoughBits)?;
+            self.acc = (self.acc << 4) | half as u128;
+            self.acc_bits += 4;
+        }
+        self.acc_bits -= bits;
+        let value = (self.acc >> self.acc_bits) as u64 & (u64::MAX >> (64 - bits));
+        self.acc &= (1 << self.acc_bits) - 1;
+        Ok(value)
+    }
+
+    /// Skips the next bits, stepping over whole nibbles without reading them.
+    pub(crate) fn skip(&mut self, bits: usize) -> Result<(), CodingError> {
+        let buffered = bits.min(self.acc_bits);
+        self.acc_bits -= buffered;
+        self.acc &= (1 << self.acc_bits) - 1;
+        let bits = bits - buffered;
+        if bits >= 4 {
+            self.iter
+                .nth(bits / 4 - 1)
+                .ok_or(CodingError::NotEnoughBits)?;
+        }
+        self.read(bits % 4)?;
+        Ok(())
+    }
+}
+
+///
+/// Reads the length of a run of zero deltas or delta-deltas following a zero run tag.
+///
+/// A run may not take a column past the `remaining` rows of the frame.
+///
+pub(crate) fn read_zero_run(
+    iter: &mut HalfIter<'_>,
+    remaining: usize,
+) -> Result<usize, CodingError> {
+    let run = read_run(iter)?;
+    if run == 0 || run > remaining as u64 {
+        return Err(CodingError::InvalidBits);
+    }
+    Ok(run as usize)
+}
+
+///
+/// Validates a frame header against the expected column types and schema hash,
+/// returning the bytes following the header.
+///
+pub fn read_frame_header<'a>(
+    bytes: &'a [u8],
+    column_types: &[u8],
+    schema_hash: u32,
+) -> Result<&'a [u8], CodingError> {
+    if bytes.is_empty() {
+        return Err(CodingError::Empty);
+    }
+
+    let header_len = frame::PREFIX_LEN + column_types.len() + frame::SCHEMA_HASH_LEN;
+    if bytes.len() < header_len {
+        return Err(CodingError::NotEnoughBits);
+    }
+
+    let (prefix, rest) = bytes.split_at(frame::PREFIX_LEN);
+    let (types, rest) = rest.split_at(column_types.len());
+    let (hash, rest) = rest.split_at(frame::SCHEMA_HASH_LEN);
+    if prefix[..2] != frame::MAGIC
+        || prefix[2] != frame::VERSION
+        || prefix[3] as usize != column_types.len()
+        || types != column_types
+        || hash != schema_hash.to_be_bytes()
+    {
+        return Err(CodingError::SchemaMismatch);
+    }
+
+    Ok(rest)
+}
+
+///
+/// Reads the zigzag encoded delta-delta following a delta-delta tag.
+///
+/// The tag is the nibble already taken from the iterator. The 64-bit
+/// delta-delta may have wrapped, so the result is meant for wrapping arithmetic.
+///
+pub(crate) fn read_delta_delta(tag: u8, iter: &mut HalfIter<'_>) -> Result<i64, CodingError> {
+    let (nibbles, word) = match tag {
+        headers::DELTA_DELTA_ZERO => return Ok(0),
+        headers::DELTA_DELTA_SIXTY_FOUR_BITS => (16, 0),
+        // 1 bit of the tag is the top of the 5 bit delta-delta
+        0b0010..=0b0011 => (1, (tag & 0b1) as u64),
+        // 1 bit of the tag is the top of the 9 bit delta-delta
+        0b0100..=0b0101 => (2, (tag & 0b1) as u64),
+        headers::DELTA_DELTA_SIXTEEN_BITS => (4, 0),
+        headers::DELTA_DELTA_THIRTY_TWO_BITS => (8, 0),
+        _ => return Err(CodingError::InvalidBits),
+    };
+
+    let mut word = word;
+    for _ in 0..nibbles {
+        let half = iter.next().ok_or(CodingError::NotEnoughBits)?;
+        word <<= 4;
+        word |= half as u64;
+    }
+
+    Ok((word >> 1) as i64 ^ -((word & 1) as i64))
+}
+
+///
+/// Reads the zigzag encoded delta-delta of a 128-bit column following a delta-delta tag.
+///
+/// A 64-bit delta-delta tag followed by a zero upper word escapes to a 128-bit delta-delta.
+///
+pub(crate) fn read_wide_delta_delta(tag: u8, iter: &mut HalfIter<'_>) -> Result<i128, CodingError> {
+    if tag != headers::DELTA_DELTA_SIXTY_FOUR_BITS {
+        return read_delta_delta(tag, iter).map(|delta_delta| delta_delta as i128);
+    }
+
+    let mut word: u128 = 0;
+    for _ in 0..8 {
+        let half = iter.next().ok_or(CodingError::NotEnoughBits)?;
+        word <<= 4;
+        word |= half as u128;
+    }
+
+    if word != 0 {
+        for _ in 0..8 {
+            let half = iter.next().ok_or(CodingError::NotEnoughBits)?;
+            word <<= 4;
+            word |= half as u128;
+        }
+    } else {
+        for _ in 0..32 {
+            let half = iter.next().ok_or(CodingError::NotEnoughBits)?;
+            word <<= 4;
+            word |= half as u128;
+        }
+        return Ok((word >> 1) as i128 ^ -((word & 1) as i128));
+    }
+
+    let word = word as u64;
+    Ok(((word >> 1) as i64 ^ -((word & 1) as i64)) as i128)
+}
+
+///
+/// A column value stored as the two's complement bits of a signed integer.
+///
+/// Signed and unsigned columns of the same bit-width share a decoder.
+///
+pub trait ColumnValue<S>: Copy {
+    /// Whether the column is floating point bits encoded by XOR with the previous value.
+    const XOR: bool = false;
+
+    fn from_bits(bits: S) -> Self;
+
+    /// Whether the bits are a value of the column, checked by the lazy decoders.
+    #[inline(always)]
+    fn is_valid(_bits: S) -> bool {
+        true
+    }
+}
+
+macro_rules! impl_column_value {
+    ($signed:ty, $unsigned:ty) => {
+        impl ColumnValue<$signed> for $signed {
+            #[inline(always)]
+            fn from_bits(bits: $signed) -> Self {
+                bits
+            }
+        }
+
+        impl ColumnValue<$signed> for $unsigned {
+            #[inline(always)]
+            fn from_bits(bits: $signed) -> Self {
+                bits as $unsigned
+            }
+        }
+
+        impl ColumnValue<i128> for $signed {
+            #[inline(always)]
+            fn from_bits(bits: i128) -> Self {
+                bits as $signed
+            }
+        }
+
+        impl ColumnValue<i128> for $unsigned {
+            #[inline(always)]
+            fn from_bits(bits: i128) -> Self {
+                bits as $unsigned
+            }
+        }
+    };
+}
+
+impl_column_value!(i8, u8);
+impl_column_value!(i16, u16);
+impl_column_value!(i32, u32);
+impl_column_value!(i64, u64);
+
+impl ColumnValue<i128> for i128 {
+    #[inline(always)]
+    fn from_bits(bits: i128) -> Self {
+        bits
+    }
+}
+
+impl ColumnValue<i128> for bool {
+    #[inline(always)]
+    fn from_bits(bits: i128) -> Self {
+        bits as u8 != 0
+    }
+
+    #[inline(always)]
+    fn is_valid(bits: i128) -> bool {
+        matches!(bits as u8, 0 | 1)
+    }
+}
+
+impl ColumnValue<i128> for f32 {
+    const XOR: bool = true;
+
+    #[inline(always)]
+    fn from_bits(bits: i128) -> Self {
+        f32::from_bits(bits as u32)
+    }
+}
+
+impl ColumnValue<i128> for f64 {
+    const XOR: bool = true;
+
+    #[inline(always)]
+    fn from_bits(bits: i128) -> Self {
+        f64::from_bits(bits as u64)
+    }
+}
+
+///
+/// Decodes the XOR words of a floating point column, tracking the window of the previous word.
+///
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct XorDecoder {
+    prev: u64,
+    nibbles: usize,
+    leading: usize,
+    meaningful: usize,
+}
+
+impl XorDecoder {
+    ///
+    /// Reads the full first value of the given bit-width, 32 or 64, after its first row tag.
+    ///
+    pub(crate) fn new(bits: usize, iter: &mut HalfIter<'_>) -> Result<(Self, u64), CodingError> {
+        let nibbles = bits / 4;
+        let prev = read_nibbles(iter, nibbles)?;
+        let decoder = Self {
+            prev,
+            nibbles,
+            leading: 0,
+            meaningful: nibbles,
+        };
+        Ok((decoder, prev))
+    }
+
+    ///
+    /// Reads the next value following the tag.
+    ///
+    pub(crate) fn read(&mut self, tag: u8, iter: &mut HalfIter<'_>) -> Result<u64, CodingError> {
+        match tag {
+            headers::XOR_ZERO => {}
+            headers::XOR_SAME_WINDOW => {
+                let trailing = self.nibbles - self.leading - self.meaningful;
+                self.prev ^= read_nibbles(iter, self.meaningful)? << (4 * trailing);
+            }
+            headers::XOR_NEW_WINDOW => {
+                let leading = iter.next().ok_or(CodingError::NotEnoughBits)? as usize;
+                let meaningful = iter.next().ok_or(CodingError::NotEnoughBits)? as usize + 1;
+                if leading + meaningful > self.nibbles {
+                    return Err(CodingError::InvalidBits);
+                }
+                let trailing = self.nibbles - leading - meaningful;
+                self.prev ^= read_nibbles(iter, meaningful)? << (4 * trailing);
+                self.leading = leading;
+                self.meaningful = meaningful;
+            }
+            _ => return Err(CodingError::InvalidBits),
+        }
+        Ok(self.prev)
+    }
+
+    ///
+    /// Resumes decoding after the previous value with its window.
+    ///
+    pub(crate) fn resume(
+        &mut self,
+        prev: u64,
+        leading: usize,
+        meaningful: usize,
+    ) -> Result<(), CodingError> {
+        if meaningful == 0 || leading + meaningful > self.nibbles {
+            return Err(CodingError::InvalidBits);
+        }
+        self.prev = prev;
+        self.leading = leading;
+        self.meaningful = meaningful;
+        Ok(())
+    }
+}
+
+/// Reads `count` nibbles, most significant first.
+fn read_nibbles(iter: &mut HalfIter<'_>, count: usize) -> Result<u64, CodingError> {
+    let mut word: u64 = 0;
+    for _ in 0..count {
+        let half = iter.next().ok_or(CodingError::NotEnoughBits)?;
+        word = (word << 4) | half as u64;
+    }
+    Ok(word)
+}
+
+///
+/// Decodes 32-bit floating point values according to the XOR encoding scheme.
+///
+/// This function reads the HalfIter in chunks, decodes the XOR of each value with the previous value,
+/// and writes the decoded values to the Vec<f32>.
+///
+pub fn decode_f32(iter: &mut HalfIter<'_>, output: &mut Vec<f32>) -> Result<(), CodingError> {
+    decode_f32_rows(iter, u32::MAX as usize, output)
+}
+
+///
+/// Decodes a column of at most `rows` f32 values, as `decode_f32`.
+///
+pub fn decode_f32_rows(
+    iter: &mut HalfIter<'_>,
+    rows: usize,
+    output: &mut Vec<f32>,
+) -> Result<(), CodingError> {
+    decode_xor(iter, rows, output)
+}
+
+///
+/// Decodes 64-bit floating point values according to the XOR encoding scheme.
+///
+/// This function reads the HalfIter in chunks, decodes the XOR of each value with the previous value,
+/// and writes the decoded values to the Vec<f64>.
+///
+pub fn decode_f64(iter: &mut HalfIter<'_>, output: &mut Vec<f64>) -> Result<(), CodingError> {
+    decode_f64_rows(iter, u32::MAX as usize, output)
+}
+
+///
+/// Decodes a column of at most `rows` f64 values, as `decode_f64`.
+///
+pub fn decode_f64_rows(
+    iter: &mut HalfIter<'_>,
+    rows: usize,
+    output: &mut Vec<f64>,
+) -> Result<(), CodingError> {
+    decode_xor(iter, rows, output)
+}
+
+///
+/// Decodes a column of XOR encoded floating point bits.
+///
+fn decode_xor<T: ColumnValue<i128>>(
+    iter: &mut HalfIter<'_>,
+    _rows: usize,
+    output: &mut Vec<T>,
+) -> Result<(), CodingError> {
     // Check for 0 rows
     match iter.next() {
         None => return Ok(()),
         Some(headers::START_OF_COLUMN) => return Ok(()),
-        Some(headers::FIRST_ROW) => {}
+        Some(headers::XOR_FIRST_ROW) => {}
         _ => return Err(CodingError::InvalidBits),
     }
 
-    // Full 8 bit value
-    let buf = [(iter.next().ok_or(CodingError::NotEnoughBits)? << 4)
-        | iter.next().ok_or(CodingError::NotEnoughBits)?];
-    let value = read_full_i8(&buf);
-    output.push(value);
+    // Full value in the bit-width of the column
+    let (mut decoder, value) = XorDecoder::new(core::mem::size_of::<T>() * 8, iter)?;
+    output.push(T::from_bits(value as i128));
+
+    while let Some(tag) = iter.next() {
+        if tag == headers::START_OF_COLUMN {
+            // Start of column of next column
+            break;
+        }
+        let value = decoder.read(tag, iter)?;
+        output.push(T::from_bits(value as i128));
+    }
+
+    Ok(())
+}
+
+///
+/// Decodes 8-bit signed integers according to the delta or delta-delta encoding scheme.
+///
+/// This function reads the HalfIter in chunks, decodes the chunks according to the delta or delta-delta encoding scheme,
+/// and writes the decoded values to the Vec<i8>.
+///
+pub fn decode_i8(iter: &mut HalfIter<'_>, output: &mut Vec<i8>) -> Result<(), CodingError> {
+    decode_i8_rows(iter, u32::MAX as usize, output)
+}
+
+///
+/// Decodes a column of at most `rows` i8 values, as `decode_i8`.
+///
+pub fn decode_i8_rows(
+    iter: &mut HalfIter<'_>,
+    rows: usize,
+    output: &mut Vec<i8>,
+) -> Result<(), CodingError> {
+    decode_8_bits(iter, rows, output)
+}
+
+///
+/// Decodes 8-bit unsigned integers according to the delta or delta-delta encoding scheme.
+///
+/// This function reads the HalfIter in chunks, decodes the chunks according to the delta or delta-delta encoding scheme,
+/// and writes the decoded values to the Vec<u8>.
+///
+pub fn decode_u8(iter: &mut HalfIter<'_>, output: &mut Vec<u8>) -> Result<(), CodingError> {
+    decode_u8_rows(iter, u32::MAX as usize, output)
+}
+
+///
+/// Decodes a column of at most `rows` u8 values, as `decode_u8`.
+///
+pub fn decode_u8_rows(
+    iter: &mut HalfIter<'_>,
+    rows: usize,
+    output: &mut Vec<u8>,
+) -> Result<(), CodingError> {
+    decode_8_bits(iter, rows, output)
+}
+
+///
+/// Decodes bools according to the transitions encoding scheme.
+///
+/// This function decodes the column as 8-bit unsigned integers, rejecting any value other than 0 or 1,
+/// and writes the decoded values to the Vec<bool>.
+///
+pub fn decode_bool(iter: &mut HalfIter<'_>, output: &mut Vec<bool>) -> Result<(), CodingError> {
+    decode_bool_rows(iter, u32::MAX as usize, output)
+}
+
+///
+/// Decodes a column of at most `rows` bool values, as `decode_bool`.
+///
+pub fn decode_bool_rows(
+    iter: &mut HalfIter<'_>,
+    rows: usize,
+    output: &mut Vec<bool>,
+) -> Result<(), CodingError> {
+    let mut bits: Vec<u8> = Vec::new();
+    decode_8_bits(iter, rows, &mut bits)?;
+    output.reserve(bits.len());
+    for bits in bits {
+        match bits {
+            0 => output.push(false),
+            1 => output.push(true),
+            _ => return Err(CodingError::InvalidBits),
+        }
+    }
+    Ok(())
+}
+
+///
+/// A decoder of a column of at most the given number of rows, after the start of column tag.
+///
+pub type ColumnDecoder<T> = fn(&mut HalfIter<'_>, usize, &mut Vec<T>) -> Result<(), CodingError>;
+
+///
+/// A fieldless enum stored as a column of its integer discriminant.
+///
+/// Implemented by `#[derive(ColumnEnum)]` on an enum with an integer `#[repr]`.
+///
+pub trait ColumnEnum: Copy {
+    /// The integer type of the discriminant, as in `#[repr(u8)]`.
+    type Repr: Copy;
+
+    /// The discriminant of the variant.
+    fn discriminant(self) -> Self::Repr;
+
+    /// The variant of the discriminant, if there is one.
+    fn from_discriminant(discriminant: Self::Repr) -> Option<Self>;
+
+    /// Decodes a column of at most `rows` discriminants into variants.
+    fn decode_column(
+        iter: &mut HalfIter<'_>,
+        rows: usize,
+        output: &mut Vec<Self>,
+    ) -> Result<(), CodingError>;
+}
+
+///
+/// Decodes a column of discriminants with the decoder of the integer type,
+/// rejecting discriminants of no variant.
+///
+pub fn decode_enum<T: ColumnEnum>(
+    iter: &mut HalfIter<'_>,
+    rows: usize,
+    output: &mut Vec<T>,
+    decode: ColumnDecoder<T::Repr>,
+) -> Result<(), CodingError> {
+    let mut discriminants = Vec::new();
+    decode(iter, rows, &mut discriminants)?;
+    output.reserve(discriminants.len());
+    for discriminant in discriminants {
+        output.push(T::from_discriminant(discriminant).ok_or(CodingError::InvalidDiscriminant)?);
+    }
+    Ok(())
+}
+
+///
+/// Decodes a column of 8-bit values, reconstructing the two's complement bits as an i8.
+/// Runs of zeros and frames of reference of more than the `rows` of the column are invalid.
+///
+fn decode_8_bits<T: ColumnValue<i8>>(
+    iter: &mut HalfIter<'_>,
+    rows: usize,
+    output: &mut Vec<T>,
+) -> Result<(), CodingError> {
+    let start = output.len();
+
+    // Check for 0 rows
+    let (delta_delta, transitions) = match iter.next() {
+        None => return Ok(()),
+        Some(headers::START_OF_COLUMN) => return Ok(()),
+        Some(headers::FIRST_ROW) => (false, false),
+        Some(headers::DELTA_DELTA_FIRST_ROW) => (true, false),
+        Some(headers::TRANSITIONS_FIRST_ROW) => (false, true),
+        Some(headers::DELTA_DELTA_TRANSITIONS_FIRST_ROW) => (true, true),
+        Some(headers::FRAME_OF_REFERENCE) => {
+            let reader = ReferenceReader::new(iter, 8)?;
+            if reader.remaining() > rows {
+                return Err(CodingError::InvalidBits);
+            }
+            for value in reader {
+                output.push(T::from_bits(value? as i8));
+            }
+            return Ok(());
+        }
+        _ => return Err(CodingError::InvalidBits),
+    };
+
+    // Deltas are added to the previous value, or toggle the bits of the previous value
+    let apply = |value: i8, delta: i16| {
+        if transitions {
+            value ^ delta as i8
+        } else {
+            (value as i16).wrapping_add(delta) as i8
+        }
+    };
+
+    // First value as a signed VLQ
+    let value = read_signed_vlq(iter)? as i8;
+    output.push(T::from_bits(value));
 
     // One row
     match iter.next() {
@@ -138,19 +772,35 @@ pub fn decode_i8(iter: &mut HalfIter<'_>, output: &mut Vec<i8>) -> Result<(), Co
         _ => return Err(CodingError::InvalidBits),
     }
 
-    // Delta encoded 16 bit value
-    let buf = [
-        (iter.next().ok_or(CodingError::NotEnoughBits)? << 4)
-            | iter.next().ok_or(CodingError::NotEnoughBits)?,
-        (iter.next().ok_or(CodingError::NotEnoughBits)? << 4)
-            | iter.next().ok_or(CodingError::NotEnoughBits)?,
-    ];
-    let delta = read_full_i16(&buf);
-    let mut value = (value as i16 + delta) as i8;
-    output.push(value);
+    // First delta as a signed VLQ
+    let delta = read_signed_vlq(iter)? as i16;
+    let mut value = apply(value, delta);
+    output.push(T::from_bits(value));
+    let mut prev_delta = delta;
 
     // Every thing is delta or delta-delta encoded from here on out
     while let Some(tag) = iter.next() {
+        // A run of zero deltas repeats the value, a run of zero delta-deltas repeats the delta
+        if is_zero_run_tag(tag, delta_delta) {
+            let run = read_zero_run(iter, rows.saturating_sub(output.len() - start))?;
+            let delta = if delta_delta && !transitions {
+                prev_delta
+            } else {
+                0
+            };
+            output.reserve(run);
+            for _ in 0..run {
+                value = (value as i16).wrapping_add(delta) as i8;
+                output.push(T::from_bits(value));
+            }
+            continue;
+        }
+
+        // Only words of the encoding marked by the first row may follow
+        if tag != headers::START_OF_COLUMN && is_delta_delta_tag(tag) != delta_delta {
+            return Err(CodingError::InvalidBits);
+        }
+
         match tag {
             headers::START_OF_COLUMN => {
                 // Start of column of next column
@@ -173,8 +823,8 @@ pub fn decode_i8(iter: &mut HalfIter<'_>, output: &mut Vec<i8>) -> Result<(), Co
                 for i in 0..10 {
                     let delta = ((word >> (shift - bit_width * i)) & 0b111) as i16;
                     let delta = (delta >> 1) ^ -(delta & 1);
-                    value = (value as i16 + delta) as i8;
-                    output.push(value);
+                    value = apply(value, delta);
+                    output.push(T::from_bits(value));
                 }
             }
             headers::SIX_BITS_FIVE_SAMPLES => {
@@ -194,8 +844,8 @@ pub fn decode_i8(iter: &mut HalfIter<'_>, output: &mut Vec<i8>) -> Result<(), Co
                 for i in 0..5 {
                     let delta = ((word >> (shift - bit_width * i)) & 0b11_1111) as i16;
                     let delta = (delta >> 1) ^ -(delta & 1);
-                    value = (value as i16 + delta) as i8;
-                    output.push(value);
+                    value = apply(value, delta);
+                    output.push(T::from_bits(value));
                 }
             }
             headers::EIGHT_BITS_FOUR_SAMPLES => {
@@ -215,8 +865,8 @@ pub fn decode_i8(iter: &mut HalfIter<'_>, output: &mut Vec<i8>) -> Result<(), Co
                 for i in 0..4 {
                     let delta = ((word >> (shift - bit_width * i)) & 0b1111_1111) as i16;
                     let delta = (delta >> 1) ^ -(delta & 1);
-                    value = (value as i16 + delta) as i8;
-                    output.push(value);
+                    value = apply(value, delta);
+                    output.push(T::from_bits(value));
                 }
             }
             headers::TEN_BITS_THREE_SAMPLES => {
@@ -236,8 +886,8 @@ pub fn decode_i8(iter: &mut HalfIter<'_>, output: &mut Vec<i8>) -> Result<(), Co
                 for i in 0..3 {
                     let delta = ((word >> (shift - bit_width * i)) & 0b11_1111_1111) as i16;
                     let delta = (delta >> 1) ^ -(delta & 1);
-                    value = (value as i16 + delta) as i8;
-                    output.push(value);
+                    value = apply(value, delta);
+                    output.push(T::from_bits(value));
                 }
             }
             headers::SIXTEEN_BITS_TWO_SAMPLES => {
@@ -257,8 +907,8 @@ pub fn decode_i8(iter: &mut HalfIter<'_>, output: &mut Vec<i8>) -> Result<(), Co
                 for i in 0..2 {
                     let delta = (word >> (shift - bit_width * i)) as i16;
                     let delta = (delta >> 1) ^ -(delta & 1);
-                    value = (value as i16 + delta) as i8;
-                    output.push(value);
+                    value = apply(value, delta);
+                    output.push(T::from_bits(value));
                 }
             }
             headers::THIRTY_TWO_BITS_ONE_SAMPLE => {
@@ -278,14 +928,23 @@ pub fn decode_i8(iter: &mut HalfIter<'_>, output: &mut Vec<i8>) -> Result<(), Co
                 for i in 0..1 {
                     let delta = (word >> (shift - bit_width * i)) as i16;
                     let delta = (delta >> 1) ^ -(delta & 1);
-                    value = (value as i16 + delta) as i8;
-                    output.push(value);
+                    value = apply(value, delta);
+                    output.push(T::from_bits(value));
                 }
             }
-            _ => {
-                // Delta-Delta encoding, or not implemented
-                todo!("Implement i8 delta-delta");
+            headers::DELTA_DELTA_ZERO..=headers::DELTA_DELTA_THIRTY_TWO_BITS => {
+                // 1 sample of delta-delta
+                let delta_delta = read_delta_delta(tag, iter)?;
+                if transitions {
+                    // The bits toggled since the previous value
+                    value ^= delta_delta as i8;
+                } else {
+                    prev_delta = prev_delta.wrapping_add(delta_delta as i16);
+                    value = (value as i16).wrapping_add(prev_delta) as i8;
+                }
+                output.push(T::from_bits(value));
             }
+            _ => return Err(CodingError::InvalidBits),
         }
     }
 
@@ -293,29 +952,91 @@ pub fn decode_i8(iter: &mut HalfIter<'_>, output: &mut Vec<i8>) -> Result<(), Co
 }
 
 ///
-/// Decodes 16-bit integers according to the delta encoding scheme.
+/// Decodes 16-bit signed integers according to the delta or delta-delta encoding scheme.
 ///
-/// This function reads the HalfIter in chunks, decodes the chunks according to the delta encoding scheme,
+/// This function reads the HalfIter in chunks, decodes the chunks according to the delta or delta-delta encoding scheme,
 /// and writes the decoded values to the Vec<i16>.
 ///
 pub fn decode_i16(iter: &mut HalfIter<'_>, output: &mut Vec<i16>) -> Result<(), CodingError> {
+    decode_i16_rows(iter, u32::MAX as usize, output)
+}
+
+///
+/// Decodes a column of at most `rows` i16 values, as `decode_i16`.
+///
+pub fn decode_i16_rows(
+    iter: &mut HalfIter<'_>,
+    rows: usize,
+    output: &mut Vec<i16>,
+) -> Result<(), CodingError> {
+    decode_16_bits(iter, rows, output)
+}
+
+///
+/// Decodes 16-bit unsigned integers according to the delta or delta-delta encoding scheme.
+///
+/// This function reads the HalfIter in chunks, decodes the chunks according to the delta or delta-delta encoding scheme,
+/// and writes the decoded values to the Vec<u16>.
+///
+pub fn decode_u16(iter: &mut HalfIter<'_>, output: &mut Vec<u16>) -> Result<(), CodingError> {
+    decode_u16_rows(iter, u32::MAX as usize, output)
+}
+
+///
+/// Decodes a column of at most `rows` u16 values, as `decode_u16`.
+///
+pub fn decode_u16_rows(
+    iter: &mut HalfIter<'_>,
+    rows: usize,
+    output: &mut Vec<u16>,
+) -> Result<(), CodingError> {
+    decode_16_bits(iter, rows, output)
+}
+
+///
+/// Decodes a column of 16-bit values, reconstructing the two's complement bits as an i16.
+/// Runs of zeros and frames of reference of more than the `rows` of the column are invalid.
+///
+fn decode_16_bits<T: ColumnValue<i16>>(
+    iter: &mut HalfIter<'_>,
+    rows: usize,
+    output: &mut Vec<T>,
+) -> Result<(), CodingError> {
+    let start = output.len();
+
     // Check for 0 rows
-    match iter.next() {
+    let (delta_delta, transitions) = match iter.next() {
         None => return Ok(()),
         Some(headers::START_OF_COLUMN) => return Ok(()),
-        Some(headers::FIRST_ROW) => {}
+        Some(headers::FIRST_ROW) => (false, false),
+        Some(headers::DELTA_DELTA_FIRST_ROW) => (true, false),
+        Some(headers::TRANSITIONS_FIRST_ROW) => (false, true),
+        Some(headers::DELTA_DELTA_TRANSITIONS_FIRST_ROW) => (true, true),
+        Some(headers::FRAME_OF_REFERENCE) => {
+            let reader = ReferenceReader::new(iter, 16)?;
+            if reader.remaining() > rows {
+                return Err(CodingError::InvalidBits);
+            }
+            for value in reader {
+                output.push(T::from_bits(value? as i16));
+            }
+            return Ok(());
+        }
         _ => return Err(CodingError::InvalidBits),
-    }
+    };
 
-    // Full 16 bit value
-    let buf = [
-        (iter.next().ok_or(CodingError::NotEnoughBits)? << 4)
-            | iter.next().ok_or(CodingError::NotEnoughBits)?,
-        (iter.next().ok_or(CodingError::NotEnoughBits)? << 4)
-            | iter.next().ok_or(CodingError::NotEnoughBits)?,
-    ];
-    let value = read_full_i16(&buf);
-    output.push(value);
+    // Deltas are added to the previous value, or toggle the bits of the previous value
+    let apply = |value: i16, delta: i32| {
+        if transitions {
+            value ^ delta as i16
+        } else {
+            (value as i32).wrapping_add(delta) as i16
+        }
+    };
+
+    // First value as a signed VLQ
+    let value = read_signed_vlq(iter)? as i16;
+    output.push(T::from_bits(value));
 
     // One row
     match iter.next() {
@@ -325,22 +1046,34 @@ pub fn decode_i16(iter: &mut HalfIter<'_>, output: &mut Vec<i16>) -> Result<(),
         _ => return Err(CodingError::InvalidBits),
     }
 
-    // Delta encoded 32 bit value
-    let buf = [
-        (iter.next().ok_or(CodingError::NotEnoughBits)? << 4)
-            | iter.next().ok_or(CodingError::NotEnoughBits)?,
-        (iter.next().ok_or(CodingError::NotEnoughBits)? << 4)
-            | iter.next().ok_or(CodingError::NotEnoughBits)?,
-        (iter.next().ok_or(CodingError::NotEnoughBits)? << 4)
-            | iter.next().ok_or(CodingError::NotEnoughBits)?,
-        (iter.next().ok_or(CodingError::NotEnoughBits)? << 4)
-            | iter.next().ok_or(CodingError::NotEnoughBits)?,
-    ];
-    let delta = read_full_i32(&buf);
-    let mut value = (value as i32 + delta) as i16;
-    output.push(value);
+    // First delta as a signed VLQ
+    let delta = read_signed_vlq(iter)? as i32;
+    let mut value = apply(value, delta);
+    output.push(T::from_bits(value));
+    let mut prev_delta = delta;
 
     while let Some(tag) = iter.next() {
+        // A run of zero deltas repeats the value, a run of zero delta-deltas repeats the delta
+        if is_zero_run_tag(tag, delta_delta) {
+            let run = read_zero_run(iter, rows.saturating_sub(output.len() - start))?;
+            let delta = if delta_delta && !transitions {
+                prev_delta
+            } else {
+                0
+            };
+            output.reserve(run);
+            for _ in 0..run {
+                value = (value as i32).wrapping_add(delta) as i16;
+                output.push(T::from_bits(value));
+            }
+            continue;
+        }
+
+        // Only words of the encoding marked by the first row may follow
+        if tag != headers::START_OF_COLUMN && is_delta_delta_tag(tag) != delta_delta {
+            return Err(CodingError::InvalidBits);
+        }
+
         match tag {
             headers::START_OF_COLUMN => {
                 // Start of column of next column
@@ -363,8 +1096,8 @@ pub fn decode_i16(iter: &mut HalfIter<'_>, output: &mut Vec<i16>) -> Result<(),
                 for i in 0..10 {
                     let delta = ((word >> (shift - bit_width * i)) & 0b111) as i32;
                     let delta = (delta >> 1) ^ -(delta & 1);
-                    value = (value as i32 + delta) as i16;
-                    output.push(value);
+                    value = apply(value, delta);
+                    output.push(T::from_bits(value));
                 }
             }
             headers::SIX_BITS_FIVE_SAMPLES => {
@@ -384,8 +1117,8 @@ pub fn decode_i16(iter: &mut HalfIter<'_>, output: &mut Vec<i16>) -> Result<(),
                 for i in 0..5 {
                     let delta = ((word >> (shift - bit_width * i)) & 0b11_1111) as i32;
                     let delta = (delta >> 1) ^ -(delta & 1);
-                    value = (value as i32 + delta) as i16;
-                    output.push(value);
+                    value = apply(value, delta);
+                    output.push(T::from_bits(value));
                 }
             }
             headers::EIGHT_BITS_FOUR_SAMPLES => {
@@ -405,8 +1138,8 @@ pub fn decode_i16(iter: &mut HalfIter<'_>, output: &mut Vec<i16>) -> Result<(),
                 for i in 0..4 {
                     let delta = ((word >> (shift - bit_width * i)) & 0b1111_1111) as i32;
                     let delta = (delta >> 1) ^ -(delta & 1);
-                    value = (value as i32 + delta) as i16;
-                    output.push(value);
+                    value = apply(value, delta);
+                    output.push(T::from_bits(value));
                 }
             }
             headers::TEN_BITS_THREE_SAMPLES => {
@@ -426,8 +1159,8 @@ pub fn decode_i16(iter: &mut HalfIter<'_>, output: &mut Vec<i16>) -> Result<(),
                 for i in 0..3 {
                     let delta = ((word >> (shift - bit_width * i)) & 0b11_1111_1111) as i32;
                     let delta = (delta >> 1) ^ -(delta & 1);
-                    value = (value as i32 + delta) as i16;
-                    output.push(value);
+                    value = apply(value, delta);
+                    output.push(T::from_bits(value));
                 }
             }
             headers::SIXTEEN_BITS_TWO_SAMPLES => {
@@ -447,8 +1180,8 @@ pub fn decode_i16(iter: &mut HalfIter<'_>, output: &mut Vec<i16>) -> Result<(),
                 for i in 0..2 {
                     let delta = ((word >> (shift - bit_width * i)) & 0xffff) as i32;
                     let delta = (delta >> 1) ^ -(delta & 1);
-                    value = (value as i32 + delta) as i16;
-                    output.push(value);
+                    value = apply(value, delta);
+                    output.push(T::from_bits(value));
                 }
             }
             headers::THIRTY_TWO_BITS_ONE_SAMPLE => {
@@ -468,14 +1201,23 @@ pub fn decode_i16(iter: &mut HalfIter<'_>, output: &mut Vec<i16>) -> Result<(),
                 for i in 0..1 {
                     let delta = (word >> (shift - bit_width * i)) as i32;
                     let delta = (delta >> 1) ^ -(delta & 1);
-                    value = (value as i32 + delta) as i16;
-                    output.push(value);
+                    value = apply(value, delta);
+                    output.push(T::from_bits(value));
                 }
             }
-            _ => {
-                // Delta-Delta encoding, or not implemented
-                todo!("Implement i16 delta-delta");
+            headers::DELTA_DELTA_ZERO..=headers::DELTA_DELTA_THIRTY_TWO_BITS => {
+                // 1 sample of delta-delta
+                let delta_delta = read_delta_delta(tag, iter)?;
+                if transitions {
+                    // The bits toggled since the previous value
+                    value ^= delta_delta as i16;
+                } else {
+                    prev_delta = prev_delta.wrapping_add(delta_delta as i32);
+                    value = (value as i32).wrapping_add(prev_delta) as i16;
+                }
+                output.push(T::from_bits(value));
             }
+            _ => return Err(CodingError::InvalidBits),
         }
     }
 
@@ -483,33 +1225,91 @@ pub fn decode_i16(iter: &mut HalfIter<'_>, output: &mut Vec<i16>) -> Result<(),
 }
 
 ///
-/// Decodes 32-bit integers according to the delta encoding scheme.
+/// Decodes 32-bit signed integers according to the delta or delta-delta encoding scheme.
 ///
-/// This function reads the HalfIter in chunks, decodes the chunks according to the delta encoding scheme,
+/// This function reads the HalfIter in chunks, decodes the chunks according to the delta or delta-delta encoding scheme,
 /// and writes the decoded values to the Vec<i32>.
 ///
 pub fn decode_i32(iter: &mut HalfIter<'_>, output: &mut Vec<i32>) -> Result<(), CodingError> {
+    decode_i32_rows(iter, u32::MAX as usize, output)
+}
+
+///
+/// Decodes a column of at most `rows` i32 values, as `decode_i32`.
+///
+pub fn decode_i32_rows(
+    iter: &mut HalfIter<'_>,
+    rows: usize,
+    output: &mut Vec<i32>,
+) -> Result<(), CodingError> {
+    decode_32_bits(iter, rows, output)
+}
+
+///
+/// Decodes 32-bit unsigned integers according to the delta or delta-delta encoding scheme.
+///
+/// This function reads the HalfIter in chunks, decodes the chunks according to the delta or delta-delta encoding scheme,
+/// and writes the decoded values to the Vec<u32>.
+///
+pub fn decode_u32(iter: &mut HalfIter<'_>, output: &mut Vec<u32>) -> Result<(), CodingError> {
+    decode_u32_rows(iter, u32::MAX as usize, output)
+}
+
+///
+/// Decodes a column of at most `rows` u32 values, as `decode_u32`.
+///
+pub fn decode_u32_rows(
+    iter: &mut HalfIter<'_>,
+    rows: usize,
+    output: &mut Vec<u32>,
+) -> Result<(), CodingError> {
+    decode_32_bits(iter, rows, output)
+}
+
+///
+/// Decodes a column of 32-bit values, reconstructing the two's complement bits as an i32.
+/// Runs of zeros and frames of reference of more than the `rows` of the column are invalid.
+///
+fn decode_32_bits<T: ColumnValue<i32>>(
+    iter: &mut HalfIter<'_>,
+    rows: usize,
+    output: &mut Vec<T>,
+) -> Result<(), CodingError> {
+    let start = output.len();
+
     // Check for 0 rows
-    match iter.next() {
+    let (delta_delta, transitions) = match iter.next() {
         None => return Ok(()),
         Some(headers::START_OF_COLUMN) => return Ok(()),
-        Some(headers::FIRST_ROW) => {}
+        Some(headers::FIRST_ROW) => (false, false),
+        Some(headers::DELTA_DELTA_FIRST_ROW) => (true, false),
+        Some(headers::TRANSITIONS_FIRST_ROW) => (false, true),
+        Some(headers::DELTA_DELTA_TRANSITIONS_FIRST_ROW) => (true, true),
+        Some(headers::FRAME_OF_REFERENCE) => {
+            let reader = ReferenceReader::new(iter, 32)?;
+            if reader.remaining() > rows {
+                return Err(CodingError::InvalidBits);
+            }
+            for value in reader {
+                output.push(T::from_bits(value? as i32));
+            }
+            return Ok(());
+        }
         _ => return Err(CodingError::InvalidBits),
-    }
+    };
 
-    // Full 32 bit value
-    let buf = [
-        (iter.next().ok_or(CodingError::NotEnoughBits)? << 4)
-            | iter.next().ok_or(CodingError::NotEnoughBits)?,
-        (iter.next().ok_or(CodingError::NotEnoughBits)? << 4)
-            | iter.next().ok_or(CodingError::NotEnoughBits)?,
-        (iter.next().ok_or(CodingError::NotEnoughBits)? << 4)
-            | iter.next().ok_or(CodingError::NotEnoughBits)?,
-        (iter.next().ok_or(CodingError::NotEnoughBits)? << 4)
-            | iter.next().ok_or(CodingError::NotEnoughBits)?,
-    ];
-    let value = read_full_i32(&buf);
-    output.push(value);
+    // Deltas are added to the previous value, or toggle the bits of the previous value
+    let apply = |value: i32, delta: i64| {
+        if transitions {
+            value ^ delta as i32
+        } else {
+            (value as i64).wrapping_add(delta) as i32
+        }
+    };
+
+    // First value as a signed VLQ
+    let value = read_signed_vlq(iter)? as i32;
+    output.push(T::from_bits(value));
 
     // One row
     match iter.next() {
@@ -519,30 +1319,34 @@ pub fn decode_i32(iter: &mut HalfIter<'_>, output: &mut Vec<i32>) -> Result<(),
         _ => return Err(CodingError::InvalidBits),
     }
 
-    // Delta encoded 64 bit value
-    let buf = [
-        (iter.next().ok_or(CodingError::NotEnoughBits)? << 4)
-            | iter.next().ok_or(CodingError::NotEnoughBits)?,
-        (iter.next().ok_or(CodingError::NotEnoughBits)? << 4)
-            | iter.next().ok_or(CodingError::NotEnoughBits)?,
-        (iter.next().ok_or(CodingError::NotEnoughBits)? << 4)
-            | iter.next().ok_or(CodingError::NotEnoughBits)?,
-        (iter.next().ok_or(CodingError::NotEnoughBits)? << 4)
-            | iter.next().ok_or(CodingError::NotEnoughBits)?,
-        (iter.next().ok_or(CodingError::NotEnoughBits)? << 4)
-            | iter.next().ok_or(CodingError::NotEnoughBits)?,
-        (iter.next().ok_or(CodingError::NotEnoughBits)? << 4)
-            | iter.next().ok_or(CodingError::NotEnoughBits)?,
-        (iter.next().ok_or(CodingError::NotEnoughBits)? << 4)
-            | iter.next().ok_or(CodingError::NotEnoughBits)?,
-        (iter.next().ok_or(CodingError::NotEnoughBits)? << 4)
-            | iter.next().ok_or(CodingError::NotEnoughBits)?,
-    ];
-    let delta = read_full_i64(&buf);
-    let mut value = (value as i64 + delta) as i32;
-    output.push(value);
+    // First delta as a signed VLQ
+    let delta = read_signed_vlq(iter)? as i64;
+    let mut value = apply(value, delta);
+    output.push(T::from_bits(value));
+    let mut prev_delta = delta;
 
     while let Some(tag) = iter.next() {
+        // A run of zero deltas repeats the value, a run of zero delta-deltas repeats the delta
+        if is_zero_run_tag(tag, delta_delta) {
+            let run = read_zero_run(iter, rows.saturating_sub(output.len() - start))?;
+            let delta = if delta_delta && !transitions {
+                prev_delta
+            } else {
+                0
+            };
+            output.reserve(run);
+            for _ in 0..run {
+                value = (value as i64).wrapping_add(delta) as i32;
+                output.push(T::from_bits(value));
+            }
+            continue;
+        }
+
+        // Only words of the encoding marked by the first row may follow
+        if tag != headers::START_OF_COLUMN && is_delta_delta_tag(tag) != delta_delta {
+            return Err(CodingError::InvalidBits);
+        }
+
         match tag {
             headers::START_OF_COLUMN => {
                 // Start of column of next column
@@ -565,8 +1369,8 @@ pub fn decode_i32(iter: &mut HalfIter<'_>, output: &mut Vec<i32>) -> Result<(),
                 for i in 0..10 {
                     let delta = ((word >> (shift - bit_width * i)) & 0b111) as i64;
                     let delta = (delta >> 1) ^ -(delta & 1);
-                    value = (value as i64 + delta) as i32;
-                    output.push(value);
+                    value = apply(value, delta);
+                    output.push(T::from_bits(value));
                 }
             }
             headers::SIX_BITS_FIVE_SAMPLES => {
@@ -586,8 +1390,8 @@ pub fn decode_i32(iter: &mut HalfIter<'_>, output: &mut Vec<i32>) -> Result<(),
                 for i in 0..5 {
                     let delta = ((word >> (shift - bit_width * i)) & 0b11_1111) as i64;
                     let delta = (delta >> 1) ^ -(delta & 1);
-                    value = (value as i64 + delta) as i32;
-                    output.push(value);
+                    value = apply(value, delta);
+                    output.push(T::from_bits(value));
                 }
             }
             headers::EIGHT_BITS_FOUR_SAMPLES => {
@@ -607,8 +1411,8 @@ pub fn decode_i32(iter: &mut HalfIter<'_>, output: &mut Vec<i32>) -> Result<(),
                 for i in 0..4 {
                     let delta = ((word >> (shift - bit_width * i)) & 0b1111_1111) as i64;
                     let delta = (delta >> 1) ^ -(delta & 1);
-                    value = (value as i64 + delta) as i32;
-                    output.push(value);
+                    value = apply(value, delta);
+                    output.push(T::from_bits(value));
                 }
             }
 
@@ -629,8 +1433,8 @@ pub fn decode_i32(iter: &mut HalfIter<'_>, output: &mut Vec<i32>) -> Result<(),
                 for i in 0..3 {
                     let delta = ((word >> (shift - bit_width * i)) & 0b11_1111_1111) as i64;
                     let delta = (delta >> 1) ^ -(delta & 1);
-                    value = (value as i64 + delta) as i32;
-                    output.push(value);
+                    value = apply(value, delta);
+                    output.push(T::from_bits(value));
                 }
             }
             headers::SIXTEEN_BITS_TWO_SAMPLES => {
@@ -650,8 +1454,8 @@ pub fn decode_i32(iter: &mut HalfIter<'_>, output: &mut Vec<i32>) -> Result<(),
                 for i in 0..2 {
                     let delta = ((word >> (shift - bit_width * i)) & 0xffff) as i64;
                     let delta = (delta >> 1) ^ -(delta & 1);
-                    value = (value as i64 + delta) as i32;
-                    output.push(value);
+                    value = apply(value, delta);
+                    output.push(T::from_bits(value));
                 }
             }
             headers::THIRTY_TWO_BITS_ONE_SAMPLE => {
@@ -671,8 +1475,8 @@ pub fn decode_i32(iter: &mut HalfIter<'_>, output: &mut Vec<i32>) -> Result<(),
                 for i in 0..1 {
                     let delta = (word >> (shift - bit_width * i)) as i64;
                     let delta = (delta >> 1) ^ -(delta & 1);
-                    value = (value as i64 + delta) as i32;
-                    output.push(value);
+                    value = apply(value, delta);
+                    output.push(T::from_bits(value));
                 }
             }
             headers::SIXTY_FOUR_BITS_ONE_SAMPLE => {
@@ -692,14 +1496,23 @@ pub fn decode_i32(iter: &mut HalfIter<'_>, output: &mut Vec<i32>) -> Result<(),
                 for i in 0..1 {
                     let delta = (word >> (shift - bit_width * i)) as i64;
                     let delta = (delta >> 1) ^ -(delta & 1);
-                    value = (value as i64 + delta) as i32;
-                    output.push(value);
+                    value = apply(value, delta);
+                    output.push(T::from_bits(value));
                 }
             }
-            _ => {
-                // Delta-Delta encoding, or not implemented
-                todo!("Implement i32 delta-delta");
+            headers::DELTA_DELTA_ZERO..=headers::DELTA_DELTA_THIRTY_TWO_BITS => {
+                // 1 sample of delta-delta
+                let delta_delta = read_delta_delta(tag, iter)?;
+                if transitions {
+                    // The bits toggled since the previous value
+                    value ^= delta_delta as i32;
+                } else {
+                    prev_delta = prev_delta.wrapping_add(delta_delta);
+                    value = (value as i64).wrapping_add(prev_delta) as i32;
+                }
+                output.push(T::from_bits(value));
             }
+            _ => return Err(CodingError::InvalidBits),
         }
     }
 
@@ -707,41 +1520,91 @@ pub fn decode_i32(iter: &mut HalfIter<'_>, output: &mut Vec<i32>) -> Result<(),
 }
 
 ///
-/// Decodes 64-bit integers according to the delta encoding scheme.
+/// Decodes 64-bit signed integers according to the delta or delta-delta encoding scheme.
 ///
-/// This function reads the HalfIter in chunks, decodes the chunks according to the delta encoding scheme,
+/// This function reads the HalfIter in chunks, decodes the chunks according to the delta or delta-delta encoding scheme,
 /// and writes the decoded values to the Vec<i64>.
 ///
 pub fn decode_i64(iter: &mut HalfIter<'_>, output: &mut Vec<i64>) -> Result<(), CodingError> {
+    decode_i64_rows(iter, u32::MAX as usize, output)
+}
+
+///
+/// Decodes a column of at most `rows` i64 values, as `decode_i64`.
+///
+pub fn decode_i64_rows(
+    iter: &mut HalfIter<'_>,
+    rows: usize,
+    output: &mut Vec<i64>,
+) -> Result<(), CodingError> {
+    decode_64_bits(iter, rows, output)
+}
+
+///
+/// Decodes 64-bit unsigned integers according to the delta or delta-delta encoding scheme.
+///
+/// This function reads the HalfIter in chunks, decodes the chunks according to the delta or delta-delta encoding scheme,
+/// and writes the decoded values to the Vec<u64>.
+///
+pub fn decode_u64(iter: &mut HalfIter<'_>, output: &mut Vec<u64>) -> Result<(), CodingError> {
+    decode_u64_rows(iter, u32::MAX as usize, output)
+}
+
+///
+/// Decodes a column of at most `rows` u64 values, as `decode_u64`.
+///
+pub fn decode_u64_rows(
+    iter: &mut HalfIter<'_>,
+    rows: usize,
+    output: &mut Vec<u64>,
+) -> Result<(), CodingError> {
+    decode_64_bits(iter, rows, output)
+}
+
+///
+/// Decodes a column of 64-bit values, reconstructing the two's complement bits as an i64.
+/// Runs of zeros and frames of reference of more than the `rows` of the column are invalid.
+///
+fn decode_64_bits<T: ColumnValue<i64>>(
+    iter: &mut HalfIter<'_>,
+    rows: usize,
+    output: &mut Vec<T>,
+) -> Result<(), CodingError> {
+    let start = output.len();
+
     // Check for 0 rows
-    match iter.next() {
+    let (delta_delta, transitions) = match iter.next() {
         None => return Ok(()),
         Some(headers::START_OF_COLUMN) => return Ok(()),
-        Some(headers::FIRST_ROW) => {}
+        Some(headers::FIRST_ROW) => (false, false),
+        Some(headers::DELTA_DELTA_FIRST_ROW) => (true, false),
+        Some(headers::TRANSITIONS_FIRST_ROW) => (false, true),
+        Some(headers::DELTA_DELTA_TRANSITIONS_FIRST_ROW) => (true, true),
+        Some(headers::FRAME_OF_REFERENCE) => {
+            let reader = ReferenceReader::new(iter, 64)?;
+            if reader.remaining() > rows {
+                return Err(CodingError::InvalidBits);
+            }
+            for value in reader {
+                output.push(T::from_bits(value? as i64));
+            }
+            return Ok(());
+        }
         _ => return Err(CodingError::InvalidBits),
-    }
+    };
 
-    // Full 64 bit value
-    let buf = [
-        (iter.next().ok_or(CodingError::NotEnoughBits)? << 4)
-            | iter.next().ok_or(CodingError::NotEnoughBits)?,
-        (iter.next().ok_or(CodingError::NotEnoughBits)? << 4)
-            | iter.next().ok_or(CodingError::NotEnoughBits)?,
-        (iter.next().ok_or(CodingError::NotEnoughBits)? << 4)
-            | iter.next().ok_or(CodingError::NotEnoughBits)?,
-        (iter.next().ok_or(CodingError::NotEnoughBits)? << 4)
-            | iter.next().ok_or(CodingError::NotEnoughBits)?,
-        (iter.next().ok_or(CodingError::NotEnoughBits)? << 4)
-            | iter.next().ok_or(CodingError::NotEnoughBits)?,
-        (iter.next().ok_or(CodingError::NotEnoughBits)? << 4)
-            | iter.next().ok_or(CodingError::NotEnoughBits)?,
-        (iter.next().ok_or(CodingError::NotEnoughBits)? << 4)
-            | iter.next().ok_or(CodingError::NotEnoughBits)?,
-        (iter.next().ok_or(CodingError::NotEnoughBits)? << 4)
-            | iter.next().ok_or(CodingError::NotEnoughBits)?,
-    ];
-    let value = read_full_i64(&buf);
-    output.push(value);
+    // Deltas are added to the previous value, or toggle the bits of the previous value
+    let apply = |value: i64, delta: i128| {
+        if transitions {
+            value ^ delta as i64
+        } else {
+            (value as i128).wrapping_add(delta) as i64
+        }
+    };
+
+    // First value as a signed VLQ
+    let value = read_signed_vlq(iter)? as i64;
+    output.push(T::from_bits(value));
 
     // One row
     match iter.next() {
@@ -751,46 +1614,34 @@ pub fn decode_i64(iter: &mut HalfIter<'_>, output: &mut Vec<i64>) -> Result<(),
         _ => return Err(CodingError::InvalidBits),
     }
 
-    // Delta encoded 128 bit value
-    let buf = [
-        (iter.next().ok_or(CodingError::NotEnoughBits)? << 4)
-            | iter.next().ok_or(CodingError::NotEnoughBits)?,
-        (iter.next().ok_or(CodingError::NotEnoughBits)? << 4)
-            | iter.next().ok_or(CodingError::NotEnoughBits)?,
-        (iter.next().ok_or(CodingError::NotEnoughBits)? << 4)
-            | iter.next().ok_or(CodingError::NotEnoughBits)?,
-        (iter.next().ok_or(CodingError::NotEnoughBits)? << 4)
-            | iter.next().ok_or(CodingError::NotEnoughBits)?,
-        (iter.next().ok_or(CodingError::NotEnoughBits)? << 4)
-            | iter.next().ok_or(CodingError::NotEnoughBits)?,
-        (iter.next().ok_or(CodingError::NotEnoughBits)? << 4)
-            | iter.next().ok_or(CodingError::NotEnoughBits)?,
-        (iter.next().ok_or(CodingError::NotEnoughBits)? << 4)
-            | iter.next().ok_or(CodingError::NotEnoughBits)?,
-        (iter.next().ok_or(CodingError::NotEnoughBits)? << 4)
-            | iter.next().ok_or(CodingError::NotEnoughBits)?,
-        (iter.next().ok_or(CodingError::NotEnoughBits)? << 4)
-            | iter.next().ok_or(CodingError::NotEnoughBits)?,
-        (iter.next().ok_or(CodingError::NotEnoughBits)? << 4)
-            | iter.next().ok_or(CodingError::NotEnoughBits)?,
-        (iter.next().ok_or(CodingError::NotEnoughBits)? << 4)
-            | iter.next().ok_or(CodingError::NotEnoughBits)?,
-        (iter.next().ok_or(CodingError::NotEnoughBits)? << 4)
-            | iter.next().ok_or(CodingError::NotEnoughBits)?,
-        (iter.next().ok_or(CodingError::NotEnoughBits)? << 4)
-            | iter.next().ok_or(CodingError::NotEnoughBits)?,
-        (iter.next().ok_or(CodingError::NotEnoughBits)? << 4)
-            | iter.next().ok_or(CodingError::NotEnoughBits)?,
-        (iter.next().ok_or(CodingError::NotEnoughBits)? << 4)
-            | iter.next().ok_or(CodingError::NotEnoughBits)?,
-        (iter.next().ok_or(CodingError::NotEnoughBits)? << 4)
-            | iter.next().ok_or(CodingError::NotEnoughBits)?,
-    ];
-    let delta = read_full_i128(&buf);
-    let mut value = (value as i128 + delta) as i64;
-    output.push(value);
+    // First delta as a signed VLQ
+    let delta = read_signed_vlq(iter)?;
+    let mut value = apply(value, delta);
+    output.push(T::from_bits(value));
+    let mut prev_delta = delta as i64;
 
     while let Some(tag) = iter.next() {
+        // A run of zero deltas repeats the value, a run of zero delta-deltas repeats the delta
+        if is_zero_run_tag(tag, delta_delta) {
+            let run = read_zero_run(iter, rows.saturating_sub(output.len() - start))?;
+            let delta = if delta_delta && !transitions {
+                prev_delta
+            } else {
+                0
+            };
+            output.reserve(run);
+            for _ in 0..run {
+                value = value.wrapping_add(delta);
+                output.push(T::from_bits(value));
+            }
+            continue;
+        }
+
+        // Only words of the encoding marked by the first row may follow
+        if tag != headers::START_OF_COLUMN && is_delta_delta_tag(tag) != delta_delta {
+            return Err(CodingError::InvalidBits);
+        }
+
         match tag {
             headers::START_OF_COLUMN => {
                 // Start of column of next column
@@ -813,8 +1664,8 @@ pub fn decode_i64(iter: &mut HalfIter<'_>, output: &mut Vec<i64>) -> Result<(),
                 for i in 0..10 {
                     let delta = ((word >> (shift - bit_width * i)) & 0b111) as i128;
                     let delta = (delta >> 1) ^ -(delta & 1);
-                    value = (value as i128 + delta) as i64;
-                    output.push(value);
+                    value = apply(value, delta);
+                    output.push(T::from_bits(value));
                 }
             }
             headers::SIX_BITS_FIVE_SAMPLES => {
@@ -834,8 +1685,8 @@ pub fn decode_i64(iter: &mut HalfIter<'_>, output: &mut Vec<i64>) -> Result<(),
                 for i in 0..5 {
                     let delta = ((word >> (shift - bit_width * i)) & 0b11_1111) as i128;
                     let delta = (delta >> 1) ^ -(delta & 1);
-                    value = (value as i128 + delta) as i64;
-                    output.push(value);
+                    value = apply(value, delta);
+                    output.push(T::from_bits(value));
                 }
             }
             headers::EIGHT_BITS_FOUR_SAMPLES => {
@@ -855,8 +1706,8 @@ pub fn decode_i64(iter: &mut HalfIter<'_>, output: &mut Vec<i64>) -> Result<(),
                 for i in 0..4 {
                     let delta = ((word >> (shift - bit_width * i)) & 0b1111_1111) as i128;
                     let delta = (delta >> 1) ^ -(delta & 1);
-                    value = (value as i128 + delta) as i64;
-                    output.push(value);
+                    value = apply(value, delta);
+                    output.push(T::from_bits(value));
                 }
             }
 
@@ -877,8 +1728,8 @@ pub fn decode_i64(iter: &mut HalfIter<'_>, output: &mut Vec<i64>) -> Result<(),
                 for i in 0..3 {
                     let delta = ((word >> (shift - bit_width * i)) & 0b11_1111_1111) as i128;
                     let delta = (delta >> 1) ^ -(delta & 1);
-                    value = (value as i128 + delta) as i64;
-                    output.push(value);
+                    value = apply(value, delta);
+                    output.push(T::from_bits(value));
                 }
             }
             headers::SIXTEEN_BITS_TWO_SAMPLES => {
@@ -898,8 +1749,8 @@ pub fn decode_i64(iter: &mut HalfIter<'_>, output: &mut Vec<i64>) -> Result<(),
                 for i in 0..2 {
                     let delta = ((word >> (shift - bit_width * i)) & 0xffff) as i128;
                     let delta = (delta >> 1) ^ -(delta & 1);
-                    value = (value as i128 + delta) as i64;
-                    output.push(value);
+                    value = apply(value, delta);
+                    output.push(T::from_bits(value));
                 }
             }
             headers::THIRTY_TWO_BITS_ONE_SAMPLE => {
@@ -919,8 +1770,8 @@ pub fn decode_i64(iter: &mut HalfIter<'_>, output: &mut Vec<i64>) -> Result<(),
                 for i in 0..1 {
                     let delta = (word >> (shift - bit_width * i)) as i128;
                     let delta = (delta >> 1) ^ -(delta & 1);
-                    value = (value as i128 + delta) as i64;
-                    output.push(value);
+                    value = apply(value, delta);
+                    output.push(T::from_bits(value));
                 }
             }
             headers::SIXTY_FOUR_BITS_ONE_SAMPLE => {
@@ -940,16 +1791,438 @@ pub fn decode_i64(iter: &mut HalfIter<'_>, output: &mut Vec<i64>) -> Result<(),
                 for i in 0..1 {
                     let delta = (word >> (shift - bit_width * i)) as i128;
                     let delta = (delta >> 1) ^ -(delta & 1);
-                    value = (value as i128 + delta) as i64;
+                    value = apply(value, delta);
+                    output.push(T::from_bits(value));
+                }
+            }
+            headers::DELTA_DELTA_ZERO..=headers::DELTA_DELTA_THIRTY_TWO_BITS => {
+                // 1 sample of delta-delta
+                let delta_delta = read_delta_delta(tag, iter)?;
+                if transitions {
+                    // The bits toggled since the previous value
+                    value ^= delta_delta;
+                } else {
+                    prev_delta = prev_delta.wrapping_add(delta_delta);
+                    value = value.wrapping_add(prev_delta);
+                }
+                output.push(T::from_bits(value));
+            }
+            _ => return Err(CodingError::InvalidBits),
+        }
+    }
+
+    Ok(())
+}
+
+///
+/// Decodes 128-bit signed integers according to the delta-delta encoding scheme.
+///
+/// This function reads the HalfIter in chunks, decodes the chunks according to the delta-delta encoding scheme,
+/// and writes the decoded values to the Vec<i128>. 128-bit columns are never delta encoded.
+///
+pub fn decode_i128(iter: &mut HalfIter<'_>, output: &mut Vec<i128>) -> Result<(), CodingError> {
+    decode_i128_rows(iter, u32::MAX as usize, output)
+}
+
+///
+/// Decodes a column of at most `rows` i128 values, as `decode_i128`.
+/// Runs of zeros of more than the `rows` of the column are invalid.
+///
+pub fn decode_i128_rows(
+    iter: &mut HalfIter<'_>,
+    rows: usize,
+    output: &mut Vec<i128>,
+) -> Result<(), CodingError> {
+    let start = output.len();
+
+    // Check for 0 rows
+    match iter.next() {
+        None => return Ok(()),
+        Some(headers::START_OF_COLUMN) => return Ok(()),
+        Some(headers::DELTA_DELTA_FIRST_ROW) => {}
+        _ => return Err(CodingError::InvalidBits),
+    }
+
+    // First value as a signed VLQ
+    let mut value = read_signed_vlq(iter)?;
+    output.push(value);
+
+    // One row
+    match iter.next() {
+        None => return Ok(()),
+        Some(headers::START_OF_COLUMN) => return Ok(()),
+        Some(headers::SECOND_ROW) => {}
+        _ => return Err(CodingError::InvalidBits),
+    }
+
+    // First delta as a signed VLQ
+    let mut prev_delta = read_signed_vlq(iter)?;
+    value = value.wrapping_add(prev_delta);
+    output.push(value);
+
+    while let Some(tag) = iter.next() {
+        match tag {
+            headers::START_OF_COLUMN => {
+                // Start of column of next column
+                break;
+            }
+            headers::DELTA_DELTA_ZERO_RUN => {
+                // A run of zero delta-deltas repeats the delta
+                let run = read_zero_run(iter, rows.saturating_sub(output.len() - start))?;
+                output.reserve(run);
+                for _ in 0..run {
+                    value = value.wrapping_add(prev_delta);
                     output.push(value);
                 }
             }
-            _ => {
-                // Delta-Delta encoding, or not implemented
-                todo!("Implement i64 delta-delta");
+            headers::DELTA_DELTA_ZERO..=headers::DELTA_DELTA_THIRTY_TWO_BITS => {
+                // 1 sample of delta-delta
+                let delta_delta = read_wide_delta_delta(tag, iter)?;
+                prev_delta = prev_delta.wrapping_add(delta_delta);
+                value = value.wrapping_add(prev_delta);
+                output.push(value);
             }
+            _ => return Err(CodingError::InvalidBits),
         }
     }
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prelude::halfvec::{HalfVec, HalfWord};
+    use rand::Rng;
+
+    /// Encode a column by hand with only delta-delta words after the second row.
+    macro_rules! delta_delta_column {
+        ($values:expr, $col_ty:ty, $dd_ty:ty, $double_ty:ty) => {{
+            let values: &[$col_ty] = $values;
+            let mut buf = HalfVec::new(values.len());
+            let mut queue: CompressionQueue<2> = CompressionQueue::new();
+            buf.push(HalfWord::Half(headers::START_OF_COLUMN));
+            buf.push(HalfWord::Half(headers::DELTA_DELTA_FIRST_ROW));
+            write_signed_vlq(&mut buf, values[0] as i128);
+            buf.push(HalfWord::Half(headers::SECOND_ROW));
+            write_signed_vlq(
+                &mut buf,
+                (values[1] as $double_ty - values[0] as $double_ty) as i128,
+            );
+            let mut prev_delta = (values[1] as $dd_ty).wrapping_sub(values[0] as $dd_ty);
+            for pair in values[1..].windows(2) {
+                let delta = (pair[1] as $dd_ty).wrapping_sub(pair[0] as $dd_ty);
+                queue.push(delta.wrapping_sub(prev_delta));
+                if queue.is_full() {
+                    queue.emit_delta_delta_bits(&mut buf);
+                }
+                prev_delta = delta;
+            }
+            while !queue.is_empty() || queue.zero_run() > 0 {
+                queue.emit_delta_delta_bits(&mut buf);
+            }
+            let mut bytes = Vec::new();
+            HalfVec::finish(&mut bytes, [&buf].into_iter());
+            bytes
+        }};
+    }
+
+    fn roundtrip_i8(values: &[i8]) -> Vec<i8> {
+        let bytes = delta_delta_column!(values, i8, i16, i16);
+        let mut iter = HalfIter::new(&bytes);
+        assert_eq!(iter.next(), Some(headers::START_OF_COLUMN));
+        let mut output = Vec::new();
+        decode_i8(&mut iter, &mut output).unwrap();
+        output
+    }
+
+    fn roundtrip_i16(values: &[i16]) -> Vec<i16> {
+        let bytes = delta_delta_column!(values, i16, i32, i32);
+        let mut iter = HalfIter::new(&bytes);
+        assert_eq!(iter.next(), Some(headers::START_OF_COLUMN));
+        let mut output = Vec::new();
+        decode_i16(&mut iter, &mut output).unwrap();
+        output
+    }
+
+    fn roundtrip_i32(values: &[i32]) -> Vec<i32> {
+        let bytes = delta_delta_column!(values, i32, i64, i64);
+        let mut iter = HalfIter::new(&bytes);
+        assert_eq!(iter.next(), Some(headers::START_OF_COLUMN));
+        let mut output = Vec::new();
+        decode_i32(&mut iter, &mut output).unwrap();
+        output
+    }
+
+    fn roundtrip_i64(values: &[i64]) -> Vec<i64> {
+        let bytes = delta_delta_column!(values, i64, i64, i128);
+        let mut iter = HalfIter::new(&bytes);
+        assert_eq!(iter.next(), Some(headers::START_OF_COLUMN));
+        let mut output = Vec::new();
+        decode_i64(&mut iter, &mut output).unwrap();
+        output
+    }
+
+    #[test]
+    fn decode_delta_delta_constant_slope() {
+        let values: Vec<i64> = (0..100).map(|i| 1_000_000 + 1000 * i).collect();
+        assert_eq!(roundtrip_i64(&values), values);
+
+        let values: Vec<i8> = (0..100).map(|i| i as i8).collect();
+        assert_eq!(roundtrip_i8(&values), values);
+    }
+
+    #[test]
+    fn decode_delta_delta_every_word_size() {
+        // Delta-deltas of 0, 5, 9, 16, 32, and 64 bits
+        let values: Vec<i64> = [0, 1, 2, 10, 200, 30_000, 2_000_000_000, i64::MAX / 3, 4, 4]
+            .into_iter()
+            .collect();
+        assert_eq!(roundtrip_i64(&values), values);
+
+        let values: Vec<i32> = [0, 1, 2, 10, 200, 30_000, i32::MAX, i32::MIN, 4, 4]
+            .into_iter()
+            .collect();
+        assert_eq!(roundtrip_i32(&values), values);
+    }
+
+    #[test]
+    fn decode_delta_delta_extremes() {
+        let values = [i8::MIN, i8::MAX, i8::MIN, i8::MAX, 0, i8::MIN];
+        assert_eq!(roundtrip_i8(&values), values);
+        let values = [i16::MIN, i16::MAX, i16::MIN, i16::MAX, 0, i16::MIN];
+        assert_eq!(roundtrip_i16(&values), values);
+        let values = [i32::MIN, i32::MAX, i32::MIN, i32::MAX, 0, i32::MIN];
+        assert_eq!(roundtrip_i32(&values), values);
+        let values = [i64::MIN, i64::MAX, i64::MIN, i64::MAX, 0, i64::MIN];
+        assert_eq!(roundtrip_i64(&values), values);
+    }
+
+    #[test]
+    fn decode_delta_delta_random() {
+        let mut rng = rand::thread_rng();
+        for _ in 0..100 {
+            let len = rng.gen_range(2..500);
+            let values: Vec<i8> = (0..len).map(|_| rng.gen()).collect();
+            assert_eq!(roundtrip_i8(&values), values);
+            let values: Vec<i16> = (0..len).map(|_| rng.gen()).collect();
+            assert_eq!(roundtrip_i16(&values), values);
+            let values: Vec<i32> = (0..len).map(|_| rng.gen()).collect();
+            assert_eq!(roundtrip_i32(&values), values);
+            let values: Vec<i64> = (0..len).map(|_| rng.gen()).collect();
+            assert_eq!(roundtrip_i64(&values), values);
+        }
+    }
+
+    #[test]
+    fn signed_vlq() {
+        for value in [
+            0,
+            1,
+            -1,
+            3,
+            -4,
+            1000,
+            i64::MIN as i128,
+            i128::MIN,
+            i128::MAX,
+        ] {
+            let mut buf = HalfVec::new(8);
+            write_signed_vlq(&mut buf, value);
+            let mut bytes = Vec::new();
+            HalfVec::finish(&mut bytes, [&buf].into_iter());
+            assert_eq!(read_signed_vlq(&mut HalfIter::new(&bytes)).unwrap(), value);
+        }
+
+        // Small values take few nibbles whatever the bit-width of the column
+        let nibbles = |value| {
+            let mut buf = HalfVec::new(8);
+            write_signed_vlq(&mut buf, value);
+            buf.len()
+        };
+        assert_eq!(nibbles(0), 1);
+        assert_eq!(nibbles(-4), 1);
+        assert_eq!(nibbles(1000), 4);
+        assert_eq!(nibbles(i128::MIN), 43);
+
+        // A VLQ longer than 128 bits is invalid
+        let bytes = [0xff; 32];
+        let result = read_signed_vlq(&mut HalfIter::new(&bytes));
+        assert!(matches!(result, Err(CodingError::InvalidBits)));
+    }
+
+    /// Decode a column whose first delta and delta-deltas overflow the double-width of the column.
+    macro_rules! overflowing_column {
+        ($decode:ident, $col_ty:ty, $first:expr, $delta:expr) => {{
+            let (first, delta): (i128, i128) = ($first, $delta);
+            let mut buf = HalfVec::new(32);
+            buf.push(HalfWord::Half(headers::DELTA_DELTA_FIRST_ROW));
+            write_signed_vlq(&mut buf, first);
+            buf.push(HalfWord::Half(headers::SECOND_ROW));
+            write_signed_vlq(&mut buf, delta);
+            // A delta-delta of 1, then a run of two zero delta-deltas
+            buf.push(HalfWord::Half(headers::DELTA_DELTA_THIRTY_TWO_BITS));
+            buf.push(HalfWord::Full(2));
+            buf.push(HalfWord::Half(headers::DELTA_DELTA_ZERO_RUN));
+            buf.push(HalfWord::Half(2));
+            let mut bytes = Vec::new();
+            HalfVec::finish(&mut bytes, [&buf].into_iter());
+
+            // The values wrap at the bit-width of the column
+            let mut output: Vec<$col_ty> = Vec::new();
+            $decode(&mut HalfIter::new(&bytes), 5, &mut output).unwrap();
+            let expected = (0..5)
+                .map(|k: i128| {
+                    first
+                        .wrapping_add(k.wrapping_mul(delta))
+                        .wrapping_add((k - 1).max(0)) as $col_ty
+                })
+                .collect::<Vec<_>>();
+            assert_eq!(output, expected);
+        }};
+    }
+
+    #[test]
+    fn decode_overflowing_deltas() {
+        overflowing_column!(decode_i8_rows, i8, i8::MAX as i128, i16::MAX as i128);
+        overflowing_column!(decode_u8_rows, u8, i8::MIN as i128, i16::MIN as i128 + 1);
+        overflowing_column!(decode_i16_rows, i16, i16::MAX as i128, i32::MAX as i128);
+        overflowing_column!(decode_u16_rows, u16, i16::MIN as i128, i32::MIN as i128 + 1);
+        overflowing_column!(decode_i32_rows, i32, i32::MAX as i128, i64::MAX as i128);
+        overflowing_column!(decode_u32_rows, u32, i32::MIN as i128, i64::MIN as i128 + 1);
+        overflowing_column!(decode_i64_rows, i64, i64::MAX as i128, i128::MAX);
+    }
+
+    #[test]
+    fn decode_unmarked_delta_delta() {
+        let mut buf = HalfVec::new(8);
+        buf.push(HalfWord::Half(headers::FIRST_ROW));
+        write_signed_vlq(&mut buf, 1);
+        buf.push(HalfWord::Half(headers::SECOND_ROW));
+        write_signed_vlq(&mut buf, 1);
+        // The zero delta-delta tag marks a run of zero deltas in delta encoding
+        buf.push(HalfWord::Half(headers::DELTA_DELTA_THIRTY_TWO_BITS));
+        buf.push(HalfWord::Full(0));
+        let mut bytes = Vec::new();
+        HalfVec::finish(&mut bytes, [&buf].into_iter());
+
+        let mut output = Vec::new();
+        let result = decode_i8_rows(&mut HalfIter::new(&bytes), 3, &mut output);
+        assert!(matches!(result, Err(CodingError::InvalidBits)));
+    }
+
+    #[test]
+    fn decode_transitions() {
+        let mut buf = HalfVec::new(8);
+        buf.push(HalfWord::Half(headers::TRANSITIONS_FIRST_ROW));
+        write_signed_vlq(&mut buf, 0b0101);
+        buf.push(HalfWord::Half(headers::SECOND_ROW));
+        write_signed_vlq(&mut buf, 0b0011);
+        let mut bytes = Vec::new();
+        HalfVec::finish(&mut bytes, [&buf].into_iter());
+
+        // The second value toggles the low bits of the first
+        let mut output = Vec::new();
+        decode_u8_rows(&mut HalfIter::new(&bytes), 2, &mut output).unwrap();
+        assert_eq!(output, vec![0b0101, 0b0110]);
+    }
+
+    #[test]
+    fn decode_invalid_bool() {
+        let mut buf = HalfVec::new(8);
+        buf.push(HalfWord::Half(headers::TRANSITIONS_FIRST_ROW));
+        write_signed_vlq(&mut buf, 1);
+        buf.push(HalfWord::Half(headers::SECOND_ROW));
+        write_signed_vlq(&mut buf, 0b0011);
+        let mut bytes = Vec::new();
+        HalfVec::finish(&mut bytes, [&buf].into_iter());
+
+        let mut output = Vec::new();
+        let result = decode_bool_rows(&mut HalfIter::new(&bytes), 2, &mut output);
+        assert!(matches!(result, Err(CodingError::InvalidBits)));
+    }
+
+    #[test]
+    fn decode_invalid_tag() {
+        let mut buf = HalfVec::new(8);
+        buf.push(HalfWord::Half(headers::FIRST_ROW));
+        write_signed_vlq(&mut buf, 1);
+        buf.push(HalfWord::Half(headers::SECOND_ROW));
+        write_signed_vlq(&mut buf, 1);
+        buf.push(HalfWord::Half(headers::SIXTY_FOUR_BITS_ONE_SAMPLE));
+        let mut bytes = Vec::new();
+        HalfVec::finish(&mut bytes, [&buf].into_iter());
+
+        let mut output = Vec::new();
+        let result = decode_i8_rows(&mut HalfIter::new(&bytes), 3, &mut output);
+        assert!(matches!(result, Err(CodingError::InvalidBits)));
+    }
+
+    fn xor_column(bits: &[u64], width: usize) -> Vec<u8> {
+        let mut buf = HalfVec::new(bits.len());
+        let mut encoder = XorEncoder::new(width);
+        encoder.emit_first_bits(bits[0], &mut buf);
+        for bits in &bits[1..] {
+            encoder.emit_bits(*bits, &mut buf);
+        }
+        let mut bytes = Vec::new();
+        HalfVec::finish(&mut bytes, [&buf].into_iter());
+        bytes
+    }
+
+    #[test]
+    fn decode_xor_special_values() {
+        let values = [
+            0.0,
+            -0.0,
+            f32::NAN,
+            f32::INFINITY,
+            f32::NEG_INFINITY,
+            f32::MIN_POSITIVE / 2.0,
+            f32::MAX,
+            f32::MIN,
+            1.5,
+            1.5,
+        ];
+        let bits: Vec<u64> = values.iter().map(|v| v.to_bits() as u64).collect();
+        let mut output = Vec::new();
+        decode_f32_rows(
+            &mut HalfIter::new(&xor_column(&bits, 32)),
+            bits.len(),
+            &mut output,
+        )
+        .unwrap();
+        let output: Vec<u32> = output.iter().map(|v| v.to_bits()).collect();
+        let values: Vec<u32> = values.iter().map(|v| v.to_bits()).collect();
+        assert_eq!(output, values);
+    }
+
+    #[test]
+    fn decode_xor_random() {
+        let mut rng = rand::thread_rng();
+        for _ in 0..100 {
+            let len = rng.gen_range(1..500);
+            let mut value: f64 = rng.gen_range(-1000.0..1000.0);
+            let values: Vec<f64> = (0..len)
+                .map(|_| {
+                    if rng.gen_bool(0.1) {
+                        value = f64::from_bits(rng.gen());
+                    } else if rng.gen_bool(0.5) {
+                        value += rng.gen_range(-1.0..1.0);
+                    }
+                    value
+                })
+                .collect();
+            let bits: Vec<u64> = values.iter().map(|v| v.to_bits()).collect();
+            let mut output = Vec::new();
+            decode_f64_rows(
+                &mut HalfIter::new(&xor_column(&bits, 64)),
+                bits.len(),
+                &mut output,
+            )
+            .unwrap();
+            let output: Vec<u64> = output.iter().map(|v| v.to_bits()).collect();
+            assert_eq!(output, bits);
+        }
+    }
+}
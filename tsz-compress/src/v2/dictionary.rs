@@ -0,0 +1,368 @@
+use crate::prelude::*;
+use crate::v2::consts::headers;
+use crate::v2::decode::{read_value_bits, BitUnpacker};
+use crate::v2::encode::{write_value_bits, BitPacker};
+use crate::v2::halfvec::{HalfVec, HalfWord};
+use crate::v2::validity::{read_run, run_nibbles, write_run};
+use alloc::vec::Vec;
+
+/// The most distinct values of a dictionary column in a frame, so that the number of entries fits in a nibble.
+pub const MAX_DICTIONARY_LEN: usize = 16;
+
+///
+/// The dictionary of a `#[tsz(dictionary)]` column: the distinct values of the frame
+/// in order of first appearance, and the index of each value into them.
+///
+/// After the start of column tag, the dictionary tag is followed by the number of entries less one
+/// in a nibble, the entries in the bit-width of the column, the number of values as a nibble-aligned VLQ,
+/// and the indices packed into nibbles with the fewest bits that index every entry.
+///
+/// A frame with more distinct values than the limit abandons the dictionary for the deltas of the column.
+///
+#[derive(Debug, Clone)]
+pub struct DictionaryColumn {
+    buffer: HalfVec,
+    entries: [i128; MAX_DICTIONARY_LEN],
+    len: usize,
+    indices: Vec<u8>,
+    count: usize,
+    bits: usize,
+    overflowed: bool,
+}
+
+impl DictionaryColumn {
+    ///
+    /// Creates an empty dictionary for a column of the bit-width.
+    ///
+    pub fn new(bits: usize) -> Self {
+        let mut buffer = HalfVec::new(16);
+        buffer.push(HalfWord::Half(headers::START_OF_COLUMN));
+        Self {
+            buffer,
+            entries: [0; MAX_DICTIONARY_LEN],
+            len: 0,
+            indices: Vec::new(),
+            count: 0,
+            bits,
+            overflowed: false,
+        }
+    }
+
+    ///
+    /// Records the next value of the column.
+    ///
+    #[inline(always)]
+    pub fn push(&mut self, value: i128) {
+        if self.overflowed {
+            return;
+        }
+        let index = match self.entries[..self.len]
+            .iter()
+            .position(|entry| *entry == value)
+        {
+            Some(index) => index,
+            None if self.len < MAX_DICTIONARY_LEN => {
+                self.entries[self.len] = value;
+                self.len += 1;
+                self.len - 1
+            }
+            None => {
+                // Too many distinct values, so the column falls back to deltas
+                self.overflowed = true;
+                self.indices.clear();
+                return;
+            }
+        };
+
+        // Hold the indices two per byte until the number of entries is known
+        if self.count.is_multiple_of(2) {
+            self.indices.push((index as u8) << 4);
+        } else if let Some(byte) = self.indices.last_mut() {
+            *byte |= index as u8;
+        }
+        self.count += 1;
+    }
+
+    ///
+    /// The number of nibbles of the column once finished, including the start of column tag,
+    /// or `None` if the column cannot use the dictionary.
+    ///
+    pub fn finished_nibbles(&self) -> Option<usize> {
+        if self.overflowed || self.count == 0 {
+            return None;
+        }
+        let entry_nibbles = self.len * self.bits / 4;
+        let index_nibbles = (self.count * index_bits(self.len)).div_ceil(4);
+        Some(3 + entry_nibbles + run_nibbles(self.count as u64) + index_nibbles)
+    }
+
+    ///
+    /// Whether the dictionary is shorter than the deltas of the column, which are preferred on ties.
+    ///
+    pub fn is_shorter(&self, nibbles: usize) -> bool {
+        self.finished_nibbles()
+            .is_some_and(|dictionary_nibbles| dictionary_nibbles < nibbles)
+    }
+
+    ///
+    /// Writes the entries and packs the indices, finishing the column.
+    ///
+    pub fn flush(&mut self) {
+        if self.finished_nibbles().is_none() {
+            return;
+        }
+        self.buffer.push(HalfWord::Half(headers::DICTIONARY));
+        self.buffer.push(HalfWord::Half((self.len - 1) as u8));
+        for entry in &self.entries[..self.len] {
+            write_value_bits(&mut self.buffer, *entry, self.bits);
+        }
+        write_run(&mut self.buffer, self.count as u64);
+
+        // Pack the indices most significant bit first, padding the last nibble with zeros
+        let bits = index_bits(self.len);
+        let mut packer = BitPacker::default();
+        for i in 0..self.count {
+            let index = (self.indices[i / 2] >> (4 * (1 - i % 2))) & 0xf;
+            packer.push(&mut self.buffer, index as u64, bits);
+        }
+        packer.finish(&mut self.buffer);
+    }
+
+    ///
+    /// The nibbles of the column, including the start of column tag.
+    ///
+    pub fn buffer(&self) -> &HalfVec {
+        &self.buffer
+    }
+
+    ///
+    /// Clears the dictionary for the next frame.
+    ///
+    pub fn clear(&mut self) {
+        self.buffer.clear();
+        self.buffer.push(HalfWord::Half(headers::START_OF_COLUMN));
+        self.len = 0;
+        self.indices.clear();
+        self.count = 0;
+        self.overflowed = false;
+    }
+}
+
+/// The fewest bits that index every entry of a dictionary.
+fn index_bits(len: usize) -> usize {
+    match len {
+        0 | 1 => 0,
+        2 => 1,
+        3..=4 => 2,
+        5..=8 => 3,
+        _ => 4,
+    }
+}
+
+///
+/// A reader of the values of a dictionary column, from the dictionary and the packed indices.
+///
+#[derive(Debug, Clone)]
+pub(crate) struct DictionaryReader<'a> {
+    indices: BitUnpacker<'a>,
+    entries: [i128; MAX_DICTIONARY_LEN],
+    len: usize,
+    bits: usize,
+    remaining: usize,
+}
+
+impl<'a> DictionaryReader<'a> {
+    ///
+    /// Reads the dictionary of a column of the bit-width after the dictionary tag.
+    ///
+    /// The iterator is advanced past the column to the start of the next column.
+    ///
+    pub(crate) fn new(iter: &mut HalfIter<'a>, bits: usize) -> Result<Self, CodingError> {
+        let len = iter.next().ok_or(CodingError::NotEnoughBits)? as usize + 1;
+        let mut entries = [0; MAX_DICTIONARY_LEN];
+        for entry in &mut entries[..len] {
+            *entry = read_value_bits(iter, bits)?;
+        }
+        let count = read_run(iter)?;
+        if count == 0 || count > u32::MAX as u64 {
+            return Err(CodingError::InvalidBits);
+        }
+
+        // Skip the packed indices
+        let reader = Self {
+            indices: BitUnpacker::new(iter.clone()),
+            entries,
+            len,
+            bits: index_bits(len),
+            remaining: count as usize,
+        };
+        let index_nibbles = (count * reader.bits as u64).div_ceil(4);
+        if index_nibbles > 0 {
+            let skip =
+                usize::try_from(index_nibbles - 1).map_err(|_| CodingError::NotEnoughBits)?;
+            iter.nth(skip).ok_or(CodingError::NotEnoughBits)?;
+        }
+        match iter.next() {
+            None | Some(headers::START_OF_COLUMN) => Ok(reader),
+            Some(_) => Err(CodingError::InvalidColumnTag),
+        }
+    }
+
+    /// The number of values that remain to be read.
+    pub(crate) fn remaining(&self) -> usize {
+        self.remaining
+    }
+
+    /// Skips the next `n` values.
+    pub(crate) fn skip_rows(&mut self, n: usize) -> Result<(), CodingError> {
+        self.remaining = self
+            .remaining
+            .checked_sub(n)
+            .ok_or(CodingError::NotEnoughBits)?;
+        let bits = n.checked_mul(self.bits).ok_or(CodingError::NotEnoughBits)?;
+        self.indices.skip(bits)
+    }
+}
+
+impl Iterator for DictionaryReader<'_> {
+    type Item = Result<i128, CodingError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.remaining == 0 {
+            return None;
+        }
+        self.remaining -= 1;
+
+        // Take the next index from the nibbles
+        let index = match self.indices.read(self.bits) {
+            Ok(index) => index as usize,
+            Err(err) => {
+                self.remaining = 0;
+                return Some(Err(err));
+            }
+        };
+
+        if index >= self.len {
+            self.remaining = 0;
+            return Some(Err(CodingError::InvalidBits));
+        }
+        Some(Ok(self.entries[index]))
+    }
+}
+
+///
+/// Decodes a `#[tsz(dictionary)]` column after the start of column tag,
+/// or the deltas of the column with `decode` when the dictionary was abandoned.
+///
+pub fn decode_dictionary<T: ColumnValue<i128>>(
+    iter: &mut HalfIter<'_>,
+    rows: usize,
+    output: &mut Vec<T>,
+    decode: ColumnDecoder<T>,
+) -> Result<(), CodingError> {
+    let mut peek = iter.clone();
+    if peek.next() != Some(headers::DICTIONARY) {
+        return decode(iter, rows, output);
+    }
+    let reader = DictionaryReader::new(&mut peek, core::mem::size_of::<T>() * 8)?;
+    if reader.remaining() > rows {
+        return Err(CodingError::InvalidBits);
+    }
+    *iter = peek;
+
+    for value in reader {
+        output.push(T::from_bits(value?));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn finish(dictionary: &mut DictionaryColumn) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        let nibbles = dictionary.finished_nibbles().unwrap();
+        dictionary.flush();
+        assert_eq!(dictionary.buffer().len(), nibbles);
+        HalfVec::finish(&mut bytes, [dictionary.buffer()].into_iter());
+        bytes
+    }
+
+    #[test]
+    fn index_widths() {
+        for len in 1..=MAX_DICTIONARY_LEN {
+            let values = (0..101)
+                .map(|i| (i * 7 % len) as i128 * -1000)
+                .collect::<Vec<_>>();
+            let mut dictionary = DictionaryColumn::new(32);
+            for value in &values {
+                dictionary.push(*value);
+            }
+            let bytes = finish(&mut dictionary);
+
+            let mut iter = HalfIter::new(&bytes);
+            assert_eq!(iter.next(), Some(headers::START_OF_COLUMN));
+            let mut output: Vec<i32> = Vec::new();
+            decode_dictionary(&mut iter, values.len(), &mut output, decode_i32_rows).unwrap();
+            let expected = values.iter().map(|value| *value as i32).collect::<Vec<_>>();
+            assert_eq!(output, expected);
+        }
+    }
+
+    #[test]
+    fn overflow() {
+        let mut dictionary = DictionaryColumn::new(8);
+        for value in 0..MAX_DICTIONARY_LEN as i128 {
+            dictionary.push(value);
+        }
+        assert!(dictionary.finished_nibbles().is_some());
+        dictionary.push(-1);
+        assert_eq!(dictionary.finished_nibbles(), None);
+        assert!(!dictionary.is_shorter(usize::MAX));
+
+        // A cleared dictionary is usable again
+        dictionary.clear();
+        dictionary.push(-1);
+        assert!(dictionary.finished_nibbles().is_some());
+    }
+
+    #[test]
+    fn invalid_index() {
+        // Three entries indexed with two bits, where the last index is out of range
+        let mut dictionary = DictionaryColumn::new(8);
+        for value in [1, 2, 3, 1] {
+            dictionary.push(value);
+        }
+        let mut bytes = finish(&mut dictionary);
+        *bytes.last_mut().unwrap() |= 0b11;
+
+        let mut iter = HalfIter::new(&bytes);
+        assert_eq!(iter.next(), Some(headers::START_OF_COLUMN));
+        let mut output: Vec<u8> = Vec::new();
+        assert!(matches!(
+            decode_dictionary(&mut iter, 4, &mut output, decode_u8_rows),
+            Err(CodingError::InvalidBits)
+        ));
+    }
+
+    #[test]
+    fn too_many_values() {
+        // A dictionary of one entry packs its indices in no bits
+        let mut dictionary = DictionaryColumn::new(8);
+        for _ in 0..10 {
+            dictionary.push(7);
+        }
+        let bytes = finish(&mut dictionary);
+
+        // More values than the rows of the column are invalid
+        let mut iter = HalfIter::new(&bytes);
+        assert_eq!(iter.next(), Some(headers::START_OF_COLUMN));
+        let mut output: Vec<u8> = Vec::new();
+        assert!(matches!(
+            decode_dictionary(&mut iter, 9, &mut output, decode_u8_rows),
+            Err(CodingError::InvalidBits)
+        ));
+        assert!(output.is_empty());
+    }
+}
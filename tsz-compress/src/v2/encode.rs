@@ -2,9 +2,10 @@ use core::fmt::Binary;
 use num_traits::PrimInt;
 
 use crate::prelude::*;
-use crate::v2::consts::headers;
+use crate::v2::consts::{self, headers};
 
 use super::halfvec::{HalfVec, HalfWord};
+use super::validity::{run_nibbles, write_run};
 
 ///
 /// A trait for types that can be represented as bits.
@@ -18,13 +19,13 @@ pub trait Bits: PrimInt + Binary {
 
     /// Language limitations prevent us from writing simple math expressions
     /// ((self << 1) ^ self >> (Self::BITS - 1)) as u32
-    fn zigzag(self) -> usize;
+    fn zigzag(self) -> u64;
 
     /// Return the zigzag encoding and number of bits required to represent the value
     #[inline(always)]
-    fn zigzag_bits(self) -> (usize, usize) {
+    fn zigzag_bits(self) -> (u64, usize) {
         let zbits = self.zigzag();
-        (zbits, (usize::BITS - zbits.leading_zeros()) as usize)
+        (zbits, (u64::BITS - zbits.leading_zeros()) as usize)
     }
 }
 
@@ -32,8 +33,8 @@ impl Bits for i8 {
     const BITS: usize = 8;
 
     #[inline(always)]
-    fn zigzag(self) -> usize {
-        ((self << 1) ^ self >> (Self::BITS - 1)) as u8 as usize
+    fn zigzag(self) -> u64 {
+        ((self << 1) ^ self >> (Self::BITS - 1)) as u8 as u64
     }
 }
 
@@ -41,8 +42,8 @@ impl Bits for i16 {
     const BITS: usize = 16;
 
     #[inline(always)]
-    fn zigzag(self) -> usize {
-        ((self << 1) ^ self >> (Self::BITS - 1)) as u16 as usize
+    fn zigzag(self) -> u64 {
+        ((self << 1) ^ self >> (Self::BITS - 1)) as u16 as u64
     }
 }
 
@@ -50,18 +51,17 @@ impl Bits for i32 {
     const BITS: usize = 32;
 
     #[inline(always)]
-    fn zigzag(self) -> usize {
-        ((self << 1) ^ self >> (Self::BITS - 1)) as u32 as usize
+    fn zigzag(self) -> u64 {
+        ((self << 1) ^ self >> (Self::BITS - 1)) as u32 as u64
     }
 }
 
-#[cfg(target_pointer_width = "64")]
 impl Bits for i64 {
     const BITS: usize = 64;
 
     #[inline(always)]
-    fn zigzag(self) -> usize {
-        ((self << 1) ^ self >> (Self::BITS - 1)) as u64 as usize
+    fn zigzag(self) -> u64 {
+        ((self << 1) ^ self >> (Self::BITS - 1)) as u64
     }
 }
 
@@ -70,7 +70,7 @@ fn push_three_bits(q: &mut CompressionQueue<10>, buf: &mut HalfVec) {
     const N: usize = 10;
     const N1: usize = N - 1;
     buf.push(HalfWord::Half(headers::THREE_BITS_TEN_SAMPLES));
-    let mut word: usize = 0;
+    let mut word: u64 = 0;
     let values = q.pop_n::<N>();
     for value in values.iter().take(N1) {
         word |= value;
@@ -85,7 +85,7 @@ fn push_six_bits(q: &mut CompressionQueue<10>, buf: &mut HalfVec) {
     const N: usize = 5;
     const N1: usize = N - 1;
     buf.push(HalfWord::Half(headers::SIX_BITS_FIVE_SAMPLES));
-    let mut word: usize = 0;
+    let mut word: u64 = 0;
     let values = q.pop_n::<N>();
     for value in values.iter().take(N1) {
         word |= value;
@@ -100,7 +100,7 @@ fn push_eight_bits(q: &mut CompressionQueue<10>, buf: &mut HalfVec) {
     const N: usize = 4;
     const N1: usize = N - 1;
     buf.push(HalfWord::Half(headers::EIGHT_BITS_FOUR_SAMPLES));
-    let mut word: usize = 0;
+    let mut word: u64 = 0;
     let values = q.pop_n::<N>();
     for value in values.iter().take(N1) {
         word |= value;
@@ -115,7 +115,7 @@ fn push_ten_bits(q: &mut CompressionQueue<10>, buf: &mut HalfVec) {
     const N: usize = 3;
     const N1: usize = N - 1;
     buf.push(HalfWord::Half(headers::TEN_BITS_THREE_SAMPLES));
-    let mut word: usize = 0b00 << 10;
+    let mut word: u64 = 0b00 << 10;
     let values = q.pop_n::<N>();
     for value in values.iter().take(N1) {
         word |= value;
@@ -130,7 +130,7 @@ fn push_sixteen_bits(q: &mut CompressionQueue<10>, buf: &mut HalfVec) {
     const N: usize = 2;
     const N1: usize = N - 1;
     buf.push(HalfWord::Half(headers::SIXTEEN_BITS_TWO_SAMPLES));
-    let mut word: usize = 0b00 << 10;
+    let mut word: u64 = 0b00 << 10;
     let values = q.pop_n::<N>();
     for value in values.iter().take(N1) {
         word |= value;
@@ -143,7 +143,7 @@ fn push_sixteen_bits(q: &mut CompressionQueue<10>, buf: &mut HalfVec) {
 #[inline(always)]
 unsafe fn push_32_or_64_bits(q: &mut CompressionQueue<10>, buf: &mut HalfVec) {
     let value = q.pop().unwrap_unchecked();
-    if value <= u32::MAX as usize {
+    if value <= u32::MAX as u64 {
         buf.push(HalfWord::Half(headers::THIRTY_TWO_BITS_ONE_SAMPLE));
     } else {
         buf.push(HalfWord::Half(headers::SIXTY_FOUR_BITS_ONE_SAMPLE));
@@ -152,6 +152,77 @@ unsafe fn push_32_or_64_bits(q: &mut CompressionQueue<10>, buf: &mut HalfVec) {
     buf.push(HalfWord::Full(value as u32));
 }
 
+///
+/// Writes the pending run of zeros, extended by the zeros at the front of the queue,
+/// returning the number of values popped from the queue.
+///
+#[inline(always)]
+fn emit_zero_run<const N: usize>(q: &mut CompressionQueue<N>, tag: u8, buf: &mut HalfVec) -> usize {
+    if q.zero_run() == 0 {
+        return 0;
+    }
+    let zeros = q.zero_prefix_len();
+    q.pop_zeros(zeros);
+    buf.push(HalfWord::Half(tag));
+    write_run(buf, q.take_zero_run());
+    zeros
+}
+
+///
+/// Checks which cases of delta compression can emit the front of a queue that may not be full.
+///
+#[inline(always)]
+fn flush_delta_fits(q: &CompressionQueue<10>) -> [bool; 5] {
+    let mut fits = [true; 5];
+
+    // Can not emit with case v of delta compression if number of samples < 10
+    if q.len() < 10 {
+        fits[0] = false;
+    }
+
+    // Can not emit with case iv of delta compression if number of samples < 5.
+    if q.len() < 5 {
+        fits[1] = false;
+    }
+
+    // Can not emit with case iii of delta compression if number of samples < 4
+    if q.len() < 4 {
+        fits[2] = false;
+    }
+
+    // Can not emit with case ii of delta compression if number of samples < 3
+    if q.len() < 3 {
+        fits[3] = false;
+    }
+
+    // Can not emit with case ii of delta compression if number of samples < 2
+    if q.len() < 2 {
+        fits[4] = false;
+    }
+
+    // Check if the values will fit in the cases
+    let values = q.peak_bitcounts::<10>();
+    for (index, bits_required) in values.into_iter().enumerate() {
+        if (index < 2) & (bits_required > 16) {
+            fits[4] = false;
+        }
+        if (index < 3) & (bits_required > 10) {
+            fits[3] = false;
+        }
+        if (index < 4) & (bits_required > 8) {
+            fits[2] = false;
+        }
+        if (index < 5) & (bits_required > 6) {
+            fits[1] = false;
+        }
+        if (index < 10) & (bits_required > 3) {
+            fits[0] = false;
+        }
+    }
+
+    fits
+}
+
 ///
 /// A trait that emits bits according to the most efficient case of Delta Compression.
 ///
@@ -163,11 +234,24 @@ pub trait EmitDeltaBits {
     /// Returns the number of elements popped from the queue.
     fn emit_delta_bits(&mut self, out: &mut HalfVec) -> usize;
     fn flush_delta_bits(&mut self, out: &mut HalfVec) -> usize;
+
+    /// The number of nibbles that flushing the queue would emit, without modifying the queue.
+    fn flushed_delta_nibbles(&self) -> usize;
 }
 
 impl EmitDeltaBits for CompressionQueue<10> {
     #[inline(always)]
     fn emit_delta_bits(&mut self, out: &mut HalfVec) -> usize {
+        // Zero deltas accumulate into a run while the queue is full of zeros
+        if self.zero_prefix_len() >= 10 {
+            self.pop_zeros(10);
+            return 10;
+        }
+        let popped = emit_zero_run(self, headers::ZERO_RUN, out);
+        if popped > 0 {
+            return popped;
+        }
+
         let mut fits = [true; 5];
 
         // Check if the values will fit in the cases
@@ -216,57 +300,13 @@ impl EmitDeltaBits for CompressionQueue<10> {
 
     #[inline(always)]
     fn flush_delta_bits(&mut self, out: &mut HalfVec) -> usize {
-        let mut fits = [true; 5];
-
-        // Can not emit with any case of delta compression if queue is empty
-        if self.is_empty() {
-            return 0;
-        }
-
-        // Can not emit with case v of delta compression if number of samples < 10
-        if self.len() < 10 {
-            fits[0] = false;
-        }
-
-        // Can not emit with case iv of delta compression if number of samples < 5.
-        if self.len() < 5 {
-            fits[1] = false;
-        }
-
-        // Can not emit with case iii of delta compression if number of samples < 4
-        if self.len() < 4 {
-            fits[2] = false;
-        }
-
-        // Can not emit with case ii of delta compression if number of samples < 3
-        if self.len() < 3 {
-            fits[3] = false;
+        // Write any pending run of zeros, then can not emit with any case of delta compression if queue is empty
+        let popped = emit_zero_run(self, headers::ZERO_RUN, out);
+        if popped > 0 || self.is_empty() {
+            return popped;
         }
 
-        // Can not emit with case ii of delta compression if number of samples < 2
-        if self.len() < 2 {
-            fits[4] = false;
-        }
-
-        // Check if the values will fit in the cases
-        let values = self.peak_bitcounts::<10>();
-        for (index, bits_required) in values.into_iter().enumerate() {
-            if (index < 2) & (bits_required > 16) {
-                fits[4] = false;
-            }
-            if (index < 3) & (bits_required > 10) {
-                fits[3] = false;
-            }
-            if (index < 4) & (bits_required > 8) {
-                fits[2] = false;
-            }
-            if (index < 5) & (bits_required > 6) {
-                fits[1] = false;
-            }
-            if (index < 10) & (bits_required > 3) {
-                fits[0] = false;
-            }
-        }
+        let fits = flush_delta_fits(self);
 
         // Emit according to priority of cases
         if fits[0] {
@@ -291,6 +331,36 @@ impl EmitDeltaBits for CompressionQueue<10> {
             1
         }
     }
+
+    fn flushed_delta_nibbles(&self) -> usize {
+        let mut queue = *self;
+        let mut nibbles = 0;
+        if queue.zero_run() > 0 {
+            // A tag and the run length
+            queue.pop_zeros(queue.zero_prefix_len());
+            nibbles += 1 + run_nibbles(queue.take_zero_run());
+        }
+        while !queue.is_empty() {
+            // A tag and a 32-bit word, except for the 64-bit case
+            let samples = match flush_delta_fits(&queue).iter().position(|fits| *fits) {
+                Some(0) => 10,
+                Some(1) => 5,
+                Some(2) => 4,
+                Some(3) => 3,
+                Some(4) => 2,
+                _ if queue.peak_bitcounts::<1>()[0] > 32 => {
+                    nibbles += 8;
+                    1
+                }
+                _ => 1,
+            };
+            for _ in 0..samples {
+                queue.pop();
+            }
+            nibbles += 9;
+        }
+        nibbles
+    }
 }
 
 // Delta-Delta Encoding
@@ -301,35 +371,61 @@ pub trait EmitDeltaDeltaBits {
     /// Emits bits according to the most efficient case of Delta-Delta Compression.
     /// Returns the number of elements popped from the queue.
     fn emit_delta_delta_bits(&mut self, out: &mut HalfVec) -> usize;
+
+    /// The number of nibbles that flushing the queue would emit, without modifying the queue.
+    fn flushed_delta_delta_nibbles(&self) -> usize;
+}
+
+///
+/// The number of nibbles of a delta-delta word for a zigzag value of the given bit count.
+///
+fn delta_delta_nibbles(bits: usize) -> usize {
+    match bits {
+        0 => 1,
+        1..=5 => 2,
+        6..=9 => 3,
+        10..=16 => 5,
+        17..=32 => 9,
+        _ => 17,
+    }
 }
 
 ///
 /// A helper function that emits bits according to the most efficient case of Delta-Delta Compression.
 fn emit_popped_values<const N: usize>(
     bitcounts: &[usize; N],
-    values: &[usize; N],
+    values: &[u64; N],
     out: &mut HalfVec,
 ) {
     for (bits, value) in bitcounts.iter().zip(values.iter()) {
         match bits {
-            0 => out.push(HalfWord::Half(0b0000)),
+            0 => out.push(HalfWord::Half(headers::DELTA_DELTA_ZERO)),
             1..=5 => {
                 let zigzag = (value & 0b1_1111) as u8;
-                out.push(HalfWord::Byte(0b0010_0000 | zigzag));
+                out.push(HalfWord::Byte(
+                    (headers::DELTA_DELTA_FIVE_BITS << 4) | zigzag,
+                ));
             }
             6..=9 => {
                 let zigzag = (value & 0b1_1111_1111) as u16;
-                out.push(HalfWord::Half(0b0100 | (zigzag >> 8) as u8));
+                out.push(HalfWord::Half(
+                    headers::DELTA_DELTA_NINE_BITS | (zigzag >> 8) as u8,
+                ));
                 out.push(HalfWord::Byte(zigzag as u8));
             }
             10..=16 => {
                 let zigzag = (value & 0b1111_1111_1111_1111) as u16;
-                out.push(HalfWord::Half(0b0110));
+                out.push(HalfWord::Half(headers::DELTA_DELTA_SIXTEEN_BITS));
                 out.push(HalfWord::Byte((zigzag >> 8) as u8));
                 out.push(HalfWord::Byte(zigzag as u8));
             }
+            17..=32 => {
+                out.push(HalfWord::Half(headers::DELTA_DELTA_THIRTY_TWO_BITS));
+                out.push(HalfWord::Full(*value as u32));
+            }
             _ => {
-                out.push(HalfWord::Half(0b0111));
+                out.push(HalfWord::Half(headers::DELTA_DELTA_SIXTY_FOUR_BITS));
+                out.push(HalfWord::Full((*value >> 32) as u32));
                 out.push(HalfWord::Full(*value as u32));
             }
         }
@@ -338,6 +434,16 @@ fn emit_popped_values<const N: usize>(
 
 impl EmitDeltaDeltaBits for CompressionQueue<2> {
     fn emit_delta_delta_bits(&mut self, out: &mut HalfVec) -> usize {
+        // Zero delta-deltas accumulate into a run while the queue is full of zeros
+        if self.is_full() && self.zero_prefix_len() >= 2 {
+            self.pop_zeros(2);
+            return 2;
+        }
+        let popped = emit_zero_run(self, headers::DELTA_DELTA_ZERO_RUN, out);
+        if popped > 0 {
+            return popped;
+        }
+
         match self.len() {
             2 => {
                 let bitcounts = self.peak_bitcounts::<2>();
@@ -354,6 +460,175 @@ impl EmitDeltaDeltaBits for CompressionQueue<2> {
             _ => 0,
         }
     }
+
+    fn flushed_delta_delta_nibbles(&self) -> usize {
+        let bitcounts = self.peak_bitcounts::<2>();
+        let bitcounts = &bitcounts[..self.len()];
+
+        // A full queue of zeros joins the run, otherwise the run takes the zeros at the front
+        let zeros = self.zero_prefix_len();
+        let (run, rest) = if self.is_full() && zeros >= 2 {
+            (self.zero_run() + 2, &[][..])
+        } else if self.zero_run() > 0 {
+            (self.zero_run() + zeros as u64, &bitcounts[zeros..])
+        } else {
+            (0, bitcounts)
+        };
+        let run_nibbles = if run > 0 { 1 + run_nibbles(run) } else { 0 };
+        run_nibbles + rest.iter().copied().map(delta_delta_nibbles).sum::<usize>()
+    }
+}
+
+///
+/// A trait that provides a method for emitting delta-deltas of 128-bit columns.
+///
+pub trait EmitWideDeltaDeltaBits {
+    /// Enqueues a delta-delta, emitting bits when the queue is full.
+    ///
+    /// A delta-delta outside of the 64-bit range flushes the queue and is emitted
+    /// as a 64-bit tag with a zero upper word followed by the 128-bit zigzag value.
+    /// The zero upper word is never emitted otherwise, since the 32-bit tag is smaller.
+    fn emit_wide_delta_delta_bits(&mut self, value: i128, out: &mut HalfVec);
+}
+
+impl EmitWideDeltaDeltaBits for CompressionQueue<2> {
+    fn emit_wide_delta_delta_bits(&mut self, value: i128, out: &mut HalfVec) {
+        if let Ok(value) = i64::try_from(value) {
+            self.push(value);
+            if self.is_full() {
+                self.emit_delta_delta_bits(out);
+            }
+            return;
+        }
+
+        while !self.is_empty() || self.zero_run() > 0 {
+            self.emit_delta_delta_bits(out);
+        }
+        let zigzag = ((value << 1) ^ (value >> 127)) as u128;
+        out.push(HalfWord::Half(headers::DELTA_DELTA_SIXTY_FOUR_BITS));
+        out.push(HalfWord::Full(0));
+        write_i128_bits(out, zigzag as i128);
+    }
+}
+
+///
+/// Encodes a column of 32-bit or 64-bit floating point bits by XOR with the previous value,
+/// in the style of Gorilla with windows of meaningful nibbles rather than bits.
+///
+/// After the full first value, each value is one of:
+/// - `XOR_ZERO`: the value is unchanged.
+/// - `XOR_SAME_WINDOW`: the meaningful nibbles of the XOR in the window of the previous word.
+/// - `XOR_NEW_WINDOW`: the count of leading zero nibbles, the count of meaningful nibbles less one,
+///   then the meaningful nibbles of the XOR.
+///
+#[derive(Debug, Clone, Copy)]
+pub struct XorEncoder {
+    prev: u64,
+    nibbles: usize,
+    leading: usize,
+    meaningful: usize,
+}
+
+impl XorEncoder {
+    ///
+    /// Creates an encoder for values of the given bit-width, 32 or 64.
+    ///
+    pub const fn new(bits: usize) -> Self {
+        Self {
+            prev: 0,
+            nibbles: bits / 4,
+            leading: 0,
+            meaningful: bits / 4,
+        }
+    }
+
+    ///
+    /// Writes the first value of the column in full.
+    ///
+    pub fn emit_first_bits(&mut self, bits: u64, out: &mut HalfVec) {
+        out.push(HalfWord::Half(headers::XOR_FIRST_ROW));
+        if self.nibbles == 16 {
+            out.push(HalfWord::Full((bits >> 32) as u32));
+        }
+        out.push(HalfWord::Full(bits as u32));
+        self.prev = bits;
+        self.leading = 0;
+        self.meaningful = self.nibbles;
+    }
+
+    ///
+    /// Writes the XOR of a value with the previous value.
+    ///
+    #[inline(always)]
+    pub fn emit_bits(&mut self, bits: u64, out: &mut HalfVec) {
+        let xor = bits ^ self.prev;
+        self.prev = bits;
+        if xor == 0 {
+            out.push(HalfWord::Half(headers::XOR_ZERO));
+            return;
+        }
+
+        let leading = (xor.leading_zeros() as usize - (64 - 4 * self.nibbles)) / 4;
+        let trailing = xor.trailing_zeros() as usize / 4;
+        let meaningful = self.nibbles - leading - trailing;
+
+        // Keep the previous window if the XOR fits and it is no longer than a new window
+        let prev_trailing = self.nibbles - self.leading - self.meaningful;
+        if leading >= self.leading && trailing >= prev_trailing && self.meaningful <= meaningful + 2
+        {
+            out.push(HalfWord::Half(headers::XOR_SAME_WINDOW));
+            write_nibbles(out, xor >> (4 * prev_trailing), self.meaningful);
+            return;
+        }
+
+        out.push(HalfWord::Half(headers::XOR_NEW_WINDOW));
+        out.push(HalfWord::Byte(((leading << 4) | (meaningful - 1)) as u8));
+        write_nibbles(out, xor >> (4 * trailing), meaningful);
+        self.leading = leading;
+        self.meaningful = meaningful;
+    }
+
+    ///
+    /// The bits of the last value written.
+    ///
+    pub fn prev(&self) -> u64 {
+        self.prev
+    }
+
+    ///
+    /// The window of the last value as written after a new window tag,
+    /// the leading nibbles and one less than the meaningful nibbles.
+    ///
+    pub fn window(&self) -> u8 {
+        ((self.leading << 4) | (self.meaningful - 1)) as u8
+    }
+}
+
+/// Writes the lowest `count` nibbles of the value, most significant first.
+fn write_nibbles(out: &mut HalfVec, value: u64, count: usize) {
+    let mut count = count;
+    if count % 2 == 1 {
+        count -= 1;
+        out.push(HalfWord::Half((value >> (4 * count)) as u8 & 0xF));
+    }
+    while count > 0 {
+        count -= 2;
+        out.push(HalfWord::Byte((value >> (4 * count)) as u8));
+    }
+}
+
+///
+/// Writes a frame header describing the columns that follow.
+///
+/// The header is the magic, format version, column count, a type code per column
+/// and a hash of the field names and types. It is byte-aligned, so it is written
+/// directly to the output rather than through a HalfVec.
+///
+pub fn write_frame_header<E: Extend<u8>>(out: &mut E, column_types: &[u8], schema_hash: u32) {
+    out.extend(consts::frame::MAGIC);
+    out.extend([consts::frame::VERSION, column_types.len() as u8]);
+    out.extend(column_types.iter().copied());
+    out.extend(schema_hash.to_be_bytes());
 }
 
 ///
@@ -413,3 +688,102 @@ pub fn write_i16_bits(buf: &mut HalfVec, i: i16) {
 pub fn write_i8_bits(buf: &mut HalfVec, i: i8) {
     buf.push(HalfWord::Byte(i as u8));
 }
+
+///
+/// Writes a signed value as a nibble-aligned VLQ of its zigzag encoding.
+///
+/// Each nibble holds 3 bits of the zigzag encoding, most significant first,
+/// with the top bit set on every nibble but the last. The first value and first delta of
+/// a column are written this way, so that small values take few nibbles whatever the bit-width.
+///
+pub fn write_signed_vlq(buf: &mut HalfVec, value: i128) {
+    let zigzag = ((value << 1) ^ (value >> 127)) as u128;
+    for i in (0..signed_vlq_nibbles(value)).rev() {
+        let more = if i > 0 { 0b1000 } else { 0 };
+        buf.push(HalfWord::Half(more | ((zigzag >> (3 * i)) & 0b111) as u8));
+    }
+}
+
+/// The number of nibbles of a signed VLQ.
+pub(crate) fn signed_vlq_nibbles(value: i128) -> usize {
+    let zigzag = ((value << 1) ^ (value >> 127)) as u128;
+    let bits = (u128::BITS - zigzag.leading_zeros()) as usize;
+    bits.div_ceil(3).max(1)
+}
+
+/// Writes the bits of a value of a column of the bit-width, most significant nibble first.
+pub(crate) fn write_value_bits(buf: &mut HalfVec, value: i128, bits: usize) {
+    for i in (0..bits / 4).rev() {
+        buf.push(HalfWord::Half(((value >> (4 * i)) & 0xf) as u8));
+    }
+}
+
+///
+/// Packs values of at most 64 bits into nibbles, most significant bit first.
+///
+#[derive(Debug, Default)]
+pub(crate) struct BitPacker {
+    acc: u128,
+    acc_bits: usize,
+}
+
+impl BitPacker {
+    /// Packs the low bits of the value, writing each nibble as it fills.
+    pub(crate) fn push(&mut self, buf: &mut HalfVec, value: u64, bits: usize) {
+        if bits == 0 {
+            return;
+        }
+        self.acc = (self.acc << bits) | (value & (u64::MAX >> (64 - bits))) as u128;
+        self.acc_bits += bits;
+        while self.acc_bits >= 4 {
+            self.acc_bits -= 4;
+            buf.push(HalfWord::Half(((self.acc >> self.acc_bits) & 0xf) as u8));
+        }
+        self.acc &= (1 << self.acc_bits) - 1;
+    }
+
+    /// Writes the last partial nibble, padding it with zeros.
+    pub(crate) fn finish(self, buf: &mut HalfVec) {
+        if self.acc_bits > 0 {
+            buf.push(HalfWord::Half(
+                ((self.acc << (4 - self.acc_bits)) & 0xf) as u8,
+            ));
+        }
+    }
+}
+
+///
+/// Writes an unsigned 64-bit integer to a HalfVec.
+///
+/// The bits are written the same as the two's complement i64.
+///
+pub fn write_u64_bits(buf: &mut HalfVec, u: u64) {
+    write_i64_bits(buf, u as i64);
+}
+
+///
+/// Writes an unsigned 32-bit integer to a HalfVec.
+///
+/// The bits are written the same as the two's complement i32.
+///
+pub fn write_u32_bits(buf: &mut HalfVec, u: u32) {
+    write_i32_bits(buf, u as i32);
+}
+
+///
+/// Writes an unsigned 16-bit integer to a HalfVec.
+///
+/// The bits are written the same as the two's complement i16.
+///
+pub fn write_u16_bits(buf: &mut HalfVec, u: u16) {
+    write_i16_bits(buf, u as i16);
+}
+
+///
+/// Writes an unsigned 8-bit integer to a HalfVec.
+///
+/// The bits are written the same as the two's complement i8.
+///
+pub fn write_u8_bits(buf: &mut HalfVec, u: u8) {
+    write_i8_bits(buf, u as i8);
+}
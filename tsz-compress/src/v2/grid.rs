@@ -0,0 +1,244 @@
+use crate::prelude::*;
+use crate::v2::consts::headers;
+use crate::v2::decode::read_signed_vlq;
+use crate::v2::encode::signed_vlq_nibbles;
+use crate::v2::halfvec::{HalfVec, HalfWord};
+use alloc::vec::Vec;
+
+///
+/// The nominal grid of a `#[tsz(timestamp(period = ...))]` column: the first timestamp
+/// and the period, written once per frame as signed VLQs after the start of column tag.
+///
+/// The column then holds the residual of each later timestamp against `first + k * period`,
+/// encoded as any other column, or nothing at all when every residual is zero.
+///
+/// Values and residuals wrap at the bit-width of the column.
+///
+#[derive(Debug, Clone)]
+pub struct TimestampGrid {
+    prefix: HalfVec,
+    bits: usize,
+    period: i128,
+    first: i128,
+    rows: usize,
+    exact: bool,
+}
+
+impl TimestampGrid {
+    ///
+    /// Creates an empty grid for a column of the bit-width and nominal period.
+    ///
+    pub fn new(bits: usize, period: i128) -> Self {
+        let mut prefix = HalfVec::new(2 + 2 * bits.div_ceil(3));
+        prefix.push(HalfWord::Half(headers::START_OF_COLUMN));
+        Self {
+            prefix,
+            bits,
+            period,
+            first: 0,
+            rows: 0,
+            exact: true,
+        }
+    }
+
+    ///
+    /// Records the next timestamp, returning its residual against the grid,
+    /// or `None` for the first timestamp of the frame.
+    ///
+    #[inline(always)]
+    pub fn push(&mut self, value: i128) -> Option<i128> {
+        let k = self.rows as i128;
+        self.rows += 1;
+        if k == 0 {
+            self.first = value;
+            return None;
+        }
+
+        // Sign-extend the residual from the bit-width of the column
+        let nominal = self.first.wrapping_add(k.wrapping_mul(self.period));
+        let residual = self.sign_extend(value.wrapping_sub(nominal));
+        self.exact &= residual == 0;
+        Some(residual)
+    }
+
+    /// Sign-extends a value from the bit-width of the column.
+    fn sign_extend(&self, value: i128) -> i128 {
+        let shift = 128 - self.bits;
+        (value << shift) >> shift
+    }
+
+    ///
+    /// Whether every timestamp is on the grid, so the residuals are omitted from the frame.
+    ///
+    pub fn is_exact(&self) -> bool {
+        self.exact
+    }
+
+    ///
+    /// The number of nibbles of the prefix once finished, including the start of column tag.
+    ///
+    pub fn finished_nibbles(&self) -> usize {
+        if self.rows == 0 {
+            1
+        } else {
+            2 + signed_vlq_nibbles(self.sign_extend(self.first))
+                + signed_vlq_nibbles(self.sign_extend(self.period))
+        }
+    }
+
+    ///
+    /// Writes the grid tag, the first timestamp and the period, finishing the prefix.
+    ///
+    pub fn flush(&mut self) {
+        if self.rows == 0 {
+            return;
+        }
+        let tag = if self.exact {
+            headers::EXACT_GRID
+        } else {
+            headers::GRID
+        };
+        self.prefix.push(HalfWord::Half(tag));
+        let (first, period) = (self.sign_extend(self.first), self.sign_extend(self.period));
+        write_signed_vlq(&mut self.prefix, first);
+        write_signed_vlq(&mut self.prefix, period);
+    }
+
+    ///
+    /// The nibbles of the prefix, including the start of column tag.
+    ///
+    pub fn buffer(&self) -> &HalfVec {
+        &self.prefix
+    }
+
+    ///
+    /// Clears the grid for the next frame.
+    ///
+    pub fn clear(&mut self) {
+        self.prefix.clear();
+        self.prefix.push(HalfWord::Half(headers::START_OF_COLUMN));
+        self.first = 0;
+        self.rows = 0;
+        self.exact = true;
+    }
+}
+
+///
+/// Reads the grid of a timestamp column after the start of column tag,
+/// returning the first timestamp, the period and whether every timestamp is on the grid,
+/// or `None` for a column without values.
+///
+pub(crate) fn read_grid(
+    iter: &mut HalfIter<'_>,
+) -> Result<Option<(i128, i128, bool)>, CodingError> {
+    let exact = match iter.next() {
+        None | Some(headers::START_OF_COLUMN) => return Ok(None),
+        Some(headers::GRID) => false,
+        Some(headers::EXACT_GRID) => true,
+        Some(_) => return Err(CodingError::InvalidInitialColumnTag),
+    };
+    let first = read_signed_vlq(iter)?;
+    let period = read_signed_vlq(iter)?;
+
+    // An exact grid has no residuals before the start of the next column
+    if exact {
+        match iter.next() {
+            None | Some(headers::START_OF_COLUMN) => (),
+            Some(_) => return Err(CodingError::InvalidColumnTag),
+        }
+    }
+    Ok(Some((first, period, exact)))
+}
+
+///
+/// Decodes a timestamp column of `rows` rows, after the start of column tag.
+///
+/// The residuals of the timestamps after the first are decoded with `decode`,
+/// then added to the grid. A column on an exact grid takes its length from the row count of the frame.
+///
+pub fn decode_grid<T: ColumnValue<i128> + Into<i128>>(
+    iter: &mut HalfIter<'_>,
+    rows: usize,
+    output: &mut Vec<T>,
+    decode: ColumnDecoder<T>,
+) -> Result<(), CodingError> {
+    let Some((first, period, exact)) = read_grid(iter)? else {
+        return Ok(());
+    };
+
+    let start = output.len();
+    output.push(T::from_bits(first));
+    if exact {
+        for k in 1..rows {
+            output.push(T::from_bits(
+                first.wrapping_add((k as i128).wrapping_mul(period)),
+            ));
+        }
+        return Ok(());
+    }
+
+    // Add the grid to the residuals
+    decode(iter, rows.saturating_sub(1), output)?;
+    for (k, value) in output[start..].iter_mut().enumerate().skip(1) {
+        let nominal = first.wrapping_add((k as i128).wrapping_mul(period));
+        *value = T::from_bits(nominal.wrapping_add((*value).into()));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn finish(grid: &mut TimestampGrid) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        let nibbles = grid.finished_nibbles();
+        grid.flush();
+        assert_eq!(grid.buffer().len(), nibbles);
+        HalfVec::finish(&mut bytes, [grid.buffer()].into_iter());
+        bytes
+    }
+
+    #[test]
+    fn residuals() {
+        let mut grid = TimestampGrid::new(16, 1000);
+        assert_eq!(grid.push(-500), None);
+        assert_eq!(grid.push(500), Some(0));
+        assert!(grid.is_exact());
+        assert_eq!(grid.push(1497), Some(-3));
+        assert!(!grid.is_exact());
+
+        // Residuals wrap at the bit-width of the column
+        assert_eq!(grid.push(i16::MAX as i128 + 2501), Some(i16::MIN as i128));
+    }
+
+    #[test]
+    fn exact_grid() {
+        let mut grid = TimestampGrid::new(32, 32768);
+        for k in 0..100 {
+            grid.push(u32::MAX as i128 - 5 + k * 32768);
+        }
+        let bytes = finish(&mut grid);
+
+        let mut iter = HalfIter::new(&bytes);
+        assert_eq!(iter.next(), Some(headers::START_OF_COLUMN));
+        let mut output: Vec<u32> = Vec::new();
+        decode_grid(&mut iter, 100, &mut output, decode_u32_rows).unwrap();
+        let expected = (0..100)
+            .map(|k| (u32::MAX - 5).wrapping_add(k * 32768))
+            .collect::<Vec<_>>();
+        assert_eq!(output, expected);
+    }
+
+    #[test]
+    fn empty_grid() {
+        let mut grid = TimestampGrid::new(64, 1000);
+        let bytes = finish(&mut grid);
+
+        let mut iter = HalfIter::new(&bytes);
+        assert_eq!(iter.next(), Some(headers::START_OF_COLUMN));
+        let mut output: Vec<i64> = Vec::new();
+        decode_grid(&mut iter, 0, &mut output, decode_i64_rows).unwrap();
+        assert!(output.is_empty());
+    }
+}
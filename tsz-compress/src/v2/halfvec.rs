@@ -13,7 +13,7 @@ pub use queue::*;
 /// Pushing is fast. There is no pop.
 /// All bits are pushed together during finish.
 ///
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct HalfVec {
     words: Vec<HalfWord>,
     len: usize,
@@ -22,7 +22,7 @@ pub struct HalfVec {
 ///
 /// Bits collected into a single word as one byte or two bytes.
 ///
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub enum HalfWord {
     /// The bottom bits of the word are used.
     /// 0b0000_1111
@@ -105,10 +105,7 @@ impl HalfVec {
             // Reserve enough space for the output
             let len = word_lists.clone().map(|w| w.len).sum::<usize>() / 2;
             let reserve_len = len + 1;
-            let avail = out.capacity() - out.len();
-            if avail < reserve_len {
-                out.reserve_exact(reserve_len - avail);
-            }
+            out.reserve_exact(reserve_len);
             let bytes = out.spare_capacity_mut();
             let mut idx = 0;
 
@@ -205,10 +202,7 @@ impl HalfVec {
         // Reserve enough space for the output
         let len = word_lists.clone().map(|w| w.len).sum::<usize>();
         let len = if len % 2 == 0 { len / 2 } else { len / 2 + 1 };
-        let avail = out.capacity() - out.len();
-        if avail < len {
-            out.reserve_exact(len - avail);
-        }
+        out.reserve_exact(len);
 
         // Keep track of whether we are on the upper or lower nibble across word lists
         let mut upper = true;
@@ -331,7 +325,7 @@ mod tests {
         // Now every nibble is pushed together
         let mut bytes = Vec::new();
         HalfVec::finish(&mut bytes, [&queue].into_iter());
-        assert_eq!(bytes.len(), (128 + 8 * 128 + 1 + 1) / 2);
+        assert_eq!(bytes.len(), (128 + 8 * 128 + 1 + 1_usize).div_ceil(2));
     }
 
     #[test]
@@ -358,13 +352,9 @@ mod tests {
         queue.push(HalfWord::Half(0));
 
         // Now every nibble is pushed together
-        let mut bytes = Vec::new();
-        bytes.push(0xDE);
-        bytes.push(0xAD);
-        bytes.push(0xBE);
-        bytes.push(0xEF);
+        let mut bytes = vec![0xDE, 0xAD, 0xBE, 0xEF];
         HalfVec::finish(&mut bytes, [&queue].into_iter());
-        assert_eq!(bytes.len(), 4 + ((128 + 8 * 128 + 1 + 1) / 2));
+        assert_eq!(bytes.len(), 4 + ((128 + 8 * 128 + 1 + 1_usize).div_ceil(2)));
         assert_eq!(bytes[0], 0xDE);
         assert_eq!(bytes[1], 0xAD);
         assert_eq!(bytes[2], 0xBE);
@@ -402,7 +392,7 @@ mod tests {
         bytes.push(0xBE);
         bytes.push(0xEF);
         HalfVec::finish_thin(&mut bytes, [&queue].into_iter());
-        assert_eq!(bytes.len(), 4 + ((128 + 8 * 128 + 1 + 1) / 2));
+        assert_eq!(bytes.len(), 4 + ((128 + 8 * 128 + 1 + 1_usize).div_ceil(2)));
         assert_eq!(bytes[0], 0xDE);
         assert_eq!(bytes[1], 0xAD);
         assert_eq!(bytes[2], 0xBE);
@@ -595,4 +585,83 @@ mod tests_emit_delta {
         // Expected length
         assert_eq!(encoded_halfvec, expected_halfvec);
     }
+
+    #[test]
+    fn test_emit_delta_zero_run() {
+        // 25 zero deltas, then 2 samples in 16 bits
+        let mut values = vec![0; 25];
+        values.extend([5, -3]);
+
+        // Emit when full, then flush as the compressor does
+        let mut queue: CompressionQueue<10> = CompressionQueue::new();
+        let mut encoded_halfvec = HalfVec::new(8);
+        for value in values {
+            queue.push(value);
+            if queue.is_full() {
+                queue.emit_delta_bits(&mut encoded_halfvec);
+            }
+        }
+        assert_eq!(queue.zero_run(), 20);
+        assert_eq!(queue.flushed_delta_nibbles(), 12);
+        while !queue.is_empty() || queue.zero_run() > 0 {
+            queue.flush_delta_bits(&mut encoded_halfvec);
+        }
+
+        // Initialize expected bit buffer
+        let mut expected_halfvec = HalfVec::new(8);
+
+        // Expecting a run of 25 zeros: 1 011, 0 001
+        expected_halfvec.push(HalfWord::Half(headers::ZERO_RUN));
+        expected_halfvec.push(HalfWord::Half(0b1011));
+        expected_halfvec.push(HalfWord::Half(0b0001));
+
+        // Zigzag values: [10, 5]
+        expected_halfvec.push(HalfWord::Half(headers::SIXTEEN_BITS_TWO_SAMPLES));
+        expected_halfvec.push(HalfWord::Full(0x000A_0005));
+
+        assert_eq!(encoded_halfvec, expected_halfvec);
+    }
+
+    #[test]
+    fn test_emit_delta_delta_zero_run() {
+        // 7 zero delta-deltas, then 1 sample in 5 bits
+        let mut values = vec![0; 7];
+        values.push(3);
+
+        // Emit when full, then flush as the compressor does
+        let mut queue: CompressionQueue<2> = CompressionQueue::new();
+        let mut encoded_halfvec = HalfVec::new(8);
+        for value in values {
+            queue.push(value);
+            if queue.is_full() {
+                queue.emit_delta_delta_bits(&mut encoded_halfvec);
+            }
+        }
+        assert_eq!(queue.flushed_delta_delta_nibbles(), 2);
+        while !queue.is_empty() || queue.zero_run() > 0 {
+            queue.emit_delta_delta_bits(&mut encoded_halfvec);
+        }
+
+        // Initialize expected bit buffer
+        let mut expected_halfvec = HalfVec::new(8);
+
+        // Expecting a run of 7 zeros, then the zigzag value 6
+        expected_halfvec.push(HalfWord::Half(headers::DELTA_DELTA_ZERO_RUN));
+        expected_halfvec.push(HalfWord::Half(0b0111));
+        expected_halfvec.push(HalfWord::Byte((headers::DELTA_DELTA_FIVE_BITS << 4) | 6));
+
+        assert_eq!(encoded_halfvec, expected_halfvec);
+
+        // A pending run with a full queue of zeros is flushed as one run
+        let mut queue: CompressionQueue<2> = CompressionQueue::new();
+        for _ in 0..6 {
+            queue.push(0);
+            if queue.is_full() {
+                queue.emit_delta_delta_bits(&mut encoded_halfvec);
+            }
+        }
+        queue.push(0);
+        assert_eq!(queue.zero_run(), 6);
+        assert_eq!(queue.flushed_delta_delta_nibbles(), 2);
+    }
 }
@@ -0,0 +1,299 @@
+use crate::prelude::*;
+use alloc::vec::Vec;
+
+/// The bytes of the shortest match.
+const MIN_MATCH: usize = 4;
+/// The last bytes of a block are always literals.
+const LAST_LITERALS: usize = 5;
+/// No match starts within the last bytes of a block.
+const MF_LIMIT: usize = 12;
+/// The farthest back a match may copy from.
+const MAX_OFFSET: usize = u16::MAX as usize;
+/// The log2 of the entries of the table of positions by hash, 2 KiB of stack.
+const HASH_LOG: u32 = 9;
+
+#[inline(always)]
+fn read_u32(bytes: &[u8], i: usize) -> u32 {
+    u32::from_le_bytes([bytes[i], bytes[i + 1], bytes[i + 2], bytes[i + 3]])
+}
+
+#[inline(always)]
+fn hash(sequence: u32) -> usize {
+    (sequence.wrapping_mul(2654435761) >> (32 - HASH_LOG)) as usize
+}
+
+///
+/// Appends the bytes of a length beyond the 15 held by the token.
+///
+fn write_length(out: &mut Vec<u8>, mut len: usize) {
+    while len >= 255 {
+        out.push(255);
+        len -= 255;
+    }
+    out.push(len as u8);
+}
+
+///
+/// Appends a sequence of literals followed by an optional match of (offset, length).
+///
+fn write_sequence(out: &mut Vec<u8>, literals: &[u8], matched: Option<(usize, usize)>) {
+    let literal_len = literals.len();
+    let match_len = matched.map_or(0, |(_, len)| len - MIN_MATCH);
+    out.push(((literal_len.min(15) as u8) << 4) | match_len.min(15) as u8);
+    if literal_len >= 15 {
+        write_length(out, literal_len - 15);
+    }
+    out.extend_from_slice(literals);
+    if let Some((offset, _)) = matched {
+        out.extend_from_slice(&(offset as u16).to_le_bytes());
+        if match_len >= 15 {
+            write_length(out, match_len - 15);
+        }
+    }
+}
+
+///
+/// Compresses bytes into an LZ4 block with a greedy search for matches.
+///
+/// The block holds no length, so the compressed bytes must be framed by the caller.
+///
+pub fn pack(input: &[u8]) -> Vec<u8> {
+    let mut out = Vec::with_capacity(input.len() + input.len() / 255 + 1);
+    let mut table = [0u32; 1 << HASH_LOG];
+    let mut anchor = 0;
+
+    // Blocks too short to hold a match are only literals
+    if input.len() > MF_LIMIT {
+        let match_limit = input.len() - LAST_LITERALS;
+        let search_limit = input.len() - MF_LIMIT;
+        let mut i = 0;
+        while i <= search_limit {
+            let sequence = read_u32(input, i);
+            let h = hash(sequence);
+            let candidate = table[h] as usize;
+            table[h] = i as u32;
+
+            // Hash collisions and stale positions are verified against the bytes
+            if candidate < i
+                && i - candidate <= MAX_OFFSET
+                && read_u32(input, candidate) == sequence
+            {
+                let mut len = MIN_MATCH;
+                while i + len < match_limit && input[candidate + len] == input[i + len] {
+                    len += 1;
+                }
+                write_sequence(&mut out, &input[anchor..i], Some((i - candidate, len)));
+                i += len;
+                anchor = i;
+            } else {
+                i += 1;
+            }
+        }
+    }
+
+    // The last sequence has only literals
+    write_sequence(&mut out, &input[anchor..], None);
+    out
+}
+
+///
+/// Reads the bytes of a length beyond the 15 held by the token.
+///
+fn read_length(input: &[u8], i: &mut usize) -> Result<usize, CodingError> {
+    let mut len = 0usize;
+    loop {
+        let byte = *input.get(*i).ok_or(CodingError::NotEnoughBits)?;
+        *i += 1;
+        len += byte as usize;
+        if byte != 255 {
+            return Ok(len);
+        }
+    }
+}
+
+///
+/// Decompresses an LZ4 block, appending the bytes to `out`.
+///
+pub fn unpack(input: &[u8], out: &mut Vec<u8>) -> Result<(), CodingError> {
+    if input.is_empty() {
+        return Err(CodingError::Empty);
+    }
+
+    let mut i = 0;
+    loop {
+        let token = *input.get(i).ok_or(CodingError::NotEnoughBits)?;
+        i += 1;
+
+        // Copy the literals
+        let mut literal_len = (token >> 4) as usize;
+        if literal_len == 15 {
+            literal_len += read_length(input, &mut i)?;
+        }
+        let literals = i
+            .checked_add(literal_len)
+            .and_then(|end| input.get(i..end))
+            .ok_or(CodingError::NotEnoughBits)?;
+        out.extend_from_slice(literals);
+        i += literal_len;
+
+        // The last sequence has only literals
+        if i == input.len() {
+            return Ok(());
+        }
+
+        // Copy the match from the bytes already written
+        let offset = match input.get(i..i + 2) {
+            Some(&[lo, hi]) => u16::from_le_bytes([lo, hi]) as usize,
+            _ => return Err(CodingError::NotEnoughBits),
+        };
+        i += 2;
+        if offset == 0 || offset > out.len() {
+            return Err(CodingError::InvalidBits);
+        }
+        let mut match_len = (token & 0xf) as usize + MIN_MATCH;
+        if token & 0xf == 15 {
+            match_len += read_length(input, &mut i)?;
+        }
+        let start = out.len() - offset;
+        if offset >= match_len {
+            out.extend_from_within(start..start + match_len);
+        } else {
+            // An overlapping match repeats the bytes it copies
+            for k in start..start + match_len {
+                out.push(out[k]);
+            }
+        }
+    }
+}
+
+///
+/// Reads the flag preceding the body of a frame written with the `lz` feature,
+/// returning the body and unpacking it into `unpacked` if it is packed.
+///
+pub fn unpack_frame<'a>(
+    bytes: &'a [u8],
+    unpacked: &'a mut Vec<u8>,
+) -> Result<&'a [u8], CodingError> {
+    match bytes.split_first() {
+        Some((&consts::frame::RAW, body)) => Ok(body),
+        Some((&consts::frame::PACKED, body)) => {
+            unpack(body, unpacked)?;
+            Ok(unpacked)
+        }
+        Some(_) => Err(CodingError::InvalidBits),
+        None => Err(CodingError::Empty),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::Rng;
+
+    fn round_trip(input: &[u8]) -> Vec<u8> {
+        let packed = pack(input);
+        let mut unpacked = Vec::new();
+        unpack(&packed, &mut unpacked).unwrap();
+        assert_eq!(unpacked, input);
+        packed
+    }
+
+    #[test]
+    fn literals() {
+        assert_eq!(round_trip(&[]), [0x00]);
+        assert_eq!(round_trip(b"tsz"), [0x30, b't', b's', b'z']);
+        let mut rng = rand::thread_rng();
+        for len in [12, 13, 14, 15, 16, 255, 270, 1000] {
+            let input = (0..len).map(|_| rng.gen()).collect::<Vec<u8>>();
+            round_trip(&input);
+        }
+    }
+
+    #[test]
+    fn matches() {
+        // A run overlaps the bytes it copies
+        let packed = round_trip(&[7; 1000]);
+        assert!(packed.len() < 16);
+
+        // Repeated nibble-packed words
+        let input = (0..4000u32)
+            .flat_map(|i| [0x9a, 0x10, (i % 7) as u8, 0x00])
+            .collect::<Vec<u8>>();
+        let packed = round_trip(&input);
+        assert!(packed.len() < input.len() / 10);
+
+        // The last bytes stay literals
+        let mut input = b"abcdabcdabcdabcdabcd".to_vec();
+        round_trip(&input);
+        input.truncate(13);
+        round_trip(&input);
+    }
+
+    #[test]
+    fn random_repeats() {
+        let mut rng = rand::thread_rng();
+        for _ in 0..100 {
+            let alphabet = rng.gen_range(1..8u8);
+            let len = rng.gen_range(0..3000);
+            let input = (0..len)
+                .map(|_| rng.gen_range(0..alphabet))
+                .collect::<Vec<u8>>();
+            round_trip(&input);
+        }
+    }
+
+    #[test]
+    fn invalid_blocks() {
+        let mut out = Vec::new();
+        assert!(matches!(unpack(&[], &mut out), Err(CodingError::Empty)));
+        assert!(matches!(
+            unpack(&[0x30, b't'], &mut out),
+            Err(CodingError::NotEnoughBits)
+        ));
+
+        // A match before the first byte
+        out.clear();
+        assert!(matches!(
+            unpack(&[0x10, b't', 0x02, 0x00], &mut out),
+            Err(CodingError::InvalidBits)
+        ));
+
+        // A zero offset
+        out.clear();
+        assert!(matches!(
+            unpack(&[0x10, b't', 0x00, 0x00], &mut out),
+            Err(CodingError::InvalidBits)
+        ));
+
+        // A truncated offset
+        out.clear();
+        assert!(matches!(
+            unpack(&[0x10, b't', 0x01], &mut out),
+            Err(CodingError::NotEnoughBits)
+        ));
+    }
+
+    #[test]
+    fn frames() {
+        let mut unpacked = Vec::new();
+        assert_eq!(
+            unpack_frame(&[consts::frame::RAW, 1, 2], &mut unpacked).unwrap(),
+            [1, 2]
+        );
+
+        let mut frame = vec![consts::frame::PACKED];
+        frame.extend(pack(&[5; 100]));
+        let mut unpacked = Vec::new();
+        assert_eq!(unpack_frame(&frame, &mut unpacked).unwrap(), [5; 100]);
+
+        let mut unpacked = Vec::new();
+        assert!(matches!(
+            unpack_frame(&[], &mut unpacked),
+            Err(CodingError::Empty)
+        ));
+        assert!(matches!(
+            unpack_frame(&[0x7f, 1], &mut unpacked),
+            Err(CodingError::InvalidBits)
+        ));
+    }
+}
@@ -1,11 +1,29 @@
+pub mod checksum;
 pub mod consts;
+pub mod cursor;
 pub mod decode;
+pub mod dictionary;
 pub mod encode;
+pub mod grid;
 pub mod halfvec;
+pub mod lz;
+pub mod packetizer;
 pub mod queue;
+pub mod reference;
+pub mod seek;
+pub mod table;
+pub mod validity;
+pub use cursor::*;
 pub use decode::*;
+pub use dictionary::*;
 pub use encode::*;
+pub use grid::*;
+pub use packetizer::*;
 pub use queue::*;
+pub use reference::*;
+pub use seek::*;
+pub use table::*;
+pub use validity::*;
 
 ///
 /// An enumeration representing the possible errors that can occur during the decoding process.
@@ -26,6 +44,32 @@ pub enum CodingError {
     ColumnLengthMismatch(ColumnLengths),
     /// The number of rows to decode cannot be valid
     InvalidRowCount(usize),
+    /// The frame header does not describe the columns of the decompressor.
+    SchemaMismatch,
+    /// The checksum trailer does not match the bytes of the frame.
+    ChecksumMismatch,
+    /// A value of an enum column is not the discriminant of a variant.
+    InvalidDiscriminant,
+}
+
+impl CodingError {
+    ///
+    /// A negative error code identifying the error across the C interface.
+    ///
+    pub fn code(&self) -> i32 {
+        match self {
+            CodingError::Empty => -1,
+            CodingError::NotEnoughBits => -2,
+            CodingError::InvalidBits => -3,
+            CodingError::InvalidInitialColumnTag => -4,
+            CodingError::InvalidColumnTag => -5,
+            CodingError::ColumnLengthMismatch(_) => -6,
+            CodingError::InvalidRowCount(_) => -7,
+            CodingError::SchemaMismatch => -8,
+            CodingError::ChecksumMismatch => -9,
+            CodingError::InvalidDiscriminant => -10,
+        }
+    }
 }
 
 ///
@@ -82,6 +126,13 @@ pub trait TszCompressV2 {
     ///
     fn row_count(&self) -> usize;
 
+    ///
+    /// The exact number of bytes that `finish_into` would append for the rows compressed so far.
+    /// With `#[tsz(lz)]` this is an upper bound, as the body is only packed when it is shorter.
+    /// The seek index of a `#[tsz(seek = K)]` struct is counted from its number of checkpoints.
+    ///
+    fn finished_len(&self) -> usize;
+
     ///
     /// Consumes the compressor state, appending compressed bytes
     /// to the provided buffer and reserving space if needed.
@@ -0,0 +1,105 @@
+use crate::prelude::*;
+use alloc::vec::Vec;
+
+///
+/// An enumeration representing the possible errors that can occur while packetizing rows.
+///
+#[derive(Debug)]
+pub enum PacketizerError {
+    /// A frame of the single row would take the given number of bytes, exceeding the budget.
+    RowExceedsBudget(usize),
+}
+
+///
+/// Splits a stream of rows into independently decodable frames of at most `budget` bytes,
+/// such as MTU-sized BLE notifications.
+///
+/// A copy of the pending frame is taken before each row, so that the frame can be restored
+/// without the row that pushed it over budget.
+///
+#[derive(Debug)]
+pub struct Packetizer<C: TszCompressV2 + Clone> {
+    compressor: C,
+    snapshot: C,
+    rows: usize,
+    budget: usize,
+}
+
+impl<C: TszCompressV2 + Clone> Packetizer<C> {
+    ///
+    /// Initializes a packetizer emitting frames of at most `budget` bytes.
+    ///
+    pub fn new(budget: usize) -> Self {
+        // At best we can emit 3 bits per row not counting any metadata for one column
+        let prealloc_rows = budget * 8 / 3;
+        Self {
+            compressor: C::new(prealloc_rows),
+            snapshot: C::new(prealloc_rows),
+            rows: 0,
+            budget,
+        }
+    }
+
+    ///
+    /// The maximum number of bytes in a frame.
+    ///
+    pub fn budget(&self) -> usize {
+        self.budget
+    }
+
+    ///
+    /// The number of rows in the pending frame.
+    ///
+    pub fn row_count(&self) -> usize {
+        self.rows
+    }
+
+    ///
+    /// Compresses a row into the pending frame.
+    ///
+    /// If the row would push the pending frame over budget, the pending frame is appended
+    /// to `frame` and the row starts the next frame. Returns true if a frame was appended.
+    ///
+    /// A row that exceeds the budget in a frame of its own is dropped with `RowExceedsBudget`.
+    /// The length of a frame depends on the values of its rows, so a later row may still fit.
+    ///
+    pub fn compress_into(
+        &mut self,
+        row: C::T,
+        frame: &mut Vec<u8>,
+    ) -> Result<bool, PacketizerError> {
+        self.snapshot.clone_from(&self.compressor);
+        self.compressor.compress(row);
+        let finished_len = self.compressor.finished_len();
+        if finished_len <= self.budget {
+            self.rows += 1;
+            return Ok(false);
+        }
+
+        // Restore the pending frame that was within budget
+        core::mem::swap(&mut self.compressor, &mut self.snapshot);
+        if self.rows == 0 {
+            return Err(PacketizerError::RowExceedsBudget(finished_len));
+        }
+        self.compressor.finish_into(frame);
+
+        // Start the next frame with the row
+        self.rows = 1;
+        self.compressor.compress(row);
+        Ok(true)
+    }
+
+    ///
+    /// Appends the pending frame to `frame` if there are any pending rows.
+    /// Returns true if a frame was appended.
+    ///
+    pub fn finish_into(&mut self, frame: &mut Vec<u8>) -> bool {
+        if self.rows == 0 {
+            return false;
+        }
+
+        self.compressor.finish_into(frame);
+        self.rows = 0;
+        true
+    }
+}
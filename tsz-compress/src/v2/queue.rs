@@ -6,12 +6,13 @@ use super::encode::Bits;
 ///
 /// The absolute max size of this buffer is 16 elements.
 ///
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy)]
 pub struct CompressionQueue<const N: usize> {
-    zigzag: [usize; 16],
+    zigzag: [u64; 16],
     bitcount: [usize; 16],
     front: usize,
     len: usize,
+    zero_run: u64,
 }
 
 impl<const N: usize> CompressionQueue<N> {
@@ -25,6 +26,7 @@ impl<const N: usize> CompressionQueue<N> {
             bitcount: [0; 16],
             front: 0,
             len: 0,
+            zero_run: 0,
         }
     }
 
@@ -51,6 +53,38 @@ impl<const N: usize> CompressionQueue<N> {
         self.len == 0
     }
 
+    ///
+    /// Returns the number of zero values popped into a run that is not yet emitted.
+    ///
+    pub const fn zero_run(&self) -> u64 {
+        self.zero_run
+    }
+
+    ///
+    /// Returns the number of zero values at the front of the queue.
+    ///
+    pub(crate) fn zero_prefix_len(&self) -> usize {
+        (0..self.len)
+            .take_while(|i| unsafe { self.count_at((self.front + i) % 16) } == 0)
+            .count()
+    }
+
+    ///
+    /// Pops the zero values at the front of the queue into the pending run.
+    ///
+    pub(crate) fn pop_zeros(&mut self, count: usize) {
+        self.front = (self.front + count) % 16;
+        self.len -= count;
+        self.zero_run += count as u64;
+    }
+
+    ///
+    /// Takes the pending run of zero values.
+    ///
+    pub(crate) fn take_zero_run(&mut self) -> u64 {
+        core::mem::take(&mut self.zero_run)
+    }
+
     ///
     /// Pushes a value into the queue,
     /// overwriting the oldest value if the queue is full.
@@ -69,7 +103,7 @@ impl<const N: usize> CompressionQueue<N> {
     /// Pops the oldest value from the queue,
     /// returning None if the queue is empty.
     ///
-    pub fn pop(&mut self) -> Option<usize> {
+    pub fn pop(&mut self) -> Option<u64> {
         if self.is_empty() {
             return None;
         }
@@ -86,8 +120,8 @@ impl<const N: usize> CompressionQueue<N> {
     /// not of length N.
     ///
     #[inline(always)]
-    pub fn pop_n<const M: usize>(&mut self) -> [usize; M] {
-        let mut values: [usize; M] = [0; M];
+    pub fn pop_n<const M: usize>(&mut self) -> [u64; M] {
+        let mut values: [u64; M] = [0; M];
         for i in 0..M {
             let index = (self.front + i) % 16;
             unsafe {
@@ -105,7 +139,7 @@ impl<const N: usize> CompressionQueue<N> {
     /// not of length N.
     ///
     #[inline(always)]
-    pub fn peak_bitcounts<const M: usize>(&mut self) -> [usize; M] {
+    pub fn peak_bitcounts<const M: usize>(&self) -> [usize; M] {
         let mut values: [usize; M] = [0; M];
         for i in 0..M {
             let index = (self.front + i) % 16;
@@ -123,7 +157,7 @@ impl<const N: usize> CompressionQueue<N> {
     /// This function is unsafe because it assumes that
     /// the index is inbounds and initialized.
     ///
-    unsafe fn value_at(&self, index: usize) -> usize {
+    unsafe fn value_at(&self, index: usize) -> u64 {
         *self.zigzag.get_unchecked(index)
     }
 
@@ -154,6 +188,12 @@ impl<const N: usize> CompressionQueue<N> {
     }
 }
 
+impl<const N: usize> Default for CompressionQueue<N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -247,13 +287,13 @@ mod tests {
         use rand::Rng;
 
         let mut rng = rand::thread_rng();
-        let mut std_queue: VecDeque<usize> = VecDeque::new();
+        let mut std_queue: VecDeque<u64> = VecDeque::new();
         let mut queue: CompressionQueue<10> = CompressionQueue::new();
         for _ in 0..10000 {
             let value = rng.gen::<i32>();
             let zig_zag_value = value.zigzag();
             if rng.gen::<bool>() {
-                std_queue.push_back(zig_zag_value as usize);
+                std_queue.push_back(zig_zag_value);
                 if queue.len() == 16 {
                     assert_eq!(std_queue.pop_front(), queue.pop());
                 }
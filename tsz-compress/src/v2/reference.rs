@@ -0,0 +1,351 @@
+use crate::prelude::*;
+use crate::v2::consts::headers;
+use crate::v2::decode::{read_value_bits, BitUnpacker};
+use crate::v2::encode::{write_value_bits, BitPacker};
+use crate::v2::halfvec::{HalfVec, HalfWord};
+use crate::v2::validity::{read_run, run_nibbles, write_run};
+use alloc::vec::Vec;
+
+///
+/// The frame of reference of an integer column: the minimum and maximum of the values in the frame,
+/// from which each value is bit-packed at the fewest bits that span them.
+///
+/// After the tag, the column holds the bit-width in a byte, the minimum in the bit-width of the column,
+/// the number of values as a nibble-aligned VLQ, and each value less the minimum packed at the bit-width.
+///
+/// Noisy columns whose zigzag deltas are wider than the spread of the values are shorter this way.
+/// Only the bounds are tracked while compressing, and the values are recovered from the deltas
+/// of the column when the frame is finished.
+///
+#[derive(Debug, Clone)]
+pub struct ReferenceColumn {
+    buffer: HalfVec,
+    min: i128,
+    max: i128,
+    count: usize,
+    bits: usize,
+    start_of_column: bool,
+}
+
+impl ReferenceColumn {
+    ///
+    /// Creates an empty frame of reference for a column of the bit-width,
+    /// which starts with the start of column tag unless it follows a validity stream or grid.
+    ///
+    pub fn new(bits: usize, start_of_column: bool) -> Self {
+        let mut reference = Self {
+            buffer: HalfVec::new(16),
+            min: i128::MAX,
+            max: i128::MIN,
+            count: 0,
+            bits,
+            start_of_column,
+        };
+        reference.clear();
+        reference
+    }
+
+    ///
+    /// Records the next value of the column.
+    ///
+    #[inline(always)]
+    pub fn push(&mut self, value: i128) {
+        self.min = self.min.min(value);
+        self.max = self.max.max(value);
+        self.count += 1;
+    }
+
+    /// The fewest bits that span the values of the frame.
+    fn width(&self) -> usize {
+        (u128::BITS - (self.max.wrapping_sub(self.min) as u128).leading_zeros()) as usize
+    }
+
+    ///
+    /// The number of nibbles of the column once finished, or `None` if there are no values.
+    ///
+    pub fn finished_nibbles(&self) -> Option<usize> {
+        if self.count == 0 {
+            return None;
+        }
+        let value_nibbles = (self.count * self.width()).div_ceil(4);
+        Some(
+            self.start_of_column as usize
+                + 3
+                + self.bits / 4
+                + run_nibbles(self.count as u64)
+                + value_nibbles,
+        )
+    }
+
+    ///
+    /// Whether the packed values are shorter than the deltas of the column, which are preferred on ties.
+    ///
+    pub fn is_shorter(&self, nibbles: usize) -> bool {
+        self.finished_nibbles()
+            .is_some_and(|reference_nibbles| reference_nibbles < nibbles)
+    }
+
+    ///
+    /// Packs the values of the finished deltas of the column, if that is shorter.
+    ///
+    pub fn flush<T: ColumnValue<i128> + Into<i128>>(&mut self, column: &HalfVec) {
+        if !self.is_shorter(column.len()) {
+            return;
+        }
+
+        // Recover the values from the deltas
+        let mut bytes = Vec::new();
+        HalfVec::finish(&mut bytes, [column].into_iter());
+        let mut iter = HalfIter::new(&bytes);
+        if self.start_of_column {
+            iter.next();
+        }
+        let Ok((values, _)) = ColumnCursor::<T>::new(&mut iter) else {
+            debug_assert!(false, "The deltas of the column are invalid");
+            return;
+        };
+
+        let width = self.width();
+        self.buffer
+            .push(HalfWord::Half(headers::FRAME_OF_REFERENCE));
+        self.buffer.push(HalfWord::Byte(width as u8));
+        write_value_bits(&mut self.buffer, self.min, self.bits);
+        write_run(&mut self.buffer, self.count as u64);
+
+        // Pack the values less the minimum, padding the last nibble with zeros
+        let mut packer = BitPacker::default();
+        for value in values.map_while(Result::ok) {
+            let offset = value.into().wrapping_sub(self.min) as u64;
+            packer.push(&mut self.buffer, offset, width);
+        }
+        packer.finish(&mut self.buffer);
+    }
+
+    ///
+    /// The nibbles of the column.
+    ///
+    pub fn buffer(&self) -> &HalfVec {
+        &self.buffer
+    }
+
+    ///
+    /// Clears the frame of reference for the next frame.
+    ///
+    pub fn clear(&mut self) {
+        self.buffer.clear();
+        if self.start_of_column {
+            self.buffer.push(HalfWord::Half(headers::START_OF_COLUMN));
+        }
+        self.min = i128::MAX;
+        self.max = i128::MIN;
+        self.count = 0;
+    }
+}
+
+///
+/// A reader of the values of a frame of reference column.
+///
+#[derive(Debug, Clone)]
+pub(crate) struct ReferenceReader<'a> {
+    values: BitUnpacker<'a>,
+    min: i128,
+    width: usize,
+    remaining: usize,
+}
+
+impl<'a> ReferenceReader<'a> {
+    ///
+    /// Reads the frame of reference of a column of the bit-width after the frame of reference tag.
+    ///
+    /// The iterator is advanced past the column to the start of the next column.
+    ///
+    pub(crate) fn new(iter: &mut HalfIter<'a>, bits: usize) -> Result<Self, CodingError> {
+        let width = read_value_bits(iter, 8)? as usize;
+        if width > bits {
+            return Err(CodingError::InvalidBits);
+        }
+        let min = read_value_bits(iter, bits)?;
+        let count = read_run(iter)?;
+        if count == 0 || count > u32::MAX as u64 {
+            return Err(CodingError::InvalidBits);
+        }
+
+        // Skip the packed values
+        let reader = Self {
+            values: BitUnpacker::new(iter.clone()),
+            min,
+            width,
+            remaining: count as usize,
+        };
+        let value_nibbles = (count * width as u64).div_ceil(4);
+        if value_nibbles > 0 {
+            let skip =
+                usize::try_from(value_nibbles - 1).map_err(|_| CodingError::NotEnoughBits)?;
+            iter.nth(skip).ok_or(CodingError::NotEnoughBits)?;
+        }
+        match iter.next() {
+            None | Some(headers::START_OF_COLUMN) => Ok(reader),
+            Some(_) => Err(CodingError::InvalidColumnTag),
+        }
+    }
+
+    /// The number of values that remain to be read.
+    pub(crate) fn remaining(&self) -> usize {
+        self.remaining
+    }
+
+    /// Skips the next `n` values.
+    pub(crate) fn skip_rows(&mut self, n: usize) -> Result<(), CodingError> {
+        self.remaining = self
+            .remaining
+            .checked_sub(n)
+            .ok_or(CodingError::NotEnoughBits)?;
+        let bits = n
+            .checked_mul(self.width)
+            .ok_or(CodingError::NotEnoughBits)?;
+        self.values.skip(bits)
+    }
+}
+
+impl Iterator for ReferenceReader<'_> {
+    type Item = Result<i128, CodingError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.remaining == 0 {
+            return None;
+        }
+        self.remaining -= 1;
+        match self.values.read(self.width) {
+            Ok(offset) => Some(Ok(self.min.wrapping_add(offset as i128))),
+            Err(err) => {
+                self.remaining = 0;
+                Some(Err(err))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::v2::halfvec::HalfVec;
+    use rand::Rng;
+
+    /// Encodes the values with the deltas of a column, as the compressor does.
+    fn deltas(values: &[i64]) -> HalfVec {
+        let mut buf = HalfVec::new(values.len());
+        let mut queue: CompressionQueue<10> = CompressionQueue::new();
+        buf.push(HalfWord::Half(headers::START_OF_COLUMN));
+        buf.push(HalfWord::Half(headers::FIRST_ROW));
+        write_signed_vlq(&mut buf, values[0] as i128);
+        if values.len() > 1 {
+            buf.push(HalfWord::Half(headers::SECOND_ROW));
+            write_signed_vlq(&mut buf, values[1] as i128 - values[0] as i128);
+        }
+        for window in values.windows(2).skip(1) {
+            queue.push(window[1].wrapping_sub(window[0]));
+            if queue.is_full() {
+                queue.emit_delta_bits(&mut buf);
+            }
+        }
+        while !queue.is_empty() || queue.zero_run() > 0 {
+            queue.flush_delta_bits(&mut buf);
+        }
+        buf
+    }
+
+    fn finish(reference: &mut ReferenceColumn, values: &[i64]) -> Vec<u8> {
+        for value in values {
+            reference.push(*value as i128);
+        }
+        let column = deltas(values);
+        let nibbles = reference.finished_nibbles().unwrap();
+        assert!(reference.is_shorter(column.len()));
+        reference.flush::<i64>(&column);
+        assert_eq!(reference.buffer().len(), nibbles);
+        let mut bytes = Vec::new();
+        HalfVec::finish(&mut bytes, [reference.buffer()].into_iter());
+        bytes
+    }
+
+    #[test]
+    fn widths() {
+        let mut rng = rand::thread_rng();
+        for width in [1, 3, 7, 13, 32, 62] {
+            let values = (0..101)
+                .map(|i| match i {
+                    0 => -5_000,
+                    1 => -5_000 + (1 << width) - 1,
+                    _ => -5_000 + rng.gen_range(0..1 << width),
+                })
+                .collect::<Vec<i64>>();
+            let mut reference = ReferenceColumn::new(64, true);
+            let bytes = finish(&mut reference, &values);
+
+            let mut iter = HalfIter::new(&bytes);
+            assert_eq!(iter.next(), Some(headers::START_OF_COLUMN));
+            let mut output = Vec::new();
+            decode_i64_rows(&mut iter, values.len(), &mut output).unwrap();
+            assert_eq!(output, values);
+
+            let mut iter = HalfIter::new(&bytes);
+            assert_eq!(iter.next(), Some(headers::START_OF_COLUMN));
+            let (cursor, rows) = ColumnCursor::<i64>::new(&mut iter).unwrap();
+            assert_eq!(rows, values.len());
+            assert_eq!(cursor.collect::<Result<Vec<_>, _>>().unwrap(), values);
+        }
+    }
+
+    #[test]
+    fn full_width() {
+        let values = [i64::MIN, 0, i64::MAX, -1].repeat(10);
+        let mut reference = ReferenceColumn::new(64, true);
+
+        // The zigzag deltas of the extremes are wider than the values
+        let bytes = finish(&mut reference, &values);
+        assert_eq!(reference.width(), 64);
+        let mut iter = HalfIter::new(&bytes);
+        assert_eq!(iter.next(), Some(headers::START_OF_COLUMN));
+        let mut output = Vec::new();
+        decode_i64_rows(&mut iter, values.len(), &mut output).unwrap();
+        assert_eq!(output, values);
+    }
+
+    #[test]
+    fn invalid_width() {
+        let values = (0..100)
+            .map(|i| if i % 2 == 0 { 0 } else { 255 })
+            .collect::<Vec<i64>>();
+        let mut reference = ReferenceColumn::new(64, true);
+        let mut bytes = finish(&mut reference, &values);
+
+        // The width byte follows the start of column and frame of reference tags
+        bytes[1] = 0x41;
+        let mut iter = HalfIter::new(&bytes);
+        assert_eq!(iter.next(), Some(headers::START_OF_COLUMN));
+        let mut output = Vec::new();
+        assert!(matches!(
+            decode_i64_rows(&mut iter, values.len(), &mut output),
+            Err(CodingError::InvalidBits)
+        ));
+    }
+
+    #[test]
+    fn too_many_values() {
+        let values = (0..100)
+            .map(|i| if i % 2 == 0 { 0 } else { 255 })
+            .collect::<Vec<i64>>();
+        let mut reference = ReferenceColumn::new(64, true);
+        let bytes = finish(&mut reference, &values);
+
+        // More values than the rows of the column are invalid
+        let mut iter = HalfIter::new(&bytes);
+        assert_eq!(iter.next(), Some(headers::START_OF_COLUMN));
+        let mut output = Vec::new();
+        assert!(matches!(
+            decode_i64_rows(&mut iter, values.len() - 1, &mut output),
+            Err(CodingError::InvalidBits)
+        ));
+        assert!(output.is_empty());
+    }
+}
@@ -0,0 +1,483 @@
+use crate::prelude::*;
+use crate::v2::halfvec::{HalfVec, HalfWord};
+use alloc::vec::Vec;
+
+///
+/// A cursor that can be placed from a checkpoint of a seek index without decoding the values before it.
+///
+/// Offsets in a checkpoint are nibbles from the start of the body following the row count.
+///
+pub trait SeekCursor<'a>: Sized {
+    ///
+    /// Places a cursor over the body from the next checkpoint of the iterator,
+    /// before the value of `row` of the `rows` rows of the frame.
+    ///
+    fn read_checkpoint(
+        body: &'a [u8],
+        checkpoints: &mut HalfIter<'_>,
+        widths: CheckpointWidths,
+        row: usize,
+        rows: usize,
+    ) -> Result<Self, CodingError>;
+}
+
+/// The bytes of the count, the interval and the length trailing a seek index.
+const TRAILER_LEN: usize = 12;
+
+///
+/// The nibbles of the offsets and counts of the checkpoints of a frame: as many as
+/// the nibble offsets into the row count and columns need, and as many as the row count needs.
+///
+/// Both follow from the frame, so every checkpoint of a column has the same number of nibbles.
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CheckpointWidths {
+    offset: usize,
+    count: usize,
+}
+
+impl CheckpointWidths {
+    ///
+    /// The widths for a frame of `rows` rows whose row count and columns take `body_len` bytes.
+    ///
+    pub fn new(body_len: usize, rows: usize) -> Self {
+        Self {
+            offset: hex_digits(2 * body_len),
+            count: hex_digits(rows),
+        }
+    }
+
+    ///
+    /// The nibbles of the checkpoint of a column of `T`: the nibble offset of the column,
+    /// the values before the row, the nibble offset of the next word and the last two values,
+    /// or the last value and the window of the last XOR word.
+    ///
+    pub fn column<T: ColumnValue<i128>>(&self) -> usize {
+        let nibbles = core::mem::size_of::<T>() * 2;
+        2 * self.offset + self.count + nibbles + if T::XOR { 2 } else { nibbles }
+    }
+
+    ///
+    /// The nibbles of the checkpoint of an `Option` column of `T`: whether the column is in the frame,
+    /// the nibble offset of the run of the row, the rows of the run before the row and whether the row
+    /// has a value, followed by the checkpoint of the present values.
+    ///
+    pub fn nullable<T: ColumnValue<i128>>(&self) -> usize {
+        2 + self.offset + self.count + self.column::<T>()
+    }
+
+    ///
+    /// The nibbles of the checkpoint of a timestamp or dictionary column of `T`:
+    /// the nibble offset of the column, followed by the checkpoint of the residuals or values.
+    ///
+    pub fn prefixed<T: ColumnValue<i128>>(&self) -> usize {
+        self.offset + self.column::<T>()
+    }
+}
+
+/// The hexadecimal digits of a value, at least one.
+fn hex_digits(value: usize) -> usize {
+    let bits = (usize::BITS - value.leading_zeros()) as usize;
+    bits.div_ceil(4).max(1)
+}
+
+///
+/// The state of a column before the row of a checkpoint, recorded by the compressor
+/// once the words of the column before the row are written.
+///
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ColumnCheckpoint {
+    /// The values of the column before the row.
+    pub taken: usize,
+    /// The nibbles of the delta words of the column before the row.
+    pub delta: usize,
+    /// The nibbles of the delta-delta words of the column before the row.
+    pub delta_delta: usize,
+    /// The bits of the last value of the column.
+    pub value: i128,
+    /// The bits of the value before the last, or the window of the last XOR word.
+    pub prev: i128,
+    /// The nibbles of the validity runs of an `Option` column before the run of the row.
+    pub validity: usize,
+    /// The rows of the run of the row before the row.
+    pub consumed: u64,
+    /// Whether the row has a value.
+    pub present: bool,
+}
+
+///
+/// Writes a seek index with a checkpoint of each column every `every` rows, starting at the first row.
+///
+/// The index is the nibbles of the checkpoints padded to a byte, the big-endian 32-bit nibble
+/// offset of each checkpoint, then the count of checkpoints, the interval in rows and the bytes of
+/// the index including this trailer as big-endian 32-bit integers.
+///
+/// Each checkpoint of a column has a fixed number of nibbles, so the length of an index
+/// follows from the count of its checkpoints.
+///
+#[derive(Debug)]
+pub struct SeekIndexWriter {
+    checkpoints: HalfVec,
+    offsets: Vec<u32>,
+    every: usize,
+    widths: CheckpointWidths,
+}
+
+impl SeekIndexWriter {
+    ///
+    /// Initializes an index with a checkpoint every `every` rows, of offsets and counts of the given widths.
+    ///
+    pub fn new(every: usize, widths: CheckpointWidths) -> Self {
+        Self {
+            checkpoints: HalfVec::new(64),
+            offsets: Vec::new(),
+            every,
+            widths,
+        }
+    }
+
+    ///
+    /// The bytes of an index of `count` checkpoints of `nibbles` each.
+    ///
+    pub fn finished_len(count: usize, nibbles: usize) -> usize {
+        (count * nibbles).div_ceil(2) + 4 * count + TRAILER_LEN
+    }
+
+    ///
+    /// Starts the checkpoint of the next row, to be followed by the state of each column.
+    ///
+    pub fn start_checkpoint(&mut self) {
+        self.offsets.push(self.checkpoints.len() as u32);
+    }
+
+    ///
+    /// Appends the checkpoint of a column of `T` at nibble `start` of the body, with its next word at nibble `word`.
+    ///
+    pub fn column<T: ColumnValue<i128>>(
+        &mut self,
+        start: usize,
+        word: usize,
+        checkpoint: &ColumnCheckpoint,
+    ) {
+        let CheckpointWidths { offset, count } = self.widths;
+        let nibbles = core::mem::size_of::<T>() * 2;
+        write_fixed(&mut self.checkpoints, start as u128, offset);
+        write_fixed(&mut self.checkpoints, checkpoint.taken as u128, count);
+        write_fixed(&mut self.checkpoints, word as u128, offset);
+        write_fixed(&mut self.checkpoints, checkpoint.value as u128, nibbles);
+        let prev_nibbles = if T::XOR { 2 } else { nibbles };
+        write_fixed(&mut self.checkpoints, checkpoint.prev as u128, prev_nibbles);
+    }
+
+    ///
+    /// Appends the checkpoint of an `Option` column of `T` with its validity runs at nibble `validity` of the body,
+    /// or of a column that is not in the frame.
+    ///
+    pub fn nullable<T: ColumnValue<i128>>(
+        &mut self,
+        validity: Option<usize>,
+        start: usize,
+        word: usize,
+        checkpoint: &ColumnCheckpoint,
+    ) {
+        let Some(validity) = validity else {
+            for _ in 0..self.widths.nullable::<T>() {
+                self.checkpoints.push(HalfWord::Half(0));
+            }
+            return;
+        };
+        let CheckpointWidths { offset, count } = self.widths;
+        self.checkpoints.push(HalfWord::Half(1));
+        write_fixed(
+            &mut self.checkpoints,
+            (validity + checkpoint.validity) as u128,
+            offset,
+        );
+        write_fixed(&mut self.checkpoints, checkpoint.consumed as u128, count);
+        self.checkpoints.push(HalfWord::Half(checkpoint.present as u8));
+        self.column::<T>(start, word, checkpoint);
+    }
+
+    ///
+    /// Appends the checkpoint of a timestamp or dictionary column of `T` at nibble `prefix` of the body.
+    ///
+    pub fn prefixed<T: ColumnValue<i128>>(
+        &mut self,
+        prefix: usize,
+        start: usize,
+        word: usize,
+        checkpoint: &ColumnCheckpoint,
+    ) {
+        write_fixed(&mut self.checkpoints, prefix as u128, self.widths.offset);
+        self.column::<T>(start, word, checkpoint);
+    }
+
+    ///
+    /// Finishes the index, returning its bytes.
+    ///
+    pub fn finish(self) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        HalfVec::finish(&mut bytes, [&self.checkpoints].into_iter());
+        for offset in &self.offsets {
+            bytes.extend_from_slice(&offset.to_be_bytes());
+        }
+        bytes.extend_from_slice(&(self.offsets.len() as u32).to_be_bytes());
+        bytes.extend_from_slice(&(self.every as u32).to_be_bytes());
+        let len = (bytes.len() + 4) as u32;
+        bytes.extend_from_slice(&len.to_be_bytes());
+        bytes
+    }
+}
+
+/// Writes the low `nibbles` nibbles of a value, most significant first.
+fn write_fixed(buf: &mut HalfVec, value: u128, nibbles: usize) {
+    for i in (0..nibbles).rev() {
+        buf.push(HalfWord::Half((value >> (4 * i)) as u8 & 0xf));
+    }
+}
+
+///
+/// Reads a value of `nibbles` nibbles of a checkpoint, most significant first.
+///
+pub(crate) fn read_fixed(
+    checkpoints: &mut HalfIter<'_>,
+    nibbles: usize,
+) -> Result<u128, CodingError> {
+    let mut value = 0;
+    for _ in 0..nibbles {
+        let half = checkpoints.next().ok_or(CodingError::NotEnoughBits)?;
+        value = (value << 4) | half as u128;
+    }
+    Ok(value)
+}
+
+///
+/// Reads a nibble offset of a checkpoint.
+///
+pub(crate) fn read_offset(
+    checkpoints: &mut HalfIter<'_>,
+    widths: CheckpointWidths,
+) -> Result<usize, CodingError> {
+    usize::try_from(read_fixed(checkpoints, widths.offset)?).map_err(|_| CodingError::InvalidBits)
+}
+
+///
+/// Reads a count of values or rows of a checkpoint.
+///
+pub(crate) fn read_count(
+    checkpoints: &mut HalfIter<'_>,
+    widths: CheckpointWidths,
+) -> Result<usize, CodingError> {
+    usize::try_from(read_fixed(checkpoints, widths.count)?).map_err(|_| CodingError::InvalidBits)
+}
+
+///
+/// Steps over `nibbles` nibbles of a checkpoint of a column without a cursor.
+///
+pub(crate) fn skip_checkpoint(
+    checkpoints: &mut HalfIter<'_>,
+    nibbles: usize,
+) -> Result<(), CodingError> {
+    for _ in 0..nibbles {
+        checkpoints.next().ok_or(CodingError::NotEnoughBits)?;
+    }
+    Ok(())
+}
+
+///
+/// The checkpoints of a seek index trailing the columns of a frame.
+///
+#[derive(Debug, Clone, Copy)]
+pub struct SeekIndex<'a> {
+    checkpoints: &'a [u8],
+    offsets: &'a [u8],
+    every: usize,
+}
+
+impl<'a> SeekIndex<'a> {
+    ///
+    /// The rows between checkpoints.
+    ///
+    pub fn every(&self) -> usize {
+        self.every
+    }
+
+    ///
+    /// The number of checkpoints.
+    ///
+    pub fn len(&self) -> usize {
+        self.offsets.len() / 4
+    }
+
+    ///
+    /// Returns true if the index has no checkpoints.
+    ///
+    pub fn is_empty(&self) -> bool {
+        self.offsets.is_empty()
+    }
+
+    ///
+    /// The nibbles of the checkpoint of row `i * every`.
+    ///
+    pub fn checkpoint(&self, i: usize) -> Result<HalfIter<'a>, CodingError> {
+        let offset = self
+            .offsets
+            .get(4 * i..4 * i + 4)
+            .ok_or(CodingError::InvalidRowCount(i * self.every))?;
+        let offset = u32::from_be_bytes([offset[0], offset[1], offset[2], offset[3]]) as usize;
+        if offset >= self.checkpoints.len() * 2 {
+            return Err(CodingError::InvalidBits);
+        }
+        Ok(HalfIter::at(self.checkpoints, offset))
+    }
+}
+
+///
+/// Splits the seek index from the end of the body of a frame,
+/// returning the row count and columns preceding it.
+///
+pub fn split_seek_index(bytes: &[u8]) -> Result<(&[u8], SeekIndex<'_>), CodingError> {
+    if bytes.len() < TRAILER_LEN {
+        return Err(CodingError::Empty);
+    }
+    let trailer = &bytes[bytes.len() - TRAILER_LEN..];
+    let read = |i: usize| {
+        u32::from_be_bytes([trailer[i], trailer[i + 1], trailer[i + 2], trailer[i + 3]]) as usize
+    };
+    let (count, every, len) = (read(0), read(4), read(8));
+    let offsets_len = count.checked_mul(4).ok_or(CodingError::InvalidBits)?;
+    if every == 0 || len > bytes.len() || len < TRAILER_LEN + offsets_len {
+        return Err(CodingError::InvalidBits);
+    }
+    let (columns, index) = bytes.split_at(bytes.len() - len);
+    let index = &index[..len - TRAILER_LEN];
+    let (checkpoints, offsets) = index.split_at(index.len() - offsets_len);
+    Ok((
+        columns,
+        SeekIndex {
+            checkpoints,
+            offsets,
+            every,
+        },
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::v2::halfvec::HalfWord;
+
+    #[test]
+    fn index_layout() {
+        let mut writer = SeekIndexWriter::new(100, CheckpointWidths::new(0, 0));
+        writer.start_checkpoint();
+        writer.checkpoints.push(HalfWord::Half(0x3));
+        writer.start_checkpoint();
+        writer.checkpoints.push(HalfWord::Byte(0x45));
+        let index = writer.finish();
+        assert_eq!(
+            index,
+            [0x34, 0x59, 0, 0, 0, 0, 0, 0, 0, 1, 0, 0, 0, 2, 0, 0, 0, 100, 0, 0, 0, 22]
+        );
+
+        // The columns precede the index
+        let mut frame = vec![9, 9, 9];
+        frame.extend(&index);
+        let (columns, index) = split_seek_index(&frame).unwrap();
+        assert_eq!(columns, [9, 9, 9]);
+        assert_eq!(index.every(), 100);
+        assert_eq!(index.len(), 2);
+        assert_eq!(
+            index.checkpoint(0).unwrap().collect::<Vec<_>>(),
+            [3, 4, 5, 9]
+        );
+        assert_eq!(index.checkpoint(1).unwrap().collect::<Vec<_>>(), [4, 5, 9]);
+        assert!(matches!(
+            index.checkpoint(2),
+            Err(CodingError::InvalidRowCount(200))
+        ));
+    }
+
+    #[test]
+    fn fixed_width_checkpoints() {
+        let checkpoint = ColumnCheckpoint {
+            taken: 5,
+            value: -1,
+            prev: 0x7f,
+            ..Default::default()
+        };
+
+        // Offsets into 300 bytes take 3 nibbles, and counts of 16 rows take 2 nibbles
+        let widths = CheckpointWidths::new(300, 16);
+        let mut writer = SeekIndexWriter::new(4, widths);
+        for _ in 0..3 {
+            writer.start_checkpoint();
+            writer.column::<i8>(1, 0x12, &checkpoint);
+            writer.nullable::<f32>(None, 0, 0, &checkpoint);
+        }
+        let index = writer.finish();
+
+        // The length of the index follows from the count of checkpoints
+        let nibbles = widths.column::<i8>() + widths.nullable::<f32>();
+        assert_eq!(nibbles, 12 + 25);
+        assert_eq!(index.len(), SeekIndexWriter::finished_len(3, nibbles));
+
+        // The values are truncated to the bit-width of the column
+        let (_, index) = split_seek_index(&index).unwrap();
+        let mut iter = index.checkpoint(1).unwrap();
+        assert_eq!(read_offset(&mut iter, widths).unwrap(), 1);
+        assert_eq!(read_count(&mut iter, widths).unwrap(), 5);
+        assert_eq!(read_offset(&mut iter, widths).unwrap(), 0x12);
+        assert_eq!(read_fixed(&mut iter, 2).unwrap(), 0xff);
+        assert_eq!(read_fixed(&mut iter, 2).unwrap(), 0x7f);
+
+        // An Option column that is not in the frame is all zeros
+        assert_eq!(read_fixed(&mut iter, 25).unwrap(), 0);
+        assert_eq!(read_offset(&mut iter, widths).unwrap(), 1);
+    }
+
+    #[test]
+    fn checkpoint_widths() {
+        assert_eq!(CheckpointWidths::new(0, 0), CheckpointWidths { offset: 1, count: 1 });
+        assert_eq!(CheckpointWidths::new(8, 15), CheckpointWidths { offset: 2, count: 1 });
+        assert_eq!(CheckpointWidths::new(7, 16), CheckpointWidths { offset: 1, count: 2 });
+        assert_eq!(
+            CheckpointWidths::new(1 << 31, u32::MAX as usize),
+            CheckpointWidths { offset: 9, count: 8 }
+        );
+    }
+
+    #[test]
+    fn invalid_index() {
+        assert!(matches!(
+            split_seek_index(&[0; 11]),
+            Err(CodingError::Empty)
+        ));
+
+        // The length exceeds the frame
+        let frame = [0, 0, 0, 0, 0, 0, 0, 1, 0, 0, 0, 13];
+        assert!(matches!(
+            split_seek_index(&frame),
+            Err(CodingError::InvalidBits)
+        ));
+
+        // The offsets exceed the length
+        let frame = [0, 0, 0, 0, 0, 0, 0, 1, 0, 0, 0, 1, 0, 0, 0, 12];
+        assert!(matches!(
+            split_seek_index(&frame),
+            Err(CodingError::InvalidBits)
+        ));
+
+        // No rows between checkpoints
+        let frame = [0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 12];
+        assert!(matches!(
+            split_seek_index(&frame),
+            Err(CodingError::InvalidBits)
+        ));
+
+        // An offset past the checkpoints
+        let frame = [0x12, 0, 0, 0, 2, 0, 0, 0, 1, 0, 0, 0, 1, 0, 0, 0, 17];
+        let (_, index) = split_seek_index(&frame).unwrap();
+        assert!(matches!(index.checkpoint(0), Err(CodingError::InvalidBits)));
+    }
+}
@@ -0,0 +1,128 @@
+use crate::prelude::*;
+use crate::v2::consts::headers;
+use crate::v2::halfvec::HalfVec;
+use crate::v2::validity::{read_run, run_nibbles, write_run};
+
+///
+/// The number of nibbles of the length of a column in the column table.
+///
+pub fn column_table_nibbles(len: usize) -> usize {
+    run_nibbles(len as u64)
+}
+
+///
+/// Writes the length in nibbles of each column, so that a decoder may jump to any column.
+///
+/// A column that is omitted from the frame has a length of 0.
+///
+pub fn write_column_table(buf: &mut HalfVec, lens: &[usize]) {
+    for len in lens {
+        write_run(buf, *len as u64);
+    }
+}
+
+///
+/// Reads the column table, returning the nibble offset of each column from the start of the bytes
+/// of the iterator and its length in nibbles. The columns follow the table.
+///
+pub fn read_column_table<const N: usize>(
+    iter: &mut HalfIter<'_>,
+) -> Result<[(usize, usize); N], CodingError> {
+    let mut lens = [0; N];
+    for len in &mut lens {
+        *len = usize::try_from(read_run(iter)?).map_err(|_| CodingError::InvalidBits)?;
+    }
+
+    let mut offset = iter.offset();
+    let mut columns = [(0, 0); N];
+    for (column, len) in columns.iter_mut().zip(lens) {
+        *column = (offset, len);
+        offset = offset.checked_add(len).ok_or(CodingError::InvalidBits)?;
+    }
+    Ok(columns)
+}
+
+///
+/// An iterator over the column of the offset and length read from the column table, after the start of column tag.
+///
+/// The bytes are cut at the end of the column, so that a decoder reads no further than the column and the
+/// start of the next column.
+///
+pub fn column_at(bytes: &[u8], (offset, len): (usize, usize)) -> Result<HalfIter<'_>, CodingError> {
+    let end = offset.checked_add(len).ok_or(CodingError::InvalidBits)?;
+    let bytes = bytes
+        .get(..end.div_ceil(2))
+        .ok_or(CodingError::NotEnoughBits)?;
+    let mut iter = HalfIter::at(bytes, offset);
+    if len == 0 || iter.next() != Some(headers::START_OF_COLUMN) {
+        return Err(CodingError::InvalidColumnTag);
+    }
+    Ok(iter)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::v2::halfvec::HalfWord;
+    use alloc::vec::Vec;
+
+    #[test]
+    fn table_layout() {
+        // Two columns of 3 and 9 nibbles and an omitted column follow a table of 4 nibbles
+        let mut buf = HalfVec::new(16);
+        write_column_table(&mut buf, &[3, 9, 0]);
+        assert_eq!(buf.len(), 4);
+        assert_eq!(column_table_nibbles(3), 1);
+        assert_eq!(column_table_nibbles(9), 2);
+        assert_eq!(column_table_nibbles(0), 1);
+        for half in [9, 1, 2, 9, 1, 2, 3, 4, 5, 6, 7, 8] {
+            buf.push(HalfWord::Half(half));
+        }
+        let mut bytes = Vec::new();
+        HalfVec::finish(&mut bytes, [&buf].into_iter());
+
+        let mut iter = HalfIter::new(&bytes);
+        let columns = read_column_table::<3>(&mut iter).unwrap();
+        assert_eq!(columns, [(4, 3), (7, 9), (16, 0)]);
+
+        // Each column ends at the start of the next column
+        let column = column_at(&bytes, columns[0]).unwrap().collect::<Vec<_>>();
+        assert_eq!(column, [1, 2, 9]);
+        let column = column_at(&bytes, columns[1]).unwrap().collect::<Vec<_>>();
+        assert_eq!(column, [1, 2, 3, 4, 5, 6, 7, 8]);
+        assert!(matches!(
+            column_at(&bytes, columns[2]),
+            Err(CodingError::InvalidColumnTag)
+        ));
+    }
+
+    #[test]
+    fn invalid_table() {
+        let mut buf = HalfVec::new(16);
+        write_column_table(&mut buf, &[3, 100]);
+        buf.push(HalfWord::Half(9));
+        let mut bytes = Vec::new();
+        HalfVec::finish(&mut bytes, [&buf].into_iter());
+
+        // A column past the end of the bytes
+        let mut iter = HalfIter::new(&bytes);
+        let columns = read_column_table::<2>(&mut iter).unwrap();
+        assert!(matches!(
+            column_at(&bytes, columns[1]),
+            Err(CodingError::NotEnoughBits)
+        ));
+
+        // A column that does not start at a column tag
+        assert!(matches!(
+            column_at(&bytes, (0, 2)),
+            Err(CodingError::InvalidColumnTag)
+        ));
+
+        // A truncated table
+        let mut iter = HalfIter::new(&bytes[..1]);
+        assert!(matches!(
+            read_column_table::<3>(&mut iter),
+            Err(CodingError::NotEnoughBits)
+        ));
+    }
+}
@@ -0,0 +1,311 @@
+use crate::prelude::*;
+use crate::v2::consts::headers;
+use crate::v2::halfvec::{HalfVec, HalfWord};
+use alloc::vec::Vec;
+
+///
+/// The validity stream of an `Option` column: alternating run lengths of present
+/// and missing values, starting with a (possibly empty) run of present values.
+///
+/// Each run length is a nibble-aligned VLQ of 3 bits per nibble, most significant
+/// group first, with the top bit of the nibble set on all but the last nibble.
+///
+/// The stream follows the start of column tag and ends once the runs cover the row count
+/// of the frame, followed by the values of the present rows encoded as any other column.
+///
+#[derive(Debug, Clone)]
+pub struct ValidityRuns {
+    runs: HalfVec,
+    present: bool,
+    run: u64,
+    present_count: usize,
+}
+
+impl ValidityRuns {
+    ///
+    /// Creates an empty validity stream.
+    ///
+    pub fn new(capacity: usize) -> Self {
+        let mut runs = HalfVec::new(capacity);
+        runs.push(HalfWord::Half(headers::START_OF_COLUMN));
+        Self {
+            runs,
+            present: true,
+            run: 0,
+            present_count: 0,
+        }
+    }
+
+    ///
+    /// Records whether the next row has a value.
+    ///
+    #[inline(always)]
+    pub fn push(&mut self, valid: bool) {
+        if valid != self.present {
+            write_run(&mut self.runs, self.run);
+            self.present = valid;
+            self.run = 0;
+        }
+        self.run += 1;
+        self.present_count += valid as usize;
+    }
+
+    ///
+    /// The number of rows with a value.
+    ///
+    pub fn present_count(&self) -> usize {
+        self.present_count
+    }
+
+    ///
+    /// The number of nibbles of the stream once finished, including the start of column tag.
+    ///
+    pub fn finished_nibbles(&self) -> usize {
+        self.runs.len() + run_nibbles(self.run)
+    }
+
+    ///
+    /// The nibble offset of the run of the next row, whether it has a value, and the rows of that run before it.
+    ///
+    pub fn checkpoint(&self, valid: bool) -> (usize, u64) {
+        if valid == self.present {
+            (self.runs.len(), self.run)
+        } else {
+            (self.runs.len() + run_nibbles(self.run), 0)
+        }
+    }
+
+    ///
+    /// Writes the pending run, finishing the stream.
+    ///
+    pub fn flush(&mut self) {
+        write_run(&mut self.runs, self.run);
+        self.run = 0;
+    }
+
+    ///
+    /// The nibbles of the stream, including the start of column tag.
+    ///
+    pub fn buffer(&self) -> &HalfVec {
+        &self.runs
+    }
+
+    ///
+    /// Clears the stream for the next frame.
+    ///
+    pub fn clear(&mut self) {
+        self.runs.clear();
+        self.runs.push(HalfWord::Half(headers::START_OF_COLUMN));
+        self.present = true;
+        self.run = 0;
+        self.present_count = 0;
+    }
+}
+
+/// The number of nibbles of a run length.
+pub(crate) fn run_nibbles(run: u64) -> usize {
+    let bits = 64 - run.leading_zeros() as usize;
+    bits.div_ceil(3).max(1)
+}
+
+/// Writes a run length as a nibble-aligned VLQ.
+pub(crate) fn write_run(buf: &mut HalfVec, run: u64) {
+    let nibbles = run_nibbles(run);
+    for i in (0..nibbles).rev() {
+        let more = if i > 0 { 0b1000 } else { 0 };
+        buf.push(HalfWord::Half(more | ((run >> (3 * i)) & 0b111) as u8));
+    }
+}
+
+///
+/// Reads a run length of the validity stream or a run of zeros.
+///
+pub(crate) fn read_run(iter: &mut HalfIter<'_>) -> Result<u64, CodingError> {
+    let mut run: u64 = 0;
+    loop {
+        let half = iter.next().ok_or(CodingError::NotEnoughBits)?;
+        if run > u64::MAX >> 3 {
+            return Err(CodingError::InvalidBits);
+        }
+        run = (run << 3) | (half & 0b111) as u64;
+        if half & 0b1000 == 0 {
+            return Ok(run);
+        }
+    }
+}
+
+///
+/// Writes whether each `Option` column is in the frame, 4 columns per nibble.
+///
+/// A column without any values is omitted from the frame.
+///
+pub fn write_presence(buf: &mut HalfVec, present: &[bool]) {
+    for columns in present.chunks(4) {
+        let mut half = 0;
+        for (i, present) in columns.iter().enumerate() {
+            half |= (*present as u8) << (3 - i);
+        }
+        buf.push(HalfWord::Half(half));
+    }
+}
+
+///
+/// Reads whether each `Option` column is in the frame.
+///
+pub fn read_presence<const N: usize>(iter: &mut HalfIter<'_>) -> Result<[bool; N], CodingError> {
+    let mut present = [false; N];
+    for columns in present.chunks_mut(4) {
+        let half = iter.next().ok_or(CodingError::NotEnoughBits)?;
+        for (i, present) in columns.iter_mut().enumerate() {
+            *present = half & (1 << (3 - i)) != 0;
+        }
+    }
+    Ok(present)
+}
+
+///
+/// The most rows of a frame in which every decoded column is an omitted `Option` column.
+///
+/// Such a frame holds no values to check its row count against, so a larger row count
+/// is an `InvalidRowCount` error rather than gigabytes of missing values.
+///
+pub const MAX_OMITTED_ROWS: usize = 1 << 24;
+
+///
+/// Reserves space for `rows` more values of a column, so that a row count too large
+/// to allocate is an error instead of aborting the decoder.
+///
+pub fn reserve_rows<T>(output: &mut Vec<T>, rows: usize) -> Result<(), CodingError> {
+    output
+        .try_reserve(rows)
+        .map_err(|_| CodingError::InvalidRowCount(rows))
+}
+
+///
+/// Extends an `Option` column that is omitted from the frame with `rows` missing values.
+///
+pub fn extend_missing<T: Copy>(
+    output: &mut Vec<Option<T>>,
+    rows: usize,
+) -> Result<(), CodingError> {
+    reserve_rows(output, rows)?;
+    output.resize(output.len() + rows, None);
+    Ok(())
+}
+
+///
+/// Decodes an `Option` column of `rows` rows, after the start of column tag.
+///
+/// The validity stream is read first, then the present values are decoded with `decode`
+/// and interleaved with the missing values.
+///
+pub fn decode_nullable<T: Copy>(
+    iter: &mut HalfIter<'_>,
+    rows: usize,
+    output: &mut Vec<Option<T>>,
+    decode: ColumnDecoder<T>,
+) -> Result<(), CodingError> {
+    // Read the runs until they cover the rows
+    let mut runs = Vec::new();
+    let mut covered = 0;
+    let mut present_count = 0;
+    while covered < rows {
+        let run = read_run(iter)?;
+        if run > (rows - covered) as u64 {
+            return Err(CodingError::InvalidBits);
+        }
+        if runs.len() % 2 == 0 {
+            present_count += run as usize;
+        }
+        covered += run as usize;
+        runs.push(run as usize);
+    }
+
+    // Decode the present values, reserved as they are decoded rather than by the runs
+    let mut values = Vec::new();
+    decode(iter, present_count, &mut values)?;
+    if values.len() != present_count {
+        return Err(CodingError::InvalidBits);
+    }
+
+    // Interleave the present and missing values
+    reserve_rows(output, rows)?;
+    let mut values = values.into_iter();
+    for (i, run) in runs.into_iter().enumerate() {
+        if i % 2 == 0 {
+            output.extend(values.by_ref().take(run).map(Some));
+        } else {
+            output.extend(core::iter::repeat_n(None, run));
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn finish(runs: &mut ValidityRuns) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        let nibbles = runs.finished_nibbles();
+        runs.flush();
+        assert_eq!(runs.buffer().len(), nibbles);
+        HalfVec::finish(&mut bytes, [runs.buffer()].into_iter());
+        bytes
+    }
+
+    #[test]
+    fn run_lengths() {
+        for run in [0, 1, 7, 8, 63, 64, 511, 512, u32::MAX as u64, u64::MAX] {
+            let mut buf = HalfVec::new(32);
+            write_run(&mut buf, run);
+            assert_eq!(buf.len(), run_nibbles(run));
+            let mut bytes = Vec::new();
+            HalfVec::finish(&mut bytes, [&buf].into_iter());
+            assert_eq!(read_run(&mut HalfIter::new(&bytes)).unwrap(), run);
+        }
+    }
+
+    #[test]
+    fn validity_stream() {
+        let valid = [false, false, true, true, true, false, true];
+        let mut runs = ValidityRuns::new(8);
+        for v in valid {
+            runs.push(v);
+        }
+        assert_eq!(runs.present_count(), 4);
+        let bytes = finish(&mut runs);
+
+        // 1001 then the runs 0, 2, 3, 1, 1
+        assert_eq!(bytes, [0x90, 0x23, 0x11]);
+    }
+
+    #[test]
+    fn presence() {
+        let present = [true, false, false, true, true];
+        let mut buf = HalfVec::new(2);
+        write_presence(&mut buf, &present);
+        assert_eq!(buf.len(), 2);
+        let mut bytes = Vec::new();
+        HalfVec::finish(&mut bytes, [&buf].into_iter());
+        assert_eq!(bytes, [0x98]);
+        assert_eq!(
+            read_presence::<5>(&mut HalfIter::new(&bytes)).unwrap(),
+            present
+        );
+    }
+
+    #[test]
+    fn missing_rows() {
+        let mut output: Vec<Option<i32>> = vec![Some(1)];
+        extend_missing(&mut output, 3).unwrap();
+        assert_eq!(output, [Some(1), None, None, None]);
+
+        // Too many rows to allocate are an error
+        assert!(matches!(
+            extend_missing(&mut output, usize::MAX),
+            Err(CodingError::InvalidRowCount(usize::MAX))
+        ));
+        assert_eq!(output.len(), 4);
+    }
+}
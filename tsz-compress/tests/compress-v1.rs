@@ -25,7 +25,7 @@ mod tests {
                 val0: i as i8,
                 val1: i as i16,
                 val2: i as i32,
-                val3: i as i64,
+                val3: i,
             };
             c.compress(row);
         }
@@ -1,4 +1,4 @@
-#![allow(unused)]
+#![allow(unused, clippy::reversed_empty_ranges)]
 use tsz_compress::prelude::*;
 
 extern crate alloc;
@@ -2284,3 +2284,4500 @@ mod test_field_attributes {
         }
     }
 }
+
+mod test_delta_delta {
+
+    use super::*;
+    use rand::Rng;
+
+    #[test]
+    fn test_macro_compress_periodic_timestamps() {
+        mod row {
+            use tsz_compress::prelude::*;
+            #[derive(Debug, Copy, Clone, CompressV2, DecompressV2)]
+            pub struct TestRow {
+                pub ts: i64,
+            }
+
+            pub use compress::TestRowCompressorImpl;
+            pub use decompress::TestRowDecompressorImpl;
+        }
+        use row::*;
+        const N: usize = 1000;
+
+        // Strictly periodic 50 Hz timestamps in microseconds
+        let values: Vec<i64> = (0..N as i64)
+            .map(|i| 1_675_465_460_000_000 + 20_000 * i)
+            .collect();
+
+        // Initialize the compressor
+        let mut compressor = TestRowCompressorImpl::new(128);
+
+        // Compress row
+        for value in &values {
+            compressor.compress(TestRow { ts: *value });
+        }
+
+        // Finalize the compression
+        let bytes = compressor.finish();
+
+        // Delta-delta is chosen, costing one nibble per row
+        assert_eq!(
+            bytes[4] >> 4,
+            tsz_compress::prelude::consts::headers::START_OF_COLUMN
+        );
+        assert_eq!(
+            bytes[4] & 0x0F,
+            tsz_compress::prelude::consts::headers::DELTA_DELTA_FIRST_ROW
+        );
+        assert!(bytes.len() < 4 + 32 + N / 2 + 1);
+
+        // Initialize the decompressor
+        let mut decompressor = TestRowDecompressorImpl::new();
+
+        // Decompress the bit buffer
+        decompressor.decompress(&bytes).unwrap();
+
+        // Assert that the decompressed data matches the original
+        assert_eq!(values, decompressor.col_ts());
+    }
+
+    #[test]
+    fn test_macro_compress_delta_and_delta_delta_columns() {
+        mod row {
+            use tsz_compress::prelude::*;
+            #[derive(Debug, Copy, Clone, CompressV2, DecompressV2)]
+            pub struct TestRow {
+                pub ts: i64,
+                pub a: i8,
+                pub b: i16,
+                pub c: i32,
+            }
+
+            pub use compress::TestRowCompressorImpl;
+            pub use decompress::TestRowDecompressorImpl;
+        }
+        use row::*;
+
+        let mut rng = rand::thread_rng();
+
+        // The compressor is reused for each frame
+        let mut compressor = TestRowCompressorImpl::new(128);
+        for _ in 0..100 {
+            // Number of samples in the input vector
+            let end_range = rng.gen_range(0..1000);
+
+            // Timestamps with jitter and noisy random walks
+            let mut rows = Vec::new();
+            let mut row = TestRow {
+                ts: rng.gen_range(0..i64::MAX / 2),
+                a: rng.gen(),
+                b: rng.gen(),
+                c: rng.gen(),
+            };
+            for _ in 0..end_range {
+                rows.push(row);
+                row.ts += 1000 + rng.gen_range(-2..=2);
+                row.a = row.a.wrapping_add(rng.gen_range(-3..=3));
+                row.b = row.b.wrapping_add(rng.gen_range(-300..=300));
+                row.c = rng.gen();
+            }
+
+            // Compression
+            for row in &rows {
+                compressor.compress(*row);
+            }
+
+            // Finalize the compression
+            let bytes = compressor.finish();
+
+            // Initialize the decompressor
+            let mut decompressor = TestRowDecompressorImpl::new();
+
+            // Decompress the bit buffer
+            decompressor.decompress(&bytes).unwrap();
+
+            // Assert that the decompressed data matches the original
+            let ts: Vec<_> = rows.iter().map(|row| row.ts).collect();
+            let a: Vec<_> = rows.iter().map(|row| row.a).collect();
+            let b: Vec<_> = rows.iter().map(|row| row.b).collect();
+            let c: Vec<_> = rows.iter().map(|row| row.c).collect();
+            assert_eq!(ts, decompressor.col_ts());
+            assert_eq!(a, decompressor.col_a());
+            assert_eq!(b, decompressor.col_b());
+            assert_eq!(c, decompressor.col_c());
+        }
+    }
+}
+
+mod test_unsigned {
+
+    use super::*;
+    use rand::Rng;
+
+    #[test]
+    fn test_macro_compress_u8_values_random() {
+        mod row {
+            use tsz_compress::prelude::*;
+            #[derive(Debug, Copy, Clone, CompressV2, DecompressV2)]
+            pub struct TestRow {
+                pub a: u8,
+            }
+
+            pub use compress::TestRowCompressorImpl;
+            pub use decompress::TestRowDecompressorImpl;
+        }
+        use row::*;
+
+        let mut rng = rand::thread_rng();
+
+        for _ in 0..100 {
+            // Initialize the compressor
+            let mut compressor = TestRowCompressorImpl::new(128);
+
+            // Number of samples in the input vector
+            let end_range = rng.gen_range(0..1000);
+
+            // Generate input vector randomly, including the extremes
+            let mut values: Vec<u8> = (0..end_range).map(|_| rng.gen()).collect();
+            values.extend([u8::MIN, u8::MAX, u8::MIN, 1, u8::MAX - 1, 0]);
+
+            // Compression
+            for value in &values {
+                compressor.compress(TestRow { a: *value });
+            }
+
+            // Finalize the compression
+            let bytes = compressor.finish();
+
+            // Initialize the decompressor
+            let mut decompressor = TestRowDecompressorImpl::new();
+
+            // Decompress the bit buffer
+            decompressor.decompress(&bytes).unwrap();
+
+            // Assert that the decompressed data matches the original
+            assert_eq!(values, decompressor.col_a());
+        }
+    }
+
+    #[test]
+    fn test_macro_compress_u16_values_random() {
+        mod row {
+            use tsz_compress::prelude::*;
+            #[derive(Debug, Copy, Clone, CompressV2, DecompressV2)]
+            pub struct TestRow {
+                pub a: u16,
+            }
+
+            pub use compress::TestRowCompressorImpl;
+            pub use decompress::TestRowDecompressorImpl;
+        }
+        use row::*;
+
+        let mut rng = rand::thread_rng();
+
+        for _ in 0..100 {
+            // Initialize the compressor
+            let mut compressor = TestRowCompressorImpl::new(128);
+
+            // Number of samples in the input vector
+            let end_range = rng.gen_range(0..1000);
+
+            // Generate input vector randomly, including the extremes
+            let mut values: Vec<u16> = (0..end_range).map(|_| rng.gen()).collect();
+            values.extend([u16::MIN, u16::MAX, u16::MIN, 1, u16::MAX - 1, 0]);
+
+            // Compression
+            for value in &values {
+                compressor.compress(TestRow { a: *value });
+            }
+
+            // Finalize the compression
+            let bytes = compressor.finish();
+
+            // Initialize the decompressor
+            let mut decompressor = TestRowDecompressorImpl::new();
+
+            // Decompress the bit buffer
+            decompressor.decompress(&bytes).unwrap();
+
+            // Assert that the decompressed data matches the original
+            assert_eq!(values, decompressor.col_a());
+        }
+    }
+
+    #[test]
+    fn test_macro_compress_u32_values_random() {
+        mod row {
+            use tsz_compress::prelude::*;
+            #[derive(Debug, Copy, Clone, CompressV2, DecompressV2)]
+            pub struct TestRow {
+                pub a: u32,
+            }
+
+            pub use compress::TestRowCompressorImpl;
+            pub use decompress::TestRowDecompressorImpl;
+        }
+        use row::*;
+
+        let mut rng = rand::thread_rng();
+
+        for _ in 0..100 {
+            // Initialize the compressor
+            let mut compressor = TestRowCompressorImpl::new(128);
+
+            // Number of samples in the input vector
+            let end_range = rng.gen_range(0..1000);
+
+            // Generate input vector randomly, including the extremes
+            let mut values: Vec<u32> = (0..end_range).map(|_| rng.gen()).collect();
+            values.extend([u32::MIN, u32::MAX, u32::MIN, 1, u32::MAX - 1, 0]);
+
+            // Compression
+            for value in &values {
+                compressor.compress(TestRow { a: *value });
+            }
+
+            // Finalize the compression
+            let bytes = compressor.finish();
+
+            // Initialize the decompressor
+            let mut decompressor = TestRowDecompressorImpl::new();
+
+            // Decompress the bit buffer
+            decompressor.decompress(&bytes).unwrap();
+
+            // Assert that the decompressed data matches the original
+            assert_eq!(values, decompressor.col_a());
+        }
+    }
+
+    #[test]
+    fn test_macro_compress_u64_values_random() {
+        mod row {
+            use tsz_compress::prelude::*;
+            #[derive(Debug, Copy, Clone, CompressV2, DecompressV2)]
+            pub struct TestRow {
+                pub a: u64,
+            }
+
+            pub use compress::TestRowCompressorImpl;
+            pub use decompress::TestRowDecompressorImpl;
+        }
+        use row::*;
+
+        let mut rng = rand::thread_rng();
+
+        for _ in 0..100 {
+            // Initialize the compressor
+            let mut compressor = TestRowCompressorImpl::new(128);
+
+            // Number of samples in the input vector
+            let end_range = rng.gen_range(0..1000);
+
+            // Generate input vector randomly, including the extremes
+            let mut values: Vec<u64> = (0..end_range).map(|_| rng.gen()).collect();
+            values.extend([u64::MIN, u64::MAX, u64::MIN, 1, u64::MAX - 1, 0]);
+
+            // Compression
+            for value in &values {
+                compressor.compress(TestRow { a: *value });
+            }
+
+            // Finalize the compression
+            let bytes = compressor.finish();
+
+            // Initialize the decompressor
+            let mut decompressor = TestRowDecompressorImpl::new();
+
+            // Decompress the bit buffer
+            decompressor.decompress(&bytes).unwrap();
+
+            // Assert that the decompressed data matches the original
+            assert_eq!(values, decompressor.col_a());
+        }
+    }
+
+    #[test]
+    fn test_macro_compress_adc_counts_and_tick_timestamps() {
+        mod row {
+            use tsz_compress::prelude::*;
+            #[derive(Debug, Copy, Clone, PartialEq, CompressV2, DecompressV2)]
+            pub struct TestRow {
+                pub ticks: u32,
+                pub adc: u16,
+            }
+
+            pub use compress::TestRowCompressorImpl;
+            pub use decompress::TestRowDecompressorImpl;
+        }
+        use row::*;
+
+        let mut rng = rand::thread_rng();
+
+        // 32768 Hz ticks sampled at 64 Hz that roll over, with 12-bit ADC counts
+        let mut ticks = u32::MAX - 10 * 512;
+        let mut adc = 2048u16;
+        let mut rows = Vec::new();
+        for _ in 0..1000 {
+            rows.push(TestRow { ticks, adc });
+            ticks = ticks.wrapping_add(512);
+            adc = (adc as i32 + rng.gen_range(-8..=8)).clamp(0, 4095) as u16;
+        }
+
+        // Initialize the compressor
+        let mut compressor = TestRowCompressorImpl::new(128);
+
+        // Compress rows
+        for row in &rows {
+            compressor.compress(*row);
+        }
+
+        // Finalize the compression
+        let bytes = compressor.finish();
+        assert!(bytes.len() < rows.len() * 6 / 2);
+
+        // Initialize the decompressor
+        let mut decompressor = TestRowDecompressorImpl::new();
+
+        // Decompress the bit buffer
+        decompressor.decompress(&bytes).unwrap();
+
+        // Assert that the decompressed data matches the original
+        assert_eq!(rows, decompressor.rows());
+    }
+}
+
+mod test_i128 {
+
+    use super::*;
+    use rand::Rng;
+
+    mod row {
+        use tsz_compress::prelude::*;
+        #[derive(Debug, Copy, Clone, CompressV2, DecompressV2)]
+        pub struct TestRow {
+            pub id: i128,
+        }
+
+        pub use compress::TestRowCompressorImpl;
+        pub use decompress::TestRowDecompressorImpl;
+    }
+    use row::*;
+
+    fn round_trip(values: &[i128]) {
+        // Initialize the compressor
+        let mut compressor = TestRowCompressorImpl::new(128);
+
+        // Compression
+        for value in values {
+            compressor.compress(TestRow { id: *value });
+        }
+
+        // Finalize the compression
+        let bytes = compressor.finish();
+
+        // Initialize the decompressor
+        let mut decompressor = TestRowDecompressorImpl::new();
+
+        // Decompress the bit buffer
+        decompressor.decompress(&bytes).unwrap();
+
+        // Assert that the decompressed data matches the original
+        assert_eq!(values, decompressor.col_id());
+    }
+
+    #[test]
+    fn test_macro_compress_i128_few_rows() {
+        round_trip(&[]);
+        round_trip(&[i128::MIN]);
+        round_trip(&[i128::MAX, i128::MIN]);
+        round_trip(&[0, 1, 2]);
+    }
+
+    #[test]
+    fn test_macro_compress_i128_extremes() {
+        let mut values = vec![];
+        for i in 0..100 {
+            values.extend([i128::MIN, i128::MAX, 0, -1, i, i128::MAX - i, i128::MIN + i]);
+            values.extend([i64::MIN as i128, i64::MAX as i128, u64::MAX as i128]);
+        }
+        round_trip(&values);
+    }
+
+    #[test]
+    fn test_macro_compress_i128_values_random() {
+        let mut rng = rand::thread_rng();
+
+        for _ in 0..100 {
+            // Number of samples in the input vector
+            let end_range = rng.gen_range(0..1000);
+
+            // Generate input vector randomly across the full range and near zero
+            let values: Vec<i128> = (0..end_range)
+                .map(|i| match i % 3 {
+                    0 => rng.gen(),
+                    1 => rng.gen::<i64>() as i128,
+                    _ => rng.gen_range(-100..100),
+                })
+                .collect();
+
+            round_trip(&values);
+        }
+    }
+
+    #[test]
+    fn test_macro_compress_i128_timestamp_and_sequence() {
+        let mut rng = rand::thread_rng();
+
+        // Nanosecond UTC timestamps in the upper 64 bits, a sequence id in the lower 64 bits
+        let mut ts: i128 = 1_700_000_000_000_000_000;
+        let mut values = vec![];
+        for seq in 0..10_000 {
+            ts += 1_000_000 + rng.gen_range(-1000..1000);
+            values.push((ts << 64) | seq);
+        }
+        round_trip(&values);
+
+        // A fixed sampling rate compresses to well below the 16 bytes per row of the raw values
+        let values: Vec<i128> = (0..10_000)
+            .map(|seq| ((1_700_000_000_000_000_000 + seq * 1_000_000) << 64) | seq)
+            .collect();
+        round_trip(&values);
+
+        let mut compressor = TestRowCompressorImpl::new(values.len());
+        for value in &values {
+            compressor.compress(TestRow { id: *value });
+        }
+        let bytes = compressor.finish();
+        assert!(bytes.len() < values.len());
+    }
+}
+
+mod test_pointer_width {
+
+    use super::*;
+
+    mod row {
+        use tsz_compress::prelude::*;
+        #[derive(Debug, Copy, Clone, CompressV2, DecompressV2)]
+        pub struct TestRow {
+            pub ts: i64,
+            pub a: i64,
+        }
+
+        pub use compress::TestRowCompressorImpl;
+        pub use decompress::TestRowDecompressorImpl;
+    }
+    use row::*;
+
+    /// Timestamps take the delta-delta path, the second column is packed in more than 32 bits per value.
+    fn rows() -> Vec<TestRow> {
+        (0..12i64)
+            .map(|i| TestRow {
+                ts: 1_700_000_000_000 + i * 1000 + (i * i) % 7,
+                a: if i % 2 == 0 { i << 40 } else { -(i << 48) },
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_macro_compress_i64_bytes_pinned() {
+        let rows = rows();
+
+        // Initialize the compressor
+        let mut compressor = TestRowCompressorImpl::new(rows.len());
+
+        // Compression
+        for row in &rows {
+            compressor.compress(*row);
+        }
+
+        // Finalize the compression
+        let bytes = compressor.finish();
+
+        // The bytes were pinned on x86_64 and must not depend on the pointer width of the target
+        #[rustfmt::skip]
+        let expected: [u8; 114] = [
+            0, 0, 0, 12, 149, 233, 190, 191, 234, 221, 136, 128, 107, 250, 34, 66, 146, 66, 66, 146, 66, 66, 66, 146,
+            73, 195, 79, 255, 80, 0, 0, 0, 0, 0, 9, 75, 0, 0, 0, 0, 0, 0, 160, 0, 0, 0, 0, 0,
+            11, 2, 0, 0, 0, 0, 0, 128, 0, 0, 0, 0, 0, 11, 4, 0, 0, 0, 0, 0, 96, 0, 0, 0,
+            0, 0, 11, 6, 0, 0, 0, 0, 0, 64, 0, 0, 0, 0, 0, 11, 8, 0, 0, 0, 0, 0, 32, 0,
+            0, 0, 0, 0, 11, 10, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 9,
+        ];
+        assert_eq!(bytes, expected);
+
+        // Initialize the decompressor
+        let mut decompressor = TestRowDecompressorImpl::new();
+
+        // Decompress the bit buffer
+        decompressor.decompress(&bytes).unwrap();
+
+        // Assert that the decompressed data matches the original
+        let ts = rows.iter().map(|row| row.ts).collect::<Vec<_>>();
+        let a = rows.iter().map(|row| row.a).collect::<Vec<_>>();
+        assert_eq!(decompressor.col_ts(), ts);
+        assert_eq!(decompressor.col_a(), a);
+    }
+}
+
+mod test_frame_header {
+
+    use super::*;
+    use rand::Rng;
+
+    mod row {
+        use tsz_compress::prelude::*;
+        #[derive(Debug, Copy, Clone, PartialEq, CompressV2, DecompressV2)]
+        #[tsz(header)]
+        pub struct TestRow {
+            pub ts: i64,
+            pub a: i16,
+            pub b: u8,
+        }
+
+        pub use compress::TestRowCompressorImpl;
+        pub use decompress::TestRowDecompressorImpl;
+    }
+
+    mod renamed {
+        use tsz_compress::prelude::*;
+        #[derive(Debug, Copy, Clone, CompressV2, DecompressV2)]
+        #[tsz(header)]
+        pub struct TestRow {
+            pub ts: i64,
+            pub b: i16,
+            pub a: u8,
+        }
+
+        pub use compress::TestRowCompressorImpl;
+        pub use decompress::TestRowDecompressorImpl;
+    }
+
+    mod retyped {
+        use tsz_compress::prelude::*;
+        #[derive(Debug, Copy, Clone, CompressV2, DecompressV2)]
+        #[tsz(header)]
+        pub struct TestRow {
+            pub ts: i64,
+            pub a: i16,
+            pub b: i8,
+        }
+
+        pub use compress::TestRowCompressorImpl;
+        pub use decompress::TestRowDecompressorImpl;
+    }
+
+    mod headerless {
+        use tsz_compress::prelude::*;
+        #[derive(Debug, Copy, Clone, CompressV2, DecompressV2)]
+        pub struct TestRow {
+            pub ts: i64,
+            pub a: i16,
+            pub b: u8,
+        }
+
+        pub use compress::TestRowCompressorImpl;
+        pub use decompress::TestRowDecompressorImpl;
+    }
+
+    mod seek {
+        use tsz_compress::prelude::*;
+        #[derive(Debug, Copy, Clone, PartialEq, CompressV2, DecompressV2)]
+        #[tsz(header, seek = 2)]
+        pub struct TestRow {
+            pub ts: i64,
+            pub a: i16,
+            pub b: u8,
+        }
+
+        pub use compress::TestRowCompressorImpl;
+        pub use decompress::TestRowDecompressorImpl;
+    }
+
+    mod table {
+        use tsz_compress::prelude::*;
+        #[derive(Debug, Copy, Clone, PartialEq, CompressV2, DecompressV2)]
+        #[tsz(header, column_table)]
+        pub struct TestRow {
+            pub ts: i64,
+            pub a: i16,
+            pub b: u8,
+        }
+
+        pub use compress::TestRowCompressorImpl;
+        pub use decompress::TestRowDecompressorImpl;
+    }
+
+    mod crc {
+        use tsz_compress::prelude::*;
+        #[derive(Debug, Copy, Clone, PartialEq, CompressV2, DecompressV2)]
+        #[tsz(header, checksum = "crc32")]
+        pub struct TestRow {
+            pub ts: i64,
+            pub a: i16,
+            pub b: u8,
+        }
+
+        pub use compress::TestRowCompressorImpl;
+        pub use decompress::TestRowDecompressorImpl;
+    }
+
+    mod packed {
+        use tsz_compress::prelude::*;
+        #[derive(Debug, Copy, Clone, PartialEq, CompressV2, DecompressV2)]
+        #[tsz(header, lz)]
+        pub struct TestRow {
+            pub ts: i64,
+            pub a: i16,
+            pub b: u8,
+        }
+
+        pub use compress::TestRowCompressorImpl;
+        pub use decompress::TestRowDecompressorImpl;
+    }
+
+    mod layout {
+        use tsz_compress::prelude::*;
+        #[derive(Debug, Copy, Clone, PartialEq, CompressV2, DecompressV2)]
+        #[tsz(header, seek = 2, column_table, checksum = "crc32")]
+        pub struct TestRow {
+            pub ts: i64,
+            pub a: i16,
+            pub b: u8,
+        }
+
+        pub use compress::TestRowCompressorImpl;
+        pub use decompress::TestRowDecompressorImpl;
+    }
+
+    fn compress(rows: &[row::TestRow]) -> Vec<u8> {
+        // Initialize the compressor
+        let mut compressor = row::TestRowCompressorImpl::new(rows.len());
+
+        // Compression
+        for row in rows {
+            compressor.compress(*row);
+        }
+
+        // Finalize the compression
+        compressor.finish()
+    }
+
+    fn random_rows() -> Vec<row::TestRow> {
+        let mut rng = rand::thread_rng();
+        let mut ts = 1_700_000_000_000;
+        (0..rng.gen_range(0..1000))
+            .map(|_| {
+                ts += 1000;
+                row::TestRow {
+                    ts,
+                    a: rng.gen(),
+                    b: rng.gen(),
+                }
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_macro_compress_frame_header_round_trip() {
+        for _ in 0..100 {
+            let rows = random_rows();
+            let bytes = compress(&rows);
+
+            // The frame header leads the bytes
+            let header_len = 4 + row::TestRowCompressorImpl::COLUMN_TYPES.len() + 4;
+            assert_eq!(&bytes[..2], b"TZ");
+            assert_eq!(bytes[3], 3);
+            assert_eq!(
+                &bytes[4..7],
+                &[consts::frame::I64, consts::frame::I16, consts::frame::U8]
+            );
+            assert_eq!(
+                &bytes[7..header_len],
+                row::TestRowCompressorImpl::SCHEMA_HASH.to_be_bytes()
+            );
+
+            // Initialize the decompressor
+            let mut decompressor = row::TestRowDecompressorImpl::new();
+
+            // Decompress the bit buffer
+            decompressor.decompress(&bytes).unwrap();
+
+            // Assert that the decompressed data matches the original
+            assert_eq!(decompressor.rows(), rows);
+        }
+    }
+
+    #[test]
+    fn test_macro_compress_frame_header_schema_mismatch() {
+        let rows = random_rows();
+        let bytes = compress(&rows);
+
+        // The field names differ
+        assert_ne!(
+            row::TestRowCompressorImpl::SCHEMA_HASH,
+            renamed::TestRowDecompressorImpl::SCHEMA_HASH
+        );
+        let mut decompressor = renamed::TestRowDecompressorImpl::new();
+        let result = decompressor.decompress(&bytes);
+        assert!(matches!(result, Err(CodingError::SchemaMismatch)));
+
+        // A field type differs
+        let mut decompressor = retyped::TestRowDecompressorImpl::new();
+        let result = decompressor.decompress(&bytes);
+        assert!(matches!(result, Err(CodingError::SchemaMismatch)));
+
+        // The bytes were compressed without a header
+        let mut compressor = headerless::TestRowCompressorImpl::new(rows.len());
+        for row in &rows {
+            compressor.compress(headerless::TestRow {
+                ts: row.ts,
+                a: row.a,
+                b: row.b,
+            });
+        }
+        let headerless_bytes = compressor.finish();
+        let mut decompressor = row::TestRowDecompressorImpl::new();
+        let result = decompressor.decompress(&headerless_bytes);
+        assert!(matches!(result, Err(CodingError::SchemaMismatch)));
+
+        // The format version differs
+        let mut versioned_bytes = bytes.clone();
+        versioned_bytes[2] += 1;
+        let result = decompressor.decompress(&versioned_bytes);
+        assert!(matches!(result, Err(CodingError::SchemaMismatch)));
+
+        // The header is truncated
+        let result = decompressor.decompress(&bytes[..6]);
+        assert!(matches!(result, Err(CodingError::NotEnoughBits)));
+        let result = decompressor.decompress(&[]);
+        assert!(matches!(result, Err(CodingError::Empty)));
+    }
+
+    /// Compresses the rows with the struct of another layout of the same fields.
+    macro_rules! layout_frame {
+        ($layout:ident, $rows:expr) => {{
+            let mut compressor = $layout::TestRowCompressorImpl::new($rows.len());
+            for row in $rows {
+                compressor.compress($layout::TestRow {
+                    ts: row.ts,
+                    a: row.a,
+                    b: row.b,
+                });
+            }
+            compressor.finish()
+        }};
+    }
+
+    /// Decodes a frame across the plain layout and another layout of the same fields.
+    macro_rules! assert_layout_mismatch {
+        ($layout:ident, $bytes:expr, $rows:expr, $reverse:pat) => {{
+            let layout_bytes = layout_frame!($layout, $rows);
+            assert_ne!(
+                row::TestRowCompressorImpl::SCHEMA_HASH,
+                $layout::TestRowCompressorImpl::SCHEMA_HASH
+            );
+
+            // The frame of the layout is rejected by the plain decoder
+            let mut decompressor = row::TestRowDecompressorImpl::new();
+            let result = decompressor.decompress(&layout_bytes);
+            assert!(matches!(result, Err(CodingError::SchemaMismatch)));
+            let mut iter = row::TestRowDecompressorImpl::iter_rows(&layout_bytes);
+            assert!(matches!(
+                iter.next(),
+                Some(Err(CodingError::SchemaMismatch))
+            ));
+
+            // The plain frame is rejected by the decoder of the layout
+            let mut decompressor = $layout::TestRowDecompressorImpl::new();
+            let result = decompressor.decompress($bytes);
+            assert!(matches!(result, Err($reverse)));
+
+            // The frame of the layout still decodes with its own decoder
+            decompressor.decompress(&layout_bytes).unwrap();
+            assert_eq!(decompressor.rows().len(), $rows.len());
+        }};
+    }
+
+    #[test]
+    fn test_macro_compress_frame_header_layout_mismatch() {
+        let mut rows = random_rows();
+        rows.truncate(100);
+        rows.push(row::TestRow { ts: 0, a: 1, b: 2 });
+        let bytes = compress(&rows);
+
+        // The seek index, column table, LZ flag byte and checksum are part of the schema hash
+        assert_layout_mismatch!(seek, &bytes, &rows, CodingError::SchemaMismatch);
+        assert_layout_mismatch!(table, &bytes, &rows, CodingError::SchemaMismatch);
+        assert_layout_mismatch!(packed, &bytes, &rows, CodingError::SchemaMismatch);
+
+        // A decoder with a checksum verifies the trailer of the plain frame first
+        assert_layout_mismatch!(crc, &bytes, &rows, CodingError::ChecksumMismatch);
+        assert_layout_mismatch!(layout, &bytes, &rows, CodingError::ChecksumMismatch);
+    }
+}
+
+mod test_packetizer {
+
+    use super::*;
+    use rand::Rng;
+
+    mod row {
+        use tsz_compress::prelude::*;
+        #[derive(Debug, Copy, Clone, PartialEq, CompressV2, DecompressV2)]
+        pub struct TestRow {
+            pub ts: i64,
+            pub a: i16,
+            pub b: i8,
+            pub c: u32,
+        }
+
+        pub use compress::TestRowCompressorImpl;
+        pub use decompress::TestRowDecompressorImpl;
+    }
+    use row::*;
+
+    mod wide {
+        use tsz_compress::prelude::*;
+        #[derive(Debug, Copy, Clone, PartialEq, CompressV2, DecompressV2)]
+        #[tsz(header)]
+        pub struct TestRow {
+            pub id: i128,
+            pub a: u64,
+        }
+
+        pub use compress::TestRowCompressorImpl;
+        pub use decompress::TestRowDecompressorImpl;
+    }
+
+    mod seek {
+        use tsz_compress::prelude::*;
+        #[derive(Debug, Copy, Clone, PartialEq, CompressV2, DecompressV2)]
+        #[tsz(seek = 16)]
+        pub struct TestRow {
+            pub ts: i64,
+            pub a: i16,
+            pub b: i8,
+            pub c: u32,
+        }
+
+        pub use compress::TestRowCompressorImpl;
+        pub use decompress::TestRowDecompressorImpl;
+    }
+
+    /// A random walk sampled at roughly 1 kHz, with occasional jumps.
+    fn random_rows(len: usize) -> Vec<TestRow> {
+        let mut rng = rand::thread_rng();
+        let mut row = TestRow {
+            ts: 1_700_000_000_000_000,
+            a: 0,
+            b: 0,
+            c: 0,
+        };
+        (0..len)
+            .map(|_| {
+                row.ts += 1000 + rng.gen_range(-3..=3);
+                row.a = row.a.wrapping_add(rng.gen_range(-20..=20));
+                row.b = if rng.gen_range(0..100) == 0 {
+                    rng.gen()
+                } else {
+                    row.b.wrapping_add(rng.gen_range(-2..=2))
+                };
+                row.c = if rng.gen_range(0..50) == 0 {
+                    rng.gen()
+                } else {
+                    row.c.wrapping_add(rng.gen_range(0..=5))
+                };
+                row
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_macro_compress_finished_len_is_exact() {
+        let mut rng = rand::thread_rng();
+
+        // Initialize the compressor
+        let mut compressor = TestRowCompressorImpl::new(128);
+        assert_eq!(compressor.finished_len(), compressor.finish().len());
+
+        for _ in 0..100 {
+            let rows = random_rows(rng.gen_range(0..200));
+            for row in &rows {
+                compressor.compress(*row);
+                let finished_len = compressor.finished_len();
+
+                // Finalize a copy of the compression by compressing the same rows again
+                let mut copy = TestRowCompressorImpl::new(128);
+                rows.iter()
+                    .take(compressor.row_count())
+                    .for_each(|row| copy.compress(*row));
+                assert_eq!(finished_len, copy.finish().len());
+            }
+            assert_eq!(compressor.finished_len(), compressor.finish().len());
+        }
+
+        let mut compressor = wide::TestRowCompressorImpl::new(128);
+        for i in 0..100 {
+            compressor.compress(wide::TestRow {
+                id: rng.gen(),
+                a: rng.gen_range(0..i + 1),
+            });
+            let finished_len = compressor.finished_len();
+            assert_eq!(finished_len, compressor.finish().len());
+        }
+    }
+
+    #[test]
+    fn test_macro_compress_packetizer_within_budget() {
+        for budget in [100, 180, 251] {
+            let rows = random_rows(10_000);
+
+            // Initialize the packetizer
+            let mut packetizer = Packetizer::<TestRowCompressorImpl>::new(budget);
+
+            // Compression
+            let mut frames = vec![];
+            let mut frame = vec![];
+            for row in &rows {
+                if packetizer.compress_into(*row, &mut frame).unwrap() {
+                    frames.push(core::mem::take(&mut frame));
+                }
+            }
+
+            // Finalize the compression
+            if packetizer.finish_into(&mut frame) {
+                frames.push(frame);
+            }
+            assert!(frames.len() > 1);
+
+            let mut decompressed = vec![];
+            for (i, frame) in frames.iter().enumerate() {
+                // No frame exceeds the budget
+                assert!(frame.len() <= budget);
+
+                // Each frame is independently decodable
+                let mut decompressor = TestRowDecompressorImpl::new();
+                decompressor.decompress(frame).unwrap();
+                let frame_rows = decompressor.rows();
+
+                // Every frame but the last was emitted because the next row would exceed the budget
+                if i + 1 < frames.len() {
+                    let mut compressor = TestRowCompressorImpl::new(frame_rows.len() + 1);
+                    frame_rows.iter().for_each(|row| compressor.compress(*row));
+                    compressor.compress(rows[decompressed.len() + frame_rows.len()]);
+                    assert!(compressor.finished_len() > budget);
+                }
+
+                decompressed.extend(frame_rows);
+            }
+
+            // Assert that the decompressed data matches the original
+            assert_eq!(decompressed, rows);
+        }
+    }
+
+    #[test]
+    fn test_macro_compress_packetizer_seek_within_budget() {
+        let rows = random_rows(2_000)
+            .into_iter()
+            .map(|row| seek::TestRow {
+                ts: row.ts,
+                a: row.a,
+                b: row.b,
+                c: row.c,
+            })
+            .collect::<Vec<_>>();
+
+        // The seek index is counted in the finished length
+        let mut compressor = seek::TestRowCompressorImpl::new(128);
+        for row in &rows[..100] {
+            compressor.compress(*row);
+            let finished_len = compressor.finished_len();
+            assert_eq!(finished_len, compressor.finish().len());
+            rows[..compressor.row_count()]
+                .iter()
+                .for_each(|row| compressor.compress(*row));
+        }
+
+        for budget in [100, 180, 251] {
+            // Initialize the packetizer
+            let mut packetizer = Packetizer::<seek::TestRowCompressorImpl>::new(budget);
+
+            // Compression
+            let mut frames = vec![];
+            let mut frame = vec![];
+            for row in &rows {
+                if packetizer.compress_into(*row, &mut frame).unwrap() {
+                    frames.push(core::mem::take(&mut frame));
+                }
+            }
+
+            // Finalize the compression
+            if packetizer.finish_into(&mut frame) {
+                frames.push(frame);
+            }
+            assert!(frames.len() > 1);
+
+            let mut decompressed = vec![];
+            for frame in &frames {
+                // No frame exceeds the budget, including its seek index
+                assert!(frame.len() <= budget, "{} > {}", frame.len(), budget);
+
+                // Each frame is independently decodable, from its seek index too
+                let mut decompressor = seek::TestRowDecompressorImpl::new();
+                decompressor.decompress(frame).unwrap();
+                let frame_rows = decompressor.rows();
+                let range = frame_rows.len() / 2..frame_rows.len();
+                let seeked = seek::TestRowDecompressorImpl::iter_rows_range(frame, range.clone())
+                    .collect::<Result<Vec<_>, _>>()
+                    .unwrap();
+                assert_eq!(seeked, frame_rows[range]);
+                decompressed.extend(frame_rows);
+            }
+
+            // Assert that the decompressed data matches the original
+            assert_eq!(decompressed, rows);
+        }
+    }
+
+    #[test]
+    fn test_macro_compress_packetizer_row_exceeds_budget() {
+        // The row count and the full width of the first row do not fit
+        let mut packetizer = Packetizer::<TestRowCompressorImpl>::new(16);
+        let mut frame = vec![];
+        let result = packetizer.compress_into(random_rows(1)[0], &mut frame);
+        assert!(matches!(result, Err(PacketizerError::RowExceedsBudget(_))));
+        assert!(frame.is_empty());
+        assert_eq!(packetizer.row_count(), 0);
+        assert!(!packetizer.finish_into(&mut frame));
+    }
+
+    #[test]
+    fn test_macro_compress_packetizer_row_fits_after_exceeding_budget() {
+        let small = TestRow {
+            ts: 0,
+            a: 0,
+            b: 0,
+            c: 0,
+        };
+        let large = TestRow {
+            ts: i64::MIN,
+            a: i16::MIN,
+            b: i8::MIN,
+            c: u32::MAX,
+        };
+
+        // The first values are VLQs, so a frame of one small row is shorter than one of a large row
+        let mut compressor = TestRowCompressorImpl::new(1);
+        compressor.compress(small);
+        let budget = compressor.finished_len();
+        let mut packetizer = Packetizer::<TestRowCompressorImpl>::new(budget);
+        let mut frame = vec![];
+        let result = packetizer.compress_into(large, &mut frame);
+        assert!(matches!(result, Err(PacketizerError::RowExceedsBudget(len)) if len > budget));
+        assert_eq!(packetizer.row_count(), 0);
+
+        // A later row may still fit, and the dropped row is not in its frame
+        assert!(!packetizer.compress_into(small, &mut frame).unwrap());
+        assert!(packetizer.finish_into(&mut frame));
+        assert!(frame.len() <= budget);
+        let mut decompressor = TestRowDecompressorImpl::new();
+        decompressor.decompress(&frame).unwrap();
+        assert_eq!(decompressor.rows(), [small]);
+    }
+}
+
+mod test_decompress_iter {
+
+    use super::*;
+    use rand::Rng;
+
+    mod row {
+        use tsz_compress::prelude::*;
+        #[derive(Debug, Copy, Clone, PartialEq, CompressV2, DecompressV2)]
+        pub struct TestRow {
+            pub ts: i64,
+            pub a: i8,
+            pub b: u16,
+            pub c: i32,
+            pub d: u64,
+            pub e: i128,
+        }
+
+        pub use compress::TestRowCompressorImpl;
+        pub use decompress::{TestRowDecompressIter, TestRowDecompressorImpl};
+    }
+    use row::*;
+
+    mod header {
+        use tsz_compress::prelude::*;
+        #[derive(Debug, Copy, Clone, PartialEq, CompressV2, DecompressV2)]
+        #[tsz(header)]
+        pub struct TestRow {
+            pub ts: u32,
+            pub a: i16,
+        }
+
+        pub use compress::TestRowCompressorImpl;
+        pub use decompress::TestRowDecompressorImpl;
+    }
+
+    mod wide {
+        use tsz_compress::prelude::*;
+        #[derive(Debug, Copy, Clone, PartialEq, CompressV2, DecompressV2)]
+        pub struct WideRow {
+            pub c0: i32,
+            pub c1: i32,
+            pub c2: i32,
+            pub c3: i32,
+            pub c4: i32,
+            pub c5: i32,
+            pub c6: i32,
+            pub c7: i32,
+            pub c8: i32,
+            pub c9: i32,
+            pub c10: i32,
+            pub c11: i32,
+            pub c12: i32,
+            pub c13: i32,
+        }
+
+        pub use compress::WideRowCompressorImpl;
+        pub use decompress::WideRowDecompressorImpl;
+    }
+
+    fn random_rows(len: usize) -> Vec<TestRow> {
+        let mut rng = rand::thread_rng();
+        let mut ts = rng.gen::<i64>();
+        (0..len)
+            .map(|i| {
+                ts = ts.wrapping_add(1000);
+                TestRow {
+                    ts,
+                    a: rng.gen(),
+                    b: if i % 7 == 0 { rng.gen() } else { i as u16 },
+                    c: rng.gen_range(-100..100),
+                    d: rng.gen::<u64>() >> rng.gen_range(0..64),
+                    e: if i % 3 == 0 { rng.gen() } else { i as i128 },
+                }
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_macro_decompress_iter_matches_decompress() {
+        let mut rng = rand::thread_rng();
+
+        for _ in 0..100 {
+            let rows = random_rows(rng.gen_range(0..1000));
+
+            // Initialize the compressor
+            let mut compressor = TestRowCompressorImpl::new(rows.len());
+
+            // Compression
+            for row in &rows {
+                compressor.compress(*row);
+            }
+
+            // Finalize the compression
+            let bytes = compressor.finish();
+
+            // Lazily decompress the bit buffer
+            let iter = TestRowDecompressorImpl::iter_rows(&bytes);
+            if rows.is_empty() {
+                // A frame of no rows has a start of column for each column, but no rows
+                assert_eq!(iter.count(), 0);
+                continue;
+            }
+            assert_eq!(iter.size_hint(), (rows.len(), Some(rows.len())));
+            let decompressed = iter.collect::<Result<Vec<_>, _>>().unwrap();
+
+            // Assert that the decompressed data matches the original
+            assert_eq!(decompressed, rows);
+        }
+    }
+
+    #[test]
+    fn test_macro_decompress_iter_delta_columns() {
+        mod row {
+            use tsz_compress::prelude::*;
+            #[derive(Debug, Copy, Clone, PartialEq, CompressV2, DecompressV2)]
+            pub struct TestRow {
+                pub a: i8,
+                pub b: i16,
+                pub c: i32,
+                pub d: i64,
+            }
+
+            pub use compress::TestRowCompressorImpl;
+            pub use decompress::TestRowDecompressorImpl;
+        }
+        use row::*;
+
+        let mut rng = rand::thread_rng();
+
+        // Noise prefers every case of delta words
+        for bits in [2, 5, 7, 9, 15, 31, 63] {
+            let rows = (0..rng.gen_range(3..1000))
+                .map(|_| TestRow {
+                    a: rng.gen::<i8>() >> 7usize.saturating_sub(bits),
+                    b: rng.gen::<i16>() >> 15usize.saturating_sub(bits),
+                    c: rng.gen::<i32>() >> 31usize.saturating_sub(bits),
+                    d: rng.gen::<i64>() >> 63usize.saturating_sub(bits),
+                })
+                .collect::<Vec<_>>();
+
+            // Compression
+            let mut compressor = TestRowCompressorImpl::new(rows.len());
+            for row in &rows {
+                compressor.compress(*row);
+            }
+            let bytes = compressor.finish();
+
+            // Lazily decompress the bit buffer
+            let decompressed = TestRowDecompressorImpl::iter_rows(&bytes)
+                .collect::<Result<Vec<_>, _>>()
+                .unwrap();
+            assert_eq!(decompressed, rows);
+        }
+    }
+
+    #[test]
+    fn test_macro_decompress_iter_wide() {
+        use wide::*;
+        let mut rng = rand::thread_rng();
+        let rows = (0..500)
+            .map(|i| WideRow {
+                c0: i,
+                c1: rng.gen(),
+                c2: -i,
+                c3: rng.gen_range(-10..10),
+                c4: 0,
+                c5: i * i,
+                c6: rng.gen(),
+                c7: 7,
+                c8: i / 3,
+                c9: rng.gen_range(0..1000),
+                c10: -7,
+                c11: i % 5,
+                c12: rng.gen(),
+                c13: i * 1000,
+            })
+            .collect::<Vec<_>>();
+
+        // Initialize the compressor
+        let mut compressor = WideRowCompressorImpl::new(rows.len());
+
+        // Compression
+        for row in &rows {
+            compressor.compress(*row);
+        }
+
+        // Finalize the compression
+        let bytes = compressor.finish();
+
+        // Lazily decompress the bit buffer
+        let iter = WideRowDecompressorImpl::iter_rows(&bytes);
+        assert!(format!("{:?}", iter).starts_with("WideRowDecompressIter { rows: 500"));
+        let decompressed = iter.collect::<Result<Vec<_>, _>>().unwrap();
+
+        // Assert that the decompressed data matches the original
+        assert_eq!(decompressed, rows);
+    }
+
+    #[test]
+    fn test_macro_decompress_iter_errors() {
+        // Compression
+        let rows = random_rows(100);
+        let mut compressor = TestRowCompressorImpl::new(rows.len());
+        for row in &rows {
+            compressor.compress(*row);
+        }
+        let bytes = compressor.finish();
+
+        // An invalid initial column tag is the only item
+        let mut corrupted = bytes.clone();
+        corrupted[4] = 0;
+        let items = TestRowDecompressorImpl::iter_rows(&corrupted).collect::<Vec<_>>();
+        assert!(matches!(
+            items[..],
+            [Err(CodingError::InvalidInitialColumnTag)]
+        ));
+
+        // A truncated frame is reported instead of any row
+        let items =
+            TestRowDecompressorImpl::iter_rows(&bytes[..bytes.len() / 2]).collect::<Vec<_>>();
+        assert_eq!(items.len(), 1);
+        assert!(items[0].is_err());
+
+        // The header is validated
+        let mut compressor = header::TestRowCompressorImpl::new(1);
+        compressor.compress(header::TestRow { ts: 1, a: 2 });
+        let mut bytes = compressor.finish();
+        let items = header::TestRowDecompressorImpl::iter_rows(&bytes).collect::<Vec<_>>();
+        assert!(matches!(items[..], [Ok(header::TestRow { ts: 1, a: 2 })]));
+        bytes[0] = 0;
+        let items = header::TestRowDecompressorImpl::iter_rows(&bytes).collect::<Vec<_>>();
+        assert!(matches!(items[..], [Err(CodingError::SchemaMismatch)]));
+    }
+}
+
+mod test_checksum {
+
+    use super::*;
+    use rand::Rng;
+
+    mod crc16 {
+        use tsz_compress::prelude::*;
+        #[derive(Debug, Copy, Clone, PartialEq, CompressV2, DecompressV2)]
+        #[tsz(checksum = "crc16")]
+        pub struct TestRow {
+            pub ts: i64,
+            pub a: i16,
+        }
+
+        pub use compress::TestRowCompressorImpl;
+        pub use decompress::TestRowDecompressorImpl;
+    }
+
+    mod crc32 {
+        use tsz_compress::prelude::*;
+        #[derive(Debug, Copy, Clone, PartialEq, CompressV2, DecompressV2)]
+        #[tsz(header, checksum = "crc32")]
+        pub struct TestRow {
+            pub ts: i64,
+            pub a: i16,
+        }
+
+        pub use compress::TestRowCompressorImpl;
+        pub use decompress::TestRowDecompressorImpl;
+    }
+
+    macro_rules! test_checksum {
+        ($name:ident, $row:ident, $trailer_len:expr, $crc:expr) => {
+            #[test]
+            fn $name() {
+                let mut rng = rand::thread_rng();
+
+                for _ in 0..10 {
+                    let mut ts = rng.gen::<i32>() as i64;
+                    let rows = (0..rng.gen_range(1..100))
+                        .map(|_| {
+                            ts += 1000;
+                            $row::TestRow { ts, a: rng.gen() }
+                        })
+                        .collect::<Vec<_>>();
+
+                    // Initialize the compressor
+                    let mut compressor = $row::TestRowCompressorImpl::new(rows.len());
+
+                    // Compression
+                    for row in &rows {
+                        compressor.compress(*row);
+                    }
+
+                    // Finalize the compression after some existing bytes
+                    let finished_len = compressor.finished_len();
+                    let mut bytes = vec![0xAA; 3];
+                    compressor.finish_into(&mut bytes);
+                    let bytes = bytes.split_off(3);
+                    assert_eq!(bytes.len(), finished_len);
+
+                    // The trailer covers the frame
+                    let (body, trailer) = bytes.split_at(bytes.len() - $trailer_len);
+                    assert_eq!(trailer, $crc(body));
+
+                    // Initialize the decompressor
+                    let mut decompressor = $row::TestRowDecompressorImpl::new();
+
+                    // Decompress the bit buffer
+                    decompressor.decompress(&bytes).unwrap();
+
+                    // Assert that the decompressed data matches the original
+                    assert_eq!(decompressor.rows(), rows);
+                    let iter_rows = $row::TestRowDecompressorImpl::iter_rows(&bytes);
+                    assert_eq!(iter_rows.collect::<Result<Vec<_>, _>>().unwrap(), rows);
+
+                    // Every flipped nibble is detected
+                    for i in 0..bytes.len() {
+                        for flip in [0x01, 0x80, 0x0F, 0xF0] {
+                            let mut corrupted = bytes.clone();
+                            corrupted[i] ^= flip;
+                            let mut decompressor = $row::TestRowDecompressorImpl::new();
+                            let result = decompressor.decompress(&corrupted);
+                            assert!(matches!(result, Err(CodingError::ChecksumMismatch)));
+                            let mut iter_rows =
+                                $row::TestRowDecompressorImpl::iter_rows(&corrupted);
+                            assert!(matches!(
+                                iter_rows.next(),
+                                Some(Err(CodingError::ChecksumMismatch))
+                            ));
+                            assert!(iter_rows.next().is_none());
+                        }
+                    }
+                }
+            }
+        };
+    }
+
+    test_checksum!(test_macro_compress_crc16_trailer, crc16, 2, |body| {
+        checksum::crc16(body).to_be_bytes().to_vec()
+    });
+    test_checksum!(test_macro_compress_crc32_trailer, crc32, 4, |body| {
+        checksum::crc32(body).to_be_bytes().to_vec()
+    });
+
+    #[test]
+    fn test_macro_compress_checksum_packetizer_within_budget() {
+        let mut rng = rand::thread_rng();
+        let mut packetizer = Packetizer::<crc32::TestRowCompressorImpl>::new(100);
+        let mut frame = vec![];
+        let mut ts = 0;
+        for _ in 0..1000 {
+            ts += rng.gen_range(0..100_000);
+            let row = crc32::TestRow { ts, a: rng.gen() };
+            if packetizer.compress_into(row, &mut frame).unwrap() {
+                assert!(frame.len() <= 100);
+                let mut decompressor = crc32::TestRowDecompressorImpl::new();
+                decompressor.decompress(&frame).unwrap();
+                frame.clear();
+            }
+        }
+    }
+}
+
+mod test_ffi {
+
+    use super::*;
+    use rand::Rng;
+
+    mod row {
+        use tsz_compress::prelude::*;
+        #[derive(Debug, Copy, Clone, PartialEq, CompressV2, DecompressV2)]
+        #[tsz(ffi, checksum = "crc16")]
+        pub struct FfiTestRow {
+            pub ts: i64,
+            pub a: i16,
+            pub b: u8,
+        }
+
+        pub use compress::*;
+        pub use decompress::*;
+    }
+    use row::*;
+
+    #[test]
+    fn test_macro_ffi_round_trip() {
+        let mut rng = rand::thread_rng();
+        let rows = (0..1000)
+            .map(|i| FfiTestRow {
+                ts: 1000 * i,
+                a: rng.gen(),
+                b: rng.gen(),
+            })
+            .collect::<Vec<_>>();
+
+        unsafe {
+            // Initialize the compressor
+            let compressor = tsz_ffi_test_row_compressor_new(rows.len());
+
+            // Compression
+            for row in &rows {
+                assert_eq!(
+                    tsz_ffi_test_row_compress(compressor, row.ts, row.a, row.b),
+                    0
+                );
+            }
+
+            // A buffer smaller than the frame leaves the rows in the compressor
+            let len = tsz_ffi_test_row_finished_len(compressor);
+            let mut bytes = vec![0u8; len];
+            assert_eq!(
+                tsz_ffi_test_row_finish(compressor, bytes.as_mut_ptr(), len - 1),
+                -1
+            );
+            assert_eq!(tsz_ffi_test_row_finished_len(compressor), len);
+
+            // Finalize the compression
+            assert_eq!(
+                tsz_ffi_test_row_finish(compressor, bytes.as_mut_ptr(), len),
+                len as isize
+            );
+            tsz_ffi_test_row_compressor_free(compressor);
+
+            // Initialize the decompressor
+            let decompressor = tsz_ffi_test_row_decompressor_new();
+
+            // Decompress the bit buffer
+            assert_eq!(
+                tsz_ffi_test_row_decompress(decompressor, bytes.as_ptr(), bytes.len()),
+                0
+            );
+
+            // Assert that the decompressed data matches the original
+            let len = tsz_ffi_test_row_rows(decompressor);
+            assert_eq!(len, rows.len());
+            let ts = core::slice::from_raw_parts(tsz_ffi_test_row_col_ts(decompressor), len);
+            let a = core::slice::from_raw_parts(tsz_ffi_test_row_col_a(decompressor), len);
+            let b = core::slice::from_raw_parts(tsz_ffi_test_row_col_b(decompressor), len);
+            for (i, row) in rows.iter().enumerate() {
+                assert_eq!(
+                    *row,
+                    FfiTestRow {
+                        ts: ts[i],
+                        a: a[i],
+                        b: b[i]
+                    }
+                );
+            }
+
+            // Errors are reported as negative codes
+            tsz_ffi_test_row_decompressor_clear(decompressor);
+            assert_eq!(tsz_ffi_test_row_rows(decompressor), 0);
+            bytes[0] ^= 1;
+            let code = tsz_ffi_test_row_decompress(decompressor, bytes.as_ptr(), bytes.len());
+            assert_eq!(code, CodingError::ChecksumMismatch.code());
+            tsz_ffi_test_row_decompressor_free(decompressor);
+
+            // Null pointers are ignored
+            assert_eq!(
+                tsz_ffi_test_row_compress(core::ptr::null_mut(), 0, 0, 0),
+                CodingError::Empty.code()
+            );
+            assert_eq!(
+                tsz_ffi_test_row_finish(core::ptr::null_mut(), bytes.as_mut_ptr(), 0),
+                -1
+            );
+            assert_eq!(tsz_ffi_test_row_rows(core::ptr::null()), 0);
+            tsz_ffi_test_row_compressor_free(core::ptr::null_mut());
+            tsz_ffi_test_row_decompressor_free(core::ptr::null_mut());
+        }
+    }
+
+    #[test]
+    fn test_macro_ffi_c_header() {
+        // The compressor header declares every function
+        assert!(compress::C_HEADER
+            .contains("typedef struct FfiTestRowCompressor FfiTestRowCompressor;"));
+        assert!(compress::C_HEADER.contains(
+            "FfiTestRowCompressor *tsz_ffi_test_row_compressor_new(size_t prealloc_rows);"
+        ));
+        assert!(compress::C_HEADER.contains(
+            "int32_t tsz_ffi_test_row_compress(FfiTestRowCompressor *compressor, int64_t ts, int16_t a, uint8_t b);"
+        ));
+        assert!(compress::C_HEADER.contains("#define TSZ_ERROR_INVALID_DISCRIMINANT -10"));
+        assert!(compress::C_HEADER.contains(
+            "size_t tsz_ffi_test_row_finished_len(const FfiTestRowCompressor *compressor);"
+        ));
+        assert!(compress::C_HEADER.contains(
+            "ptrdiff_t tsz_ffi_test_row_finish(FfiTestRowCompressor *compressor, uint8_t *out, size_t capacity);"
+        ));
+        assert!(compress::C_HEADER
+            .contains("void tsz_ffi_test_row_compressor_free(FfiTestRowCompressor *compressor);"));
+
+        // The decompressor header declares every function
+        assert!(decompress::C_HEADER
+            .contains("FfiTestRowDecompressor *tsz_ffi_test_row_decompressor_new(void);"));
+        assert!(decompress::C_HEADER.contains(
+            "int32_t tsz_ffi_test_row_decompress(FfiTestRowDecompressor *decompressor, const uint8_t *bytes, size_t len);"
+        ));
+        assert!(decompress::C_HEADER
+            .contains("size_t tsz_ffi_test_row_rows(const FfiTestRowDecompressor *decompressor);"));
+        assert!(decompress::C_HEADER.contains(
+            "const int64_t *tsz_ffi_test_row_col_ts(const FfiTestRowDecompressor *decompressor);"
+        ));
+        assert!(decompress::C_HEADER.contains(
+            "const uint8_t *tsz_ffi_test_row_col_b(const FfiTestRowDecompressor *decompressor);"
+        ));
+        assert!(decompress::C_HEADER.contains("#define TSZ_ERROR_CHECKSUM_MISMATCH -9"));
+    }
+
+    #[test]
+    fn test_macro_ffi_prefix() {
+        mod row {
+            use tsz_compress::prelude::*;
+            #[derive(Debug, Copy, Clone, CompressV2, DecompressV2)]
+            #[tsz(ffi = "abcd")]
+            pub struct TestRow {
+                pub ts: u32,
+            }
+
+            pub use compress::*;
+            pub use decompress::*;
+        }
+        use row::*;
+
+        unsafe {
+            let compressor = abcd_compressor_new(1);
+            abcd_compress(compressor, 7);
+            let mut bytes = [0u8; 16];
+            let len = abcd_finish(compressor, bytes.as_mut_ptr(), bytes.len());
+            abcd_compressor_free(compressor);
+
+            let decompressor = abcd_decompressor_new();
+            assert_eq!(
+                abcd_decompress(decompressor, bytes.as_ptr(), len as usize),
+                0
+            );
+            assert_eq!(*abcd_col_ts(decompressor), 7);
+            abcd_decompressor_free(decompressor);
+        }
+        assert!(compress::C_HEADER.contains("#ifndef ABCD_COMPRESS_H"));
+    }
+
+    #[test]
+    fn test_macro_ffi_enum_discriminant() {
+        mod row {
+            use tsz_compress::prelude::*;
+
+            #[derive(Debug, Copy, Clone, PartialEq, ColumnEnum)]
+            #[repr(u8)]
+            pub enum Mode {
+                Idle,
+                Walking,
+                Running = 4,
+            }
+
+            #[derive(Debug, Copy, Clone, PartialEq, CompressV2, DecompressV2)]
+            #[tsz(ffi = "ffi_enum")]
+            pub struct TestRow {
+                pub ts: u32,
+                #[tsz(repr = "u8")]
+                pub mode: Mode,
+            }
+
+            pub use compress::*;
+            pub use decompress::*;
+        }
+        use row::*;
+
+        unsafe {
+            let compressor = ffi_enum_compressor_new(2);
+            assert_eq!(ffi_enum_compress(compressor, 1, Mode::Walking as u8), 0);
+            assert_eq!(ffi_enum_compress(compressor, 2, Mode::Running as u8), 0);
+
+            // A discriminant of no variant is rejected without compressing the row
+            assert_eq!(
+                ffi_enum_compress(compressor, 3, 2),
+                CodingError::InvalidDiscriminant.code()
+            );
+            let mut bytes = [0u8; 32];
+            let len = ffi_enum_finish(compressor, bytes.as_mut_ptr(), bytes.len());
+            ffi_enum_compressor_free(compressor);
+
+            // The column of an enum is read as its discriminant
+            let decompressor = ffi_enum_decompressor_new();
+            assert_eq!(
+                ffi_enum_decompress(decompressor, bytes.as_ptr(), len as usize),
+                0
+            );
+            let len = ffi_enum_rows(decompressor);
+            assert_eq!(len, 2);
+            let ts = core::slice::from_raw_parts(ffi_enum_col_ts(decompressor), len);
+            let mode = core::slice::from_raw_parts(ffi_enum_col_mode(decompressor), len);
+            assert_eq!(ts, [1, 2]);
+            assert_eq!(mode, [Mode::Walking as u8, Mode::Running as u8]);
+            ffi_enum_decompressor_free(decompressor);
+        }
+        assert!(compress::C_HEADER.contains(
+            "int32_t ffi_enum_compress(TestRowCompressor *compressor, uint32_t ts, uint8_t mode);"
+        ));
+        assert!(decompress::C_HEADER.contains(
+            "const uint8_t *ffi_enum_col_mode(const TestRowDecompressor *decompressor);"
+        ));
+    }
+}
+
+mod test_nullable {
+
+    use super::*;
+    use rand::Rng;
+
+    mod row {
+        use tsz_compress::prelude::*;
+        #[derive(Debug, Copy, Clone, PartialEq, CompressV2, DecompressV2)]
+        pub struct TestRow {
+            pub ts: i64,
+            pub lat: Option<i32>,
+            pub a: Option<u8>,
+            pub b: i16,
+            pub c: Option<i128>,
+            pub d: Option<u64>,
+        }
+
+        pub use compress::TestRowCompressorImpl;
+        pub use decompress::TestRowDecompressorImpl;
+    }
+    use row::*;
+
+    mod gps {
+        use tsz_compress::prelude::*;
+        #[derive(Debug, Copy, Clone, PartialEq, CompressV2, DecompressV2)]
+        #[tsz(header)]
+        pub struct GpsRow {
+            pub fix: Option<i32>,
+        }
+
+        pub use compress::GpsRowCompressorImpl;
+        pub use decompress::GpsRowDecompressorImpl;
+    }
+    use gps::*;
+
+    mod plain {
+        use tsz_compress::prelude::*;
+        #[derive(Debug, Copy, Clone, PartialEq, CompressV2, DecompressV2)]
+        pub struct PlainRow {
+            pub fix: i32,
+        }
+
+        pub use compress::PlainRowCompressorImpl;
+    }
+    use plain::*;
+
+    mod missing {
+        use tsz_compress::prelude::*;
+        #[derive(Debug, Copy, Clone, PartialEq, CompressV2, DecompressV2)]
+        pub struct MissingRow {
+            pub value: Option<i8>,
+        }
+
+        pub use compress::MissingRowCompressorImpl;
+        pub use decompress::MissingRowDecompressorImpl;
+    }
+    use missing::*;
+
+    /// Values present in runs of random lengths, or always, or never.
+    fn random_validity(len: usize) -> Vec<bool> {
+        let mut rng = rand::thread_rng();
+        match rng.gen_range(0..4) {
+            0 => vec![true; len],
+            1 => vec![false; len],
+            2 => (0..len).map(|_| rng.gen()).collect(),
+            _ => {
+                let mut present: bool = rng.gen();
+                let mut validity = Vec::with_capacity(len);
+                while validity.len() < len {
+                    let run = rng.gen_range(1..100).min(len - validity.len());
+                    validity.extend(core::iter::repeat_n(present, run));
+                    present = !present;
+                }
+                validity
+            }
+        }
+    }
+
+    fn random_rows(len: usize) -> Vec<TestRow> {
+        let mut rng = rand::thread_rng();
+        let lat = random_validity(len);
+        let a = random_validity(len);
+        let c = random_validity(len);
+        let d = random_validity(len);
+        let mut ts = rng.gen::<i64>();
+        (0..len)
+            .map(|i| {
+                ts = ts.wrapping_add(1000);
+                TestRow {
+                    ts,
+                    lat: lat[i].then(|| 473_977_000 + rng.gen_range(-50..50)),
+                    a: a[i].then(|| rng.gen()),
+                    b: rng.gen_range(-100..100),
+                    c: c[i].then(|| i as i128 * 1000),
+                    d: d[i].then(|| rng.gen::<u64>() >> rng.gen_range(0..64)),
+                }
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_macro_compress_nullable_round_trip() {
+        let mut rng = rand::thread_rng();
+
+        for _ in 0..100 {
+            let rows = random_rows(rng.gen_range(1..1000));
+
+            // Initialize the compressor
+            let mut compressor = TestRowCompressorImpl::new(rows.len());
+
+            // Compression
+            for row in &rows {
+                compressor.compress(*row);
+            }
+
+            // Finalize the compression
+            let finished_len = compressor.finished_len();
+            let bytes = compressor.finish();
+            assert_eq!(bytes.len(), finished_len);
+
+            // Initialize the decompressor
+            let mut decompressor = TestRowDecompressorImpl::new();
+
+            // Decompress the bit buffer
+            decompressor.decompress(&bytes).unwrap();
+
+            // Assert that the decompressed data matches the original
+            assert_eq!(decompressor.rows(), rows);
+            assert_eq!(
+                decompressor.col_lat(),
+                rows.iter().map(|row| row.lat).collect::<Vec<_>>()
+            );
+            let iter_rows = TestRowDecompressorImpl::iter_rows(&bytes)
+                .collect::<Result<Vec<_>, _>>()
+                .unwrap();
+            assert_eq!(iter_rows, rows);
+        }
+    }
+
+    #[test]
+    fn test_macro_compress_nullable_all_none_omitted() {
+        for len in [1, 2, 3, 10, 1000] {
+            // Initialize the compressor
+            let mut compressor = GpsRowCompressorImpl::new(len);
+
+            // Compression
+            for _ in 0..len {
+                compressor.compress(GpsRow { fix: None });
+            }
+
+            // Finalize the compression
+            let finished_len = compressor.finished_len();
+            let bytes = compressor.finish();
+            assert_eq!(bytes.len(), finished_len);
+
+            // The frame header, the row count and the nibble of column presence padded to a byte
+            let header_len = 4 + 1 + 4;
+            assert_eq!(bytes.len(), header_len + 4 + 1);
+
+            // Initialize the decompressor
+            let mut decompressor = GpsRowDecompressorImpl::new();
+
+            // Decompress the bit buffer
+            decompressor.decompress(&bytes).unwrap();
+
+            // Assert that the decompressed data matches the original
+            assert_eq!(decompressor.col_fix(), vec![None; len]);
+            assert_eq!(GpsRowDecompressorImpl::iter_rows(&bytes).count(), len);
+        }
+    }
+
+    #[test]
+    fn test_macro_compress_nullable_deltas_across_present_values() {
+        let mut rng = rand::thread_rng();
+
+        for _ in 0..100 {
+            // A slowly drifting fix that is lost in short runs
+            let len = rng.gen_range(1..2000);
+            let mut fix = rng.gen::<i32>();
+            let mut present = true;
+            let mut runs: usize = 0;
+            let mut rows = Vec::with_capacity(len);
+            while rows.len() < len {
+                let run = rng.gen_range(1..8).min(len - rows.len());
+                for _ in 0..run {
+                    fix = fix.wrapping_add(rng.gen_range(-3..=3));
+                    rows.push(GpsRow {
+                        fix: present.then_some(fix),
+                    });
+                }
+                runs += 1;
+                present = !present;
+            }
+
+            // Compress the rows and the present values alone
+            let mut compressor = GpsRowCompressorImpl::new(len);
+            let mut plain_compressor = PlainRowCompressorImpl::new(len);
+            for row in &rows {
+                compressor.compress(*row);
+                if let Some(fix) = row.fix {
+                    plain_compressor.compress(PlainRow { fix });
+                }
+            }
+            let bytes = compressor.finish();
+            let plain_bytes = plain_compressor.finish();
+
+            // Deltas across the present values cost the same as without the missing values,
+            // adding one nibble per run, the column presence and the frame header
+            let header_len = 4 + 1 + 4;
+            assert!(bytes.len() <= header_len + plain_bytes.len() + runs.div_ceil(2) + 1);
+
+            // Initialize the decompressor
+            let mut decompressor = GpsRowDecompressorImpl::new();
+
+            // Decompress the bit buffer
+            decompressor.decompress(&bytes).unwrap();
+
+            // Assert that the decompressed data matches the original
+            assert_eq!(decompressor.rows(), rows);
+        }
+    }
+
+    #[test]
+    fn test_macro_compress_nullable_frame_header() {
+        use tsz_compress::prelude::consts::frame;
+        assert_eq!(
+            GpsRowCompressorImpl::COLUMN_TYPES,
+            [frame::I32 | frame::NULLABLE]
+        );
+        assert_ne!(
+            GpsRowCompressorImpl::SCHEMA_HASH,
+            PlainRowCompressorImpl::SCHEMA_HASH
+        );
+    }
+
+    #[test]
+    fn test_macro_decompress_nullable_truncated() {
+        let rows = random_rows(500);
+
+        // Initialize the compressor
+        let mut compressor = TestRowCompressorImpl::new(rows.len());
+
+        // Compression
+        for row in &rows {
+            compressor.compress(*row);
+        }
+
+        // Finalize the compression
+        let bytes = compressor.finish();
+
+        // Truncated frames fail to decode without panicking
+        for len in 0..bytes.len() {
+            let mut decompressor = TestRowDecompressorImpl::new();
+            assert!(decompressor.decompress(&bytes[..len]).is_err());
+            assert!(TestRowDecompressorImpl::iter_rows(&bytes[..len]).any(|row| row.is_err()));
+        }
+    }
+
+    #[test]
+    fn test_macro_decompress_nullable_corrupted_row_count() {
+        // Initialize the compressor
+        let mut compressor = MissingRowCompressorImpl::new(10);
+
+        // Compression
+        for _ in 0..10 {
+            compressor.compress(MissingRow { value: None });
+        }
+
+        // Finalize the compression
+        let mut bytes = compressor.finish();
+
+        // A frame of only omitted columns may hold at most MAX_OMITTED_ROWS rows
+        let mut decompressor = MissingRowDecompressorImpl::new();
+        for rows in [MAX_OMITTED_ROWS as u32 + 1, u32::MAX] {
+            bytes[..4].copy_from_slice(&rows.to_be_bytes());
+            assert!(matches!(
+                decompressor.decompress(&bytes),
+                Err(CodingError::InvalidRowCount(invalid)) if invalid == rows as usize
+            ));
+            assert!(decompressor.col_value().is_empty());
+        }
+        bytes[..4].copy_from_slice(&1000u32.to_be_bytes());
+        decompressor.decompress(&bytes).unwrap();
+        assert_eq!(decompressor.col_value(), [None; 1000]);
+    }
+
+    #[test]
+    fn test_macro_decompress_omitted_corrupted_row_count() {
+        // Initialize the compressor
+        let mut compressor = TestRowCompressorImpl::new(10);
+
+        // Compression
+        for i in 0..10 {
+            compressor.compress(TestRow {
+                ts: i,
+                lat: None,
+                a: None,
+                b: 3,
+                c: None,
+                d: None,
+            });
+        }
+
+        // Finalize the compression
+        let mut bytes = compressor.finish();
+
+        // The omitted columns are not filled when the columns in the frame disagree with the row count
+        bytes[..4].copy_from_slice(&u32::MAX.to_be_bytes());
+        let mut decompressor = TestRowDecompressorImpl::new();
+        match decompressor.decompress(&bytes) {
+            Err(CodingError::ColumnLengthMismatch(lengths)) => {
+                assert_eq!(lengths.expected_rows, u32::MAX as usize);
+                assert_eq!(lengths.column_lengths, [10, 0, 0, 10, 0, 0]);
+            }
+            result => panic!("unexpected {result:?}"),
+        }
+        assert!(decompressor.rows().is_empty());
+    }
+}
+
+mod test_float {
+
+    use super::*;
+    use rand::Rng;
+
+    mod row {
+        use tsz_compress::prelude::*;
+        #[derive(Debug, Copy, Clone, CompressV2, DecompressV2)]
+        #[tsz(header)]
+        pub struct TestRow {
+            pub ts: i64,
+            pub temperature: f32,
+            pub battery: f64,
+            pub count: u16,
+            pub humidity: Option<f32>,
+        }
+
+        pub use compress::TestRowCompressorImpl;
+        pub use decompress::TestRowDecompressorImpl;
+    }
+    use row::*;
+
+    mod temperature {
+        use tsz_compress::prelude::*;
+        #[derive(Debug, Copy, Clone, CompressV2, DecompressV2)]
+        pub struct TemperatureRow {
+            pub temperature: f32,
+        }
+
+        pub use compress::TemperatureRowCompressorImpl;
+        pub use decompress::TemperatureRowDecompressorImpl;
+    }
+    use temperature::*;
+
+    /// Compare the bits of each row, so that NaN and -0.0 must round trip exactly.
+    fn row_bits(rows: &[TestRow]) -> Vec<(i64, u32, u64, u16, Option<u32>)> {
+        rows.iter()
+            .map(|row| {
+                (
+                    row.ts,
+                    row.temperature.to_bits(),
+                    row.battery.to_bits(),
+                    row.count,
+                    row.humidity.map(f32::to_bits),
+                )
+            })
+            .collect()
+    }
+
+    fn random_rows(len: usize) -> Vec<TestRow> {
+        let mut rng = rand::thread_rng();
+        let mut ts = rng.gen::<i64>();
+        let mut temperature = rng.gen_range(-40.0..85.0);
+        let mut battery = 4.2;
+        (0..len)
+            .map(|i| {
+                ts = ts.wrapping_add(1000);
+                if rng.gen_bool(0.3) {
+                    temperature += rng.gen_range(-0.5..0.5);
+                }
+                battery -= rng.gen_range(0.0..0.0001);
+                TestRow {
+                    ts,
+                    temperature: match i % 97 {
+                        13 => f32::NAN,
+                        29 => -0.0,
+                        31 => f32::INFINITY,
+                        _ => temperature,
+                    },
+                    battery: if i % 89 == 7 {
+                        f64::from_bits(rng.gen())
+                    } else {
+                        battery
+                    },
+                    count: i as u16,
+                    humidity: rng.gen_bool(0.8).then(|| rng.gen_range(0.0..100.0)),
+                }
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_macro_compress_float_round_trip() {
+        let mut rng = rand::thread_rng();
+
+        for _ in 0..100 {
+            let rows = random_rows(rng.gen_range(0..1000));
+
+            // Initialize the compressor
+            let mut compressor = TestRowCompressorImpl::new(rows.len());
+
+            // Compression
+            for row in &rows {
+                compressor.compress(*row);
+            }
+
+            // Finalize the compression
+            let finished_len = compressor.finished_len();
+            let bytes = compressor.finish();
+            assert_eq!(bytes.len(), finished_len);
+
+            // Initialize the decompressor
+            let mut decompressor = TestRowDecompressorImpl::new();
+
+            // Decompress the bit buffer
+            decompressor.decompress(&bytes).unwrap();
+
+            // Assert that the decompressed data matches the original
+            assert_eq!(row_bits(&decompressor.rows()), row_bits(&rows));
+            if rows.is_empty() {
+                continue;
+            }
+            let iter_rows = TestRowDecompressorImpl::iter_rows(&bytes)
+                .collect::<Result<Vec<_>, _>>()
+                .unwrap();
+            assert_eq!(row_bits(&iter_rows), row_bits(&rows));
+        }
+    }
+
+    #[test]
+    fn test_macro_compress_float_slowly_changing() {
+        // A reading quantized by the sensor changes in a few mantissa bits
+        let values = (0..1000)
+            .map(|i| 21.5 + ((i / 50) % 8) as f32 * 0.125)
+            .collect::<Vec<_>>();
+
+        // Initialize the compressor
+        let mut compressor = TemperatureRowCompressorImpl::new(values.len());
+
+        // Compression
+        for temperature in &values {
+            compressor.compress(TemperatureRow {
+                temperature: *temperature,
+            });
+        }
+
+        // Finalize the compression
+        let bytes = compressor.finish();
+
+        // Mostly unchanged values take a nibble each
+        assert!(bytes.len() < values.len() * 3 / 4, "{}", bytes.len());
+
+        // Initialize the decompressor
+        let mut decompressor = TemperatureRowDecompressorImpl::new();
+
+        // Decompress the bit buffer
+        decompressor.decompress(&bytes).unwrap();
+
+        // Assert that the decompressed data matches the original
+        assert_eq!(decompressor.col_temperature(), values);
+    }
+
+    #[test]
+    fn test_macro_compress_float_frame_header() {
+        use tsz_compress::prelude::consts::frame;
+        assert_eq!(
+            TestRowCompressorImpl::COLUMN_TYPES,
+            [
+                frame::I64,
+                frame::F32,
+                frame::F64,
+                frame::U16,
+                frame::F32 | frame::NULLABLE
+            ]
+        );
+    }
+}
+
+mod test_scaled {
+
+    use super::*;
+    use rand::Rng;
+
+    mod row {
+        use tsz_compress::prelude::*;
+        #[derive(Debug, Copy, Clone, PartialEq, CompressV2, DecompressV2)]
+        pub struct TestRow {
+            pub ts: i64,
+            #[tsz(scale = 100, as = "i16")]
+            pub temperature: f32,
+            #[tsz(scale = 1000, as = "i32")]
+            pub volts: f64,
+            #[tsz(scale = 2, as = "u8")]
+            pub humidity: Option<f32>,
+        }
+
+        pub use compress::TestRowCompressorImpl;
+        pub use decompress::TestRowDecompressorImpl;
+    }
+    use row::*;
+
+    mod raw {
+        use tsz_compress::prelude::*;
+        #[derive(Debug, Copy, Clone, PartialEq, CompressV2, DecompressV2)]
+        pub struct RawRow {
+            pub ts: i64,
+            pub temperature: i16,
+            pub volts: i32,
+            pub humidity: Option<u8>,
+        }
+
+        pub use compress::RawRowCompressorImpl;
+    }
+    use raw::*;
+
+    fn random_rows(len: usize) -> Vec<TestRow> {
+        let mut rng = rand::thread_rng();
+        let mut ts = rng.gen::<i64>();
+        let mut temperature: f32 = rng.gen_range(-40.0..85.0);
+        let mut volts: f64 = rng.gen_range(3.0..4.2);
+        (0..len)
+            .map(|_| {
+                ts = ts.wrapping_add(1000);
+                temperature = (temperature + rng.gen_range(-0.05..0.05)).clamp(-40.0, 85.0);
+                volts = (volts + rng.gen_range(-0.002..0.002)).clamp(3.0, 4.2);
+                TestRow {
+                    ts,
+                    temperature,
+                    volts,
+                    humidity: rng.gen_bool(0.9).then(|| rng.gen_range(0.0..100.0)),
+                }
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_macro_compress_scaled_round_trip() {
+        let mut rng = rand::thread_rng();
+
+        for _ in 0..100 {
+            let rows = random_rows(rng.gen_range(1..1000));
+
+            // Initialize the compressor
+            let mut compressor = TestRowCompressorImpl::new(rows.len());
+            let mut raw_compressor = RawRowCompressorImpl::new(rows.len());
+
+            // Compression
+            for row in &rows {
+                compressor.compress(*row);
+                raw_compressor.compress(RawRow {
+                    ts: row.ts,
+                    temperature: (row.temperature * 100.0).round() as i16,
+                    volts: (row.volts * 1000.0).round() as i32,
+                    humidity: row.humidity.map(|humidity| (humidity * 2.0).round() as u8),
+                });
+            }
+
+            // Finalize the compression
+            let finished_len = compressor.finished_len();
+            let bytes = compressor.finish();
+            assert_eq!(bytes.len(), finished_len);
+
+            // The frame is the frame of the quantized integer columns
+            assert_eq!(bytes, raw_compressor.finish());
+
+            // Initialize the decompressor
+            let mut decompressor = TestRowDecompressorImpl::new();
+
+            // Decompress the bit buffer
+            decompressor.decompress(&bytes).unwrap();
+
+            // Assert that the decompressed data matches the original within the precision of the scale
+            let decompressed = decompressor.rows();
+            assert_eq!(decompressed.len(), rows.len());
+            for (row, decompressed) in rows.iter().zip(&decompressed) {
+                assert_eq!(row.ts, decompressed.ts);
+                assert!((row.temperature - decompressed.temperature).abs() <= 0.0051);
+                assert!((row.volts - decompressed.volts).abs() <= 0.00051);
+                assert_eq!(row.humidity.is_some(), decompressed.humidity.is_some());
+                if let (Some(humidity), Some(decompressed)) = (row.humidity, decompressed.humidity)
+                {
+                    assert!((humidity - decompressed).abs() <= 0.25);
+                }
+            }
+
+            // The integer and rescaled columns are both exposed
+            assert_eq!(decompressor.col_temperature().len(), rows.len());
+            for (raw, value) in decompressor
+                .col_temperature_raw()
+                .iter()
+                .zip(decompressor.col_temperature())
+            {
+                assert_eq!(*raw as f32 / 100.0, *value);
+            }
+            for (raw, value) in decompressor
+                .col_humidity_raw()
+                .iter()
+                .zip(decompressor.col_humidity())
+            {
+                assert_eq!(raw.map(|raw| raw as f32 / 2.0), *value);
+            }
+
+            // Lazily decompress the bit buffer
+            let iter_rows = TestRowDecompressorImpl::iter_rows(&bytes)
+                .collect::<Result<Vec<_>, _>>()
+                .unwrap();
+            assert_eq!(iter_rows, decompressed);
+        }
+    }
+
+    #[test]
+    fn test_macro_compress_scaled_rounding_and_saturation() {
+        let rows = [
+            (0.004, 0),
+            (0.005, 1),
+            (-0.005, -1),
+            (-0.004, 0),
+            (327.67, i16::MAX),
+            (1000.0, i16::MAX),
+            (-1000.0, i16::MIN),
+            (f32::NAN, 0),
+        ];
+
+        // Initialize the compressor
+        let mut compressor = TestRowCompressorImpl::new(rows.len());
+
+        // Compression
+        for (temperature, _) in rows {
+            compressor.compress(TestRow {
+                ts: 0,
+                temperature,
+                volts: 0.0,
+                humidity: None,
+            });
+        }
+
+        // Finalize the compression
+        let bytes = compressor.finish();
+
+        // Initialize the decompressor
+        let mut decompressor = TestRowDecompressorImpl::new();
+
+        // Decompress the bit buffer
+        decompressor.decompress(&bytes).unwrap();
+
+        // Assert that the values were rounded half away from zero and saturated
+        assert_eq!(decompressor.col_temperature_raw(), rows.map(|(_, raw)| raw));
+    }
+}
+
+mod test_max_error {
+
+    use super::*;
+    use rand::Rng;
+
+    mod row {
+        use tsz_compress::prelude::*;
+        #[derive(Debug, Copy, Clone, PartialEq, CompressV2, DecompressV2)]
+        pub struct TestRow {
+            pub ts: i64,
+            #[tsz(max_error = 4)]
+            pub accel: i16,
+            #[tsz(max_error = 1000)]
+            pub position: i64,
+            #[tsz(max_error = 2)]
+            pub level: u8,
+            #[tsz(max_error = 3)]
+            pub pressure: Option<i32>,
+            pub exact: i32,
+        }
+
+        pub use compress::TestRowCompressorImpl;
+        pub use decompress::TestRowDecompressorImpl;
+    }
+    use row::*;
+
+    mod lossless {
+        use tsz_compress::prelude::*;
+        #[derive(Debug, Copy, Clone, PartialEq, CompressV2, DecompressV2)]
+        pub struct LosslessRow {
+            pub ts: i64,
+            pub accel: i16,
+            pub position: i64,
+            pub level: u8,
+            pub pressure: Option<i32>,
+            pub exact: i32,
+        }
+
+        pub use compress::LosslessRowCompressorImpl;
+    }
+    use lossless::*;
+
+    fn assert_within_bound(rows: &[TestRow], decompressed: &[TestRow]) {
+        assert_eq!(decompressed.len(), rows.len());
+        for (row, decompressed) in rows.iter().zip(decompressed) {
+            assert_eq!(row.ts, decompressed.ts);
+            assert!((row.accel as i32 - decompressed.accel as i32).abs() <= 4);
+            assert!((row.position as i128 - decompressed.position as i128).abs() <= 1000);
+            assert!((row.level as i32 - decompressed.level as i32).abs() <= 2);
+            assert_eq!(row.pressure.is_some(), decompressed.pressure.is_some());
+            if let (Some(pressure), Some(decompressed)) = (row.pressure, decompressed.pressure) {
+                assert!((pressure as i64 - decompressed as i64).abs() <= 3);
+            }
+            assert_eq!(row.exact, decompressed.exact);
+        }
+    }
+
+    #[test]
+    fn test_macro_compress_max_error_random_walk() {
+        let mut rng = rand::thread_rng();
+        let mut lossy_len = 0;
+        let mut lossless_len = 0;
+
+        for _ in 0..100 {
+            // Noisy random walks, which never drift from the input however long the frame
+            let len = rng.gen_range(1..5000);
+            let mut ts = rng.gen::<i64>();
+            let mut accel: i16 = rng.gen();
+            let mut position: i64 = rng.gen();
+            let mut level: u8 = rng.gen();
+            let mut pressure: i32 = rng.gen_range(90000..110000);
+            let mut exact: i32 = rng.gen();
+            let rows = (0..len)
+                .map(|_| {
+                    ts = ts.wrapping_add(10);
+                    accel = accel.saturating_add(rng.gen_range(-6..=6));
+                    position = position.wrapping_add(rng.gen_range(-2000..=2000));
+                    level = level.saturating_add_signed(rng.gen_range(-3..=3));
+                    pressure += rng.gen_range(-5..=5);
+                    exact = exact.wrapping_add(rng.gen_range(-8..=8));
+                    TestRow {
+                        ts,
+                        accel,
+                        position,
+                        level,
+                        pressure: rng.gen_bool(0.9).then_some(pressure),
+                        exact,
+                    }
+                })
+                .collect::<Vec<_>>();
+
+            // Initialize the compressor
+            let mut compressor = TestRowCompressorImpl::new(rows.len());
+            let mut lossless_compressor = LosslessRowCompressorImpl::new(rows.len());
+
+            // Compression
+            for row in &rows {
+                compressor.compress(*row);
+                lossless_compressor.compress(LosslessRow {
+                    ts: row.ts,
+                    accel: row.accel,
+                    position: row.position,
+                    level: row.level,
+                    pressure: row.pressure,
+                    exact: row.exact,
+                });
+            }
+
+            // Finalize the compression
+            let finished_len = compressor.finished_len();
+            let bytes = compressor.finish();
+            assert_eq!(bytes.len(), finished_len);
+            lossy_len += bytes.len();
+            lossless_len += lossless_compressor.finish().len();
+
+            // Initialize the decompressor
+            let mut decompressor = TestRowDecompressorImpl::new();
+
+            // Decompress the bit buffer
+            decompressor.decompress(&bytes).unwrap();
+
+            // Assert that the decompressed data is within the bound of the original
+            assert_within_bound(&rows, &decompressor.rows());
+        }
+
+        // The bound is traded for smaller deltas
+        assert!(lossy_len < lossless_len);
+    }
+
+    #[test]
+    fn test_macro_compress_max_error_extremes() {
+        let mut rng = rand::thread_rng();
+
+        for _ in 0..100 {
+            // Values jumping across the whole range and pinned at the bounds of the columns
+            let rows = (0..rng.gen_range(1..1000))
+                .map(|i| {
+                    let pinned = rng.gen_range(0..4);
+                    TestRow {
+                        ts: i,
+                        accel: [i16::MIN, i16::MAX, rng.gen(), 0][pinned],
+                        position: [i64::MIN, i64::MAX, rng.gen(), 0][pinned],
+                        level: [u8::MIN, u8::MAX, rng.gen(), 1][pinned],
+                        pressure: rng
+                            .gen_bool(0.5)
+                            .then_some([i32::MIN, i32::MAX, rng.gen(), -1][pinned]),
+                        exact: [i32::MIN, i32::MAX, rng.gen(), 0][pinned],
+                    }
+                })
+                .collect::<Vec<_>>();
+
+            // Initialize the compressor
+            let mut compressor = TestRowCompressorImpl::new(rows.len());
+
+            // Compression
+            for row in &rows {
+                compressor.compress(*row);
+            }
+
+            // Finalize the compression
+            let bytes = compressor.finish();
+
+            // Initialize the decompressor
+            let mut decompressor = TestRowDecompressorImpl::new();
+
+            // Decompress the bit buffer
+            decompressor.decompress(&bytes).unwrap();
+
+            // Assert that the decompressed data is within the bound of the original
+            assert_within_bound(&rows, &decompressor.rows());
+        }
+    }
+}
+
+mod test_zero_runs {
+
+    use super::*;
+    use rand::Rng;
+
+    mod row {
+        use tsz_compress::prelude::*;
+        #[derive(Debug, Copy, Clone, PartialEq, CompressV2, DecompressV2)]
+        pub struct TestRow {
+            pub ts: i64,
+            pub status: u8,
+            pub reading: i32,
+            pub counter: u64,
+            pub id: i128,
+            pub battery: Option<i16>,
+        }
+
+        pub use compress::TestRowCompressorImpl;
+        pub use decompress::TestRowDecompressorImpl;
+    }
+    use row::*;
+
+    mod status {
+        use tsz_compress::prelude::*;
+        #[derive(Debug, Copy, Clone, PartialEq, CompressV2, DecompressV2)]
+        pub struct StatusRow {
+            pub status: u8,
+        }
+
+        pub use compress::StatusRowCompressorImpl;
+        pub use decompress::StatusRowDecompressorImpl;
+    }
+    use status::*;
+
+    #[test]
+    fn test_macro_compress_zero_runs_round_trip() {
+        let mut rng = rand::thread_rng();
+
+        for _ in 0..100 {
+            // Columns that are idle for long stretches between bursts of activity
+            let mut ts = rng.gen::<i64>();
+            let mut status: u8 = rng.gen();
+            let mut reading: i32 = rng.gen();
+            let mut counter: u64 = rng.gen();
+            let mut id: i128 = rng.gen();
+            let mut battery: i16 = rng.gen();
+            let mut idle = 0;
+            let rows = (0..rng.gen_range(1..5000))
+                .map(|_| {
+                    if idle == 0 {
+                        idle = rng.gen_range(0..500);
+                        status = rng.gen();
+                        battery = battery.wrapping_add(rng.gen_range(-3..=3));
+                    }
+                    idle -= idle.min(1);
+                    if idle % 7 == 0 {
+                        reading = reading.wrapping_add(rng.gen_range(-100..=100));
+                    }
+                    ts = ts.wrapping_add(1000);
+                    counter = counter.wrapping_add(3);
+                    id = id.wrapping_add(1 << 80);
+                    TestRow {
+                        ts,
+                        status,
+                        reading,
+                        counter,
+                        id,
+                        battery: rng.gen_bool(0.99).then_some(battery),
+                    }
+                })
+                .collect::<Vec<_>>();
+
+            // Initialize the compressor
+            let mut compressor = TestRowCompressorImpl::new(rows.len());
+
+            // Compression
+            for row in &rows {
+                compressor.compress(*row);
+            }
+
+            // Finalize the compression
+            let finished_len = compressor.finished_len();
+            let bytes = compressor.finish();
+            assert_eq!(bytes.len(), finished_len);
+
+            // Initialize the decompressor
+            let mut decompressor = TestRowDecompressorImpl::new();
+
+            // Decompress the bit buffer
+            decompressor.decompress(&bytes).unwrap();
+
+            // Assert that the decompressed data matches the original
+            assert_eq!(decompressor.rows(), rows);
+            let iter_rows = TestRowDecompressorImpl::iter_rows(&bytes);
+            assert_eq!(iter_rows.collect::<Result<Vec<_>, _>>().unwrap(), rows);
+        }
+    }
+
+    #[test]
+    fn test_macro_compress_zero_runs_constant_column() {
+        for len in [1, 2, 3, 12, 13, 100, 1000, 100_000] {
+            // Initialize the compressor
+            let mut compressor = StatusRowCompressorImpl::new(len);
+
+            // Compression
+            for _ in 0..len {
+                compressor.compress(StatusRow { status: 7 });
+            }
+
+            // Finalize the compression
+            let finished_len = compressor.finished_len();
+            let bytes = compressor.finish();
+            assert_eq!(bytes.len(), finished_len);
+
+            // A constant column is a run of zeros after the first two rows
+            assert!(bytes.len() <= 16, "{} rows in {} bytes", len, bytes.len());
+
+            // Initialize the decompressor
+            let mut decompressor = StatusRowDecompressorImpl::new();
+
+            // Decompress the bit buffer
+            decompressor.decompress(&bytes).unwrap();
+
+            // Assert that the decompressed data matches the original
+            assert_eq!(decompressor.col_status(), vec![7; len]);
+        }
+    }
+
+    /// A frame of a constant status column written by hand: the row count, the first two rows, then a run of zeros.
+    fn constant_status_frame(rows: u32, run: u64) -> Vec<u8> {
+        use tsz_compress::prelude::consts::headers;
+        use tsz_compress::prelude::halfvec::{HalfVec, HalfWord};
+
+        let mut buf = HalfVec::new(32);
+        write_i32_bits(&mut buf, rows as i32);
+        buf.push(HalfWord::Half(headers::START_OF_COLUMN));
+        buf.push(HalfWord::Half(headers::FIRST_ROW));
+        write_signed_vlq(&mut buf, 7);
+        buf.push(HalfWord::Half(headers::SECOND_ROW));
+        write_signed_vlq(&mut buf, 0);
+        buf.push(HalfWord::Half(headers::ZERO_RUN));
+
+        // The run length as a VLQ of 3 bits per nibble, the high bit marking more nibbles
+        let nibbles = (64 - run.leading_zeros() as usize).div_ceil(3).max(1);
+        for i in (0..nibbles).rev() {
+            let more = if i > 0 { 0b1000 } else { 0 };
+            buf.push(HalfWord::Half(more | ((run >> (3 * i)) & 0b111) as u8));
+        }
+
+        let mut bytes = Vec::new();
+        HalfVec::finish(&mut bytes, [&buf].into_iter());
+        bytes
+    }
+
+    #[test]
+    fn test_macro_decompress_zero_run_past_row_count() {
+        // The run covers the rows after the first two
+        let bytes = constant_status_frame(30, 28);
+        let mut decompressor = StatusRowDecompressorImpl::new();
+        decompressor.decompress(&bytes).unwrap();
+        assert_eq!(decompressor.col_status(), vec![7; 30]);
+
+        // A run past the row count of the frame is invalid, without decoding the run
+        let bytes = constant_status_frame(30, u32::MAX as u64 - 2);
+        assert_eq!(bytes.len(), 13);
+        let mut decompressor = StatusRowDecompressorImpl::new();
+        assert!(matches!(
+            decompressor.decompress(&bytes),
+            Err(CodingError::InvalidBits)
+        ));
+
+        // A column shorter than the row count of the frame is invalid
+        let bytes = constant_status_frame(30, 20);
+        let mut decompressor = StatusRowDecompressorImpl::new();
+        assert!(matches!(
+            decompressor.decompress(&bytes),
+            Err(CodingError::ColumnLengthMismatch(ColumnLengths {
+                expected_rows: 30,
+                ..
+            }))
+        ));
+        assert!(decompressor.col_status().is_empty());
+        assert!(StatusRowDecompressorImpl::iter_rows(&bytes).any(|row| row.is_err()));
+    }
+}
+
+mod test_transitions {
+
+    use super::*;
+    use rand::Rng;
+
+    mod row {
+        use tsz_compress::prelude::*;
+        #[derive(Debug, Copy, Clone, PartialEq, CompressV2, DecompressV2)]
+        #[tsz(header)]
+        pub struct TestRow {
+            pub ts: i64,
+            pub charging: bool,
+            pub pressed: bool,
+            pub saturated: Option<bool>,
+            #[tsz(flags)]
+            pub status: u16,
+            #[tsz(flags)]
+            pub mask: i64,
+            #[tsz(flags)]
+            pub leds: u8,
+        }
+
+        pub use compress::TestRowCompressorImpl;
+        pub use decompress::TestRowDecompressorImpl;
+    }
+    use row::*;
+
+    mod status {
+        use tsz_compress::prelude::*;
+        #[derive(Debug, Copy, Clone, PartialEq, CompressV2, DecompressV2)]
+        pub struct FlagsRow {
+            #[tsz(flags)]
+            pub status: u16,
+            pub charging: bool,
+        }
+
+        pub use compress::FlagsRowCompressorImpl;
+        pub use decompress::FlagsRowDecompressorImpl;
+    }
+    use status::*;
+
+    mod plain {
+        use tsz_compress::prelude::*;
+        #[derive(Debug, Copy, Clone, PartialEq, CompressV2, DecompressV2)]
+        pub struct PlainRow {
+            pub status: u16,
+            pub charging: u8,
+        }
+
+        pub use compress::PlainRowCompressorImpl;
+        pub use decompress::PlainRowDecompressorImpl;
+    }
+    use plain::*;
+
+    mod raw {
+        use tsz_compress::prelude::*;
+        #[derive(Debug, Copy, Clone, PartialEq, CompressV2, DecompressV2)]
+        pub struct RawRow {
+            #[tsz(flags)]
+            pub status: u16,
+            #[tsz(flags)]
+            pub charging: u8,
+        }
+
+        pub use compress::RawRowCompressorImpl;
+    }
+    use raw::*;
+
+    fn toggle<T: Copy + core::ops::BitXor<Output = T>>(
+        rng: &mut impl Rng,
+        value: T,
+        bits: &[T],
+    ) -> T {
+        if rng.gen_bool(0.02) {
+            value ^ bits[rng.gen_range(0..bits.len())]
+        } else {
+            value
+        }
+    }
+
+    #[test]
+    fn test_macro_compress_transitions_round_trip() {
+        let mut rng = rand::thread_rng();
+
+        for _ in 0..100 {
+            // Status bits that toggle rarely
+            let mut ts = rng.gen::<i64>();
+            let mut charging: bool = rng.gen();
+            let mut pressed: bool = rng.gen();
+            let mut saturated: bool = rng.gen();
+            let mut status: u16 = rng.gen();
+            let mut mask: i64 = rng.gen();
+            let mut leds: u8 = rng.gen();
+            let rows = (0..rng.gen_range(1..5000))
+                .map(|_| {
+                    ts = ts.wrapping_add(1000);
+                    charging = toggle(&mut rng, charging, &[true]);
+                    pressed = toggle(&mut rng, pressed, &[true]);
+                    saturated = toggle(&mut rng, saturated, &[true]);
+                    status = toggle(&mut rng, status, &[1, 1 << 7, 1 << 15, u16::MAX]);
+                    mask = toggle(&mut rng, mask, &[1, 1 << 40, i64::MIN, -1]);
+                    leds = toggle(&mut rng, leds, &[1, 1 << 3, 1 << 7]);
+                    TestRow {
+                        ts,
+                        charging,
+                        pressed,
+                        saturated: rng.gen_bool(0.9).then_some(saturated),
+                        status,
+                        mask,
+                        leds,
+                    }
+                })
+                .collect::<Vec<_>>();
+
+            // Initialize the compressor
+            let mut compressor = TestRowCompressorImpl::new(rows.len());
+
+            // Compression
+            for row in &rows {
+                compressor.compress(*row);
+            }
+
+            // Finalize the compression
+            let finished_len = compressor.finished_len();
+            let bytes = compressor.finish();
+            assert_eq!(bytes.len(), finished_len);
+
+            // Initialize the decompressor
+            let mut decompressor = TestRowDecompressorImpl::new();
+
+            // Decompress the bit buffer
+            decompressor.decompress(&bytes).unwrap();
+
+            // Assert that the decompressed data matches the original
+            assert_eq!(decompressor.rows(), rows);
+            let iter_rows = TestRowDecompressorImpl::iter_rows(&bytes);
+            assert_eq!(iter_rows.collect::<Result<Vec<_>, _>>().unwrap(), rows);
+        }
+
+        // The frame header marks the bool and flags columns
+        assert_eq!(
+            TestRowCompressorImpl::COLUMN_TYPES[1..],
+            [
+                consts::frame::BOOL,
+                consts::frame::BOOL,
+                consts::frame::BOOL | consts::frame::NULLABLE,
+                consts::frame::U16 | consts::frame::FLAGS,
+                consts::frame::I64 | consts::frame::FLAGS,
+                consts::frame::U8 | consts::frame::FLAGS,
+            ]
+        );
+    }
+
+    #[test]
+    fn test_macro_compress_transitions_smaller_than_deltas() {
+        let mut rng = rand::thread_rng();
+
+        // A status word whose high bits toggle rarely
+        let mut status: u16 = 0;
+        let mut charging = false;
+        let rows = (0..100_000)
+            .map(|_| {
+                status = toggle(&mut rng, status, &[1 << 14, 1 << 15]);
+                charging = toggle(&mut rng, charging, &[true]);
+                FlagsRow { status, charging }
+            })
+            .collect::<Vec<_>>();
+
+        // Initialize the compressor
+        let mut compressor = FlagsRowCompressorImpl::new(rows.len());
+        let mut plain_compressor = PlainRowCompressorImpl::new(rows.len());
+
+        // Compression
+        for row in &rows {
+            compressor.compress(*row);
+            plain_compressor.compress(PlainRow {
+                status: row.status,
+                charging: row.charging as u8,
+            });
+        }
+
+        // Finalize the compression
+        let bytes = compressor.finish();
+        let plain_bytes = plain_compressor.finish();
+        assert!(
+            bytes.len() < plain_bytes.len(),
+            "{} bytes of transitions, {} bytes of deltas",
+            bytes.len(),
+            plain_bytes.len()
+        );
+
+        // Initialize the decompressor
+        let mut decompressor = FlagsRowDecompressorImpl::new();
+
+        // Decompress the bit buffer
+        decompressor.decompress(&bytes).unwrap();
+
+        // Assert that the decompressed data matches the original
+        assert_eq!(decompressor.rows(), rows);
+    }
+
+    #[test]
+    fn test_macro_compress_transitions_invalid_bool() {
+        // A frame with a value other than 0 or 1 in the bool column
+        let mut compressor = RawRowCompressorImpl::new(16);
+        for charging in [0, 1, 1, 0, 2, 0] {
+            compressor.compress(RawRow {
+                status: 0,
+                charging,
+            });
+        }
+        let bytes = compressor.finish();
+
+        // Initialize the decompressor
+        let mut decompressor = FlagsRowDecompressorImpl::new();
+
+        // Decompress the bit buffer
+        assert!(matches!(
+            decompressor.decompress(&bytes),
+            Err(CodingError::InvalidBits)
+        ));
+        let items = FlagsRowDecompressorImpl::iter_rows(&bytes).collect::<Vec<_>>();
+        assert!(matches!(items.last(), Some(Err(CodingError::InvalidBits))));
+    }
+}
+
+mod test_enums {
+
+    use super::*;
+    use rand::Rng;
+
+    mod row {
+        use tsz_compress::prelude::*;
+
+        #[derive(Debug, Copy, Clone, PartialEq, ColumnEnum)]
+        #[repr(u8)]
+        pub enum Mode {
+            Idle,
+            Walking,
+            Running = 4,
+            Cycling,
+        }
+
+        #[derive(Debug, Copy, Clone, PartialEq, ColumnEnum)]
+        #[repr(i16)]
+        pub enum Grade {
+            Down = -300,
+            Level = 0,
+            Up = 300,
+        }
+
+        #[derive(Debug, Copy, Clone, PartialEq, CompressV2, DecompressV2)]
+        #[tsz(header)]
+        pub struct TestRow {
+            pub ts: i64,
+            #[tsz(repr = "u8")]
+            pub mode: Mode,
+            #[tsz(repr = "i16")]
+            pub grade: Option<Grade>,
+        }
+
+        pub use compress::TestRowCompressorImpl;
+        pub use decompress::TestRowDecompressorImpl;
+    }
+    use row::*;
+
+    mod mode {
+        use super::row::Mode;
+        use tsz_compress::prelude::*;
+        #[derive(Debug, Copy, Clone, PartialEq, CompressV2, DecompressV2)]
+        pub struct ModeRow {
+            pub ts: i64,
+            #[tsz(repr = "u8")]
+            pub mode: Mode,
+        }
+
+        pub use compress::ModeRowCompressorImpl;
+        pub use decompress::ModeRowDecompressorImpl;
+    }
+    use mode::*;
+
+    mod raw {
+        use tsz_compress::prelude::*;
+        #[derive(Debug, Copy, Clone, PartialEq, CompressV2, DecompressV2)]
+        pub struct RawRow {
+            pub ts: i64,
+            pub mode: u8,
+        }
+
+        pub use compress::RawRowCompressorImpl;
+    }
+    use raw::*;
+
+    const MODES: [Mode; 4] = [Mode::Idle, Mode::Walking, Mode::Running, Mode::Cycling];
+    const GRADES: [Grade; 3] = [Grade::Down, Grade::Level, Grade::Up];
+
+    #[test]
+    fn test_macro_compress_enums_round_trip() {
+        let mut rng = rand::thread_rng();
+
+        for _ in 0..100 {
+            // Activity classes that change rarely
+            let mut ts = rng.gen::<i64>();
+            let mut mode = MODES[rng.gen_range(0..MODES.len())];
+            let mut grade = GRADES[rng.gen_range(0..GRADES.len())];
+            let rows = (0..rng.gen_range(1..5000))
+                .map(|_| {
+                    ts = ts.wrapping_add(1000);
+                    if rng.gen_bool(0.05) {
+                        mode = MODES[rng.gen_range(0..MODES.len())];
+                    }
+                    if rng.gen_bool(0.05) {
+                        grade = GRADES[rng.gen_range(0..GRADES.len())];
+                    }
+                    TestRow {
+                        ts,
+                        mode,
+                        grade: rng.gen_bool(0.9).then_some(grade),
+                    }
+                })
+                .collect::<Vec<_>>();
+
+            // Initialize the compressor
+            let mut compressor = TestRowCompressorImpl::new(rows.len());
+
+            // Compression
+            for row in &rows {
+                compressor.compress(*row);
+            }
+
+            // Finalize the compression
+            let bytes = compressor.finish();
+
+            // Initialize the decompressor
+            let mut decompressor = TestRowDecompressorImpl::new();
+
+            // Decompress the bit buffer
+            decompressor.decompress(&bytes).unwrap();
+
+            // Assert that the decompressed data matches the original
+            assert_eq!(decompressor.rows(), rows);
+            let iter_rows = TestRowDecompressorImpl::iter_rows(&bytes);
+            assert_eq!(iter_rows.collect::<Result<Vec<_>, _>>().unwrap(), rows);
+        }
+
+        // The frame header stores the discriminant type of the enum columns
+        assert_eq!(
+            TestRowCompressorImpl::COLUMN_TYPES[1..],
+            [
+                consts::frame::U8,
+                consts::frame::I16 | consts::frame::NULLABLE
+            ]
+        );
+    }
+
+    #[test]
+    fn test_macro_compress_enums_invalid_discriminant() {
+        // A frame with a discriminant of no variant in the enum column
+        let mut compressor = RawRowCompressorImpl::new(16);
+        for (ts, mode) in [0, 1, 4, 5, 2, 0].into_iter().enumerate() {
+            compressor.compress(RawRow {
+                ts: ts as i64,
+                mode,
+            });
+        }
+        let bytes = compressor.finish();
+
+        // Initialize the decompressor
+        let mut decompressor = ModeRowDecompressorImpl::new();
+
+        // Decompress the bit buffer
+        assert!(matches!(
+            decompressor.decompress(&bytes),
+            Err(CodingError::InvalidDiscriminant)
+        ));
+        let items = ModeRowDecompressorImpl::iter_rows(&bytes).collect::<Vec<_>>();
+        assert_eq!(items.len(), 5);
+        assert!(matches!(
+            items.last(),
+            Some(Err(CodingError::InvalidDiscriminant))
+        ));
+
+        // The valid discriminants decode
+        let mut compressor = ModeRowCompressorImpl::new(16);
+        for (ts, mode) in MODES.into_iter().enumerate() {
+            compressor.compress(ModeRow {
+                ts: ts as i64,
+                mode,
+            });
+        }
+        let bytes = compressor.finish();
+        let rows = ModeRowDecompressorImpl::iter_rows(&bytes)
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        assert_eq!(rows.iter().map(|row| row.mode).collect::<Vec<_>>(), MODES);
+        assert_eq!(CodingError::InvalidDiscriminant.code(), -10);
+    }
+}
+
+mod test_timestamp_grid {
+
+    use super::*;
+    use rand::Rng;
+
+    mod row {
+        use tsz_compress::prelude::*;
+        #[derive(Debug, Copy, Clone, PartialEq, CompressV2, DecompressV2)]
+        #[tsz(header)]
+        pub struct TestRow {
+            #[tsz(timestamp(period = 1000))]
+            pub ts: i64,
+            #[tsz(timestamp(period = 32768))]
+            pub ticks: u32,
+            #[tsz(timestamp(period = 100))]
+            pub counter: u8,
+            pub value: i16,
+        }
+
+        pub use compress::TestRowCompressorImpl;
+        pub use decompress::TestRowDecompressorImpl;
+    }
+    use row::*;
+
+    mod grid {
+        use tsz_compress::prelude::*;
+        #[derive(Debug, Copy, Clone, PartialEq, CompressV2, DecompressV2)]
+        pub struct GridRow {
+            #[tsz(timestamp(period = 1000))]
+            pub ts: i64,
+        }
+
+        pub use compress::GridRowCompressorImpl;
+        pub use decompress::GridRowDecompressorImpl;
+    }
+    use grid::*;
+
+    mod plain {
+        use tsz_compress::prelude::*;
+        #[derive(Debug, Copy, Clone, PartialEq, CompressV2, DecompressV2)]
+        pub struct PlainRow {
+            pub ts: i64,
+        }
+
+        pub use compress::PlainRowCompressorImpl;
+    }
+    use plain::*;
+
+    #[test]
+    fn test_macro_compress_timestamp_grid_round_trip() {
+        let mut rng = rand::thread_rng();
+
+        for i in 0..100 {
+            // Timestamps on a grid with rare jitter, except for every other frame
+            let jitter = i % 2 == 0;
+            let ts0 = rng.gen::<i64>();
+            let ticks0 = rng.gen::<u32>();
+            let counter0 = rng.gen::<u8>();
+            let rows = (0..rng.gen_range(1..5000))
+                .map(|k: i64| {
+                    let residual = if jitter && rng.gen_bool(0.01) {
+                        rng.gen_range(-3..=3)
+                    } else {
+                        0
+                    };
+                    TestRow {
+                        ts: ts0.wrapping_add(k * 1000).wrapping_add(residual),
+                        ticks: ticks0
+                            .wrapping_add((k as u32).wrapping_mul(32768))
+                            .wrapping_add(residual as u32),
+                        counter: counter0.wrapping_add((k as u8).wrapping_mul(100)),
+                        value: rng.gen(),
+                    }
+                })
+                .collect::<Vec<_>>();
+
+            // Initialize the compressor
+            let mut compressor = TestRowCompressorImpl::new(rows.len());
+
+            // Compression
+            for row in &rows {
+                compressor.compress(*row);
+            }
+
+            // Finalize the compression
+            let finished_len = compressor.finished_len();
+            let bytes = compressor.finish();
+            assert_eq!(bytes.len(), finished_len);
+
+            // Initialize the decompressor
+            let mut decompressor = TestRowDecompressorImpl::new();
+
+            // Decompress the bit buffer
+            decompressor.decompress(&bytes).unwrap();
+
+            // Assert that the decompressed data matches the original
+            assert_eq!(decompressor.rows(), rows);
+            let iter_rows = TestRowDecompressorImpl::iter_rows(&bytes);
+            assert_eq!(iter_rows.collect::<Result<Vec<_>, _>>().unwrap(), rows);
+        }
+    }
+
+    #[test]
+    fn test_macro_compress_timestamp_grid_exact() {
+        // Initialize the compressor
+        let mut compressor = GridRowCompressorImpl::new(10_000);
+        let mut plain_compressor = PlainRowCompressorImpl::new(10_000);
+
+        // Timestamps exactly on the grid take the same bytes for any number of rows
+        let mut lens = Vec::new();
+        for rows in [1, 2, 10, 10_000] {
+            // Compression
+            let rows = (0..rows)
+                .map(|k| GridRow {
+                    ts: 1_675_465_460_000 + k * 1000,
+                })
+                .collect::<Vec<_>>();
+            for row in &rows {
+                compressor.compress(*row);
+                plain_compressor.compress(PlainRow { ts: row.ts });
+            }
+
+            // Finalize the compression
+            let finished_len = compressor.finished_len();
+            let bytes = compressor.finish();
+            let plain_bytes = plain_compressor.finish();
+            assert_eq!(bytes.len(), finished_len);
+            if rows.len() > 10 {
+                assert!(bytes.len() < plain_bytes.len());
+            }
+            lens.push(bytes.len());
+
+            // Initialize the decompressor
+            let mut decompressor = GridRowDecompressorImpl::new();
+
+            // Decompress the bit buffer
+            decompressor.decompress(&bytes).unwrap();
+
+            // Assert that the decompressed data matches the original
+            assert_eq!(decompressor.rows(), rows);
+            let iter_rows = GridRowDecompressorImpl::iter_rows(&bytes);
+            assert_eq!(iter_rows.collect::<Result<Vec<_>, _>>().unwrap(), rows);
+        }
+        assert!(lens.iter().all(|len| *len == lens[0]), "{:?}", lens);
+
+        // An empty frame has an empty timestamp column
+        let bytes = compressor.finish();
+        let mut decompressor = GridRowDecompressorImpl::new();
+        decompressor.decompress(&bytes).unwrap();
+        assert!(decompressor.rows().is_empty());
+    }
+}
+
+mod test_dictionary {
+
+    use super::*;
+    use rand::Rng;
+
+    mod row {
+        use tsz_compress::prelude::*;
+        #[derive(Debug, Copy, Clone, PartialEq, CompressV2, DecompressV2)]
+        #[tsz(header)]
+        pub struct TestRow {
+            pub ts: i64,
+            #[tsz(dictionary)]
+            pub range: u8,
+            #[tsz(dictionary)]
+            pub gain: i16,
+            #[tsz(dictionary)]
+            pub rate: u32,
+            #[tsz(dictionary)]
+            pub code: i64,
+        }
+
+        pub use compress::TestRowCompressorImpl;
+        pub use decompress::TestRowDecompressorImpl;
+    }
+    use row::*;
+
+    mod gain {
+        use tsz_compress::prelude::*;
+        #[derive(Debug, Copy, Clone, PartialEq, CompressV2, DecompressV2)]
+        pub struct GainRow {
+            #[tsz(dictionary)]
+            pub gain: i32,
+        }
+
+        pub use compress::GainRowCompressorImpl;
+        pub use decompress::GainRowDecompressorImpl;
+    }
+    use gain::*;
+
+    mod plain {
+        use tsz_compress::prelude::*;
+        #[derive(Debug, Copy, Clone, PartialEq, CompressV2, DecompressV2)]
+        pub struct PlainRow {
+            pub gain: i32,
+        }
+
+        pub use compress::PlainRowCompressorImpl;
+    }
+    use plain::*;
+
+    #[test]
+    fn test_macro_compress_dictionary_round_trip() {
+        let mut rng = rand::thread_rng();
+
+        for _ in 0..100 {
+            // A handful of distinct values that jump between each other,
+            // with more distinct values than fit in a dictionary in some frames
+            let ranges = (0..rng.gen_range(1..=4))
+                .map(|_| rng.gen())
+                .collect::<Vec<u8>>();
+            let gains = (0..rng.gen_range(1..=16))
+                .map(|_| rng.gen())
+                .collect::<Vec<i16>>();
+            let rates = (0..rng.gen_range(1..=20))
+                .map(|_| rng.gen())
+                .collect::<Vec<u32>>();
+            let codes = (0..rng.gen_range(1..=9))
+                .map(|_| rng.gen())
+                .collect::<Vec<i64>>();
+            let mut ts = rng.gen::<i64>();
+            let rows = (0..rng.gen_range(1..5000))
+                .map(|_| {
+                    ts = ts.wrapping_add(1000);
+                    TestRow {
+                        ts,
+                        range: ranges[rng.gen_range(0..ranges.len())],
+                        gain: gains[rng.gen_range(0..gains.len())],
+                        rate: rates[rng.gen_range(0..rates.len())],
+                        code: codes[rng.gen_range(0..codes.len())],
+                    }
+                })
+                .collect::<Vec<_>>();
+
+            // Initialize the compressor
+            let mut compressor = TestRowCompressorImpl::new(rows.len());
+
+            // Compression
+            for row in &rows {
+                compressor.compress(*row);
+            }
+
+            // Finalize the compression
+            let finished_len = compressor.finished_len();
+            let bytes = compressor.finish();
+            assert_eq!(bytes.len(), finished_len);
+
+            // Initialize the decompressor
+            let mut decompressor = TestRowDecompressorImpl::new();
+
+            // Decompress the bit buffer
+            decompressor.decompress(&bytes).unwrap();
+
+            // Assert that the decompressed data matches the original
+            assert_eq!(decompressor.rows(), rows);
+            let iter_rows = TestRowDecompressorImpl::iter_rows(&bytes);
+            assert_eq!(iter_rows.collect::<Result<Vec<_>, _>>().unwrap(), rows);
+        }
+    }
+
+    #[test]
+    fn test_macro_compress_dictionary_smaller_than_deltas() {
+        let mut rng = rand::thread_rng();
+
+        // Gain settings that jump between distant values
+        let gains = [1, 1 << 20, -(1 << 24), 7];
+        let rows = (0..10_000)
+            .map(|_| GainRow {
+                gain: gains[rng.gen_range(0..gains.len())],
+            })
+            .collect::<Vec<_>>();
+
+        // Initialize the compressor
+        let mut compressor = GainRowCompressorImpl::new(rows.len());
+        let mut plain_compressor = PlainRowCompressorImpl::new(rows.len());
+
+        // Compression
+        for row in &rows {
+            compressor.compress(*row);
+            plain_compressor.compress(PlainRow { gain: row.gain });
+        }
+
+        // Finalize the compression
+        let bytes = compressor.finish();
+        let plain_bytes = plain_compressor.finish();
+
+        // 2 bits per row, rather than a 32-bit delta
+        assert!(bytes.len() < 10_000 * 2 / 8 + 32, "{} bytes", bytes.len());
+        assert!(bytes.len() * 8 < plain_bytes.len());
+
+        // Initialize the decompressor
+        let mut decompressor = GainRowDecompressorImpl::new();
+
+        // Decompress the bit buffer
+        decompressor.decompress(&bytes).unwrap();
+
+        // Assert that the decompressed data matches the original
+        assert_eq!(decompressor.rows(), rows);
+    }
+
+    #[test]
+    fn test_macro_compress_dictionary_fallback() {
+        // More distinct values than fit in a dictionary fall back to deltas
+        let rows = (0..1000)
+            .map(|i| GainRow { gain: i * 3 })
+            .collect::<Vec<_>>();
+
+        // Initialize the compressor
+        let mut compressor = GainRowCompressorImpl::new(rows.len());
+        let mut plain_compressor = PlainRowCompressorImpl::new(rows.len());
+
+        // Compression
+        for row in &rows {
+            compressor.compress(*row);
+            plain_compressor.compress(PlainRow { gain: row.gain });
+        }
+
+        // Finalize the compression
+        let bytes = compressor.finish();
+        let plain_bytes = plain_compressor.finish();
+        assert_eq!(bytes, plain_bytes);
+
+        // Initialize the decompressor
+        let mut decompressor = GainRowDecompressorImpl::new();
+
+        // Decompress the bit buffer
+        decompressor.decompress(&bytes).unwrap();
+
+        // Assert that the decompressed data matches the original
+        assert_eq!(decompressor.rows(), rows);
+        let iter_rows = GainRowDecompressorImpl::iter_rows(&bytes);
+        assert_eq!(iter_rows.collect::<Result<Vec<_>, _>>().unwrap(), rows);
+    }
+}
+
+mod test_frame_of_reference {
+
+    use super::*;
+    use rand::Rng;
+
+    mod row {
+        use tsz_compress::prelude::*;
+        #[derive(Debug, Copy, Clone, PartialEq, CompressV2, DecompressV2)]
+        #[tsz(header)]
+        pub struct TestRow {
+            #[tsz(timestamp(period = 1000))]
+            pub ts: i64,
+            pub a: i8,
+            pub b: u16,
+            pub c: i32,
+            pub d: u64,
+            pub e: Option<i16>,
+            pub f: bool,
+        }
+
+        pub use compress::TestRowCompressorImpl;
+        pub use decompress::TestRowDecompressorImpl;
+    }
+    use row::*;
+
+    mod oscillating {
+        use tsz_compress::prelude::*;
+        #[derive(Debug, Copy, Clone, PartialEq, CompressV2, DecompressV2)]
+        pub struct OscillatingRow {
+            pub a: i16,
+        }
+
+        pub use compress::OscillatingRowCompressorImpl;
+        pub use decompress::OscillatingRowDecompressorImpl;
+    }
+    use oscillating::*;
+
+    #[test]
+    fn test_macro_compress_frame_of_reference_round_trip() {
+        let mut rng = rand::thread_rng();
+
+        for _ in 0..100 {
+            // Noisy columns of random spread, some of which are shorter packed than as deltas
+            let spread = rng.gen_range(0..64);
+            let noise = |rng: &mut rand::rngs::ThreadRng| rng.gen_range(0..=u64::MAX >> spread);
+            let mut ts = rng.gen::<i64>();
+            let rows = (0..rng.gen_range(1..2000))
+                .map(|i| {
+                    ts = ts.wrapping_add(1000 + rng.gen_range(-3..=3));
+                    let sign = if i % 2 == 0 { 1 } else { -1 };
+                    TestRow {
+                        ts,
+                        a: (sign * (noise(&mut rng) as i64 % 100)) as i8,
+                        b: noise(&mut rng) as u16,
+                        c: (sign * (noise(&mut rng) >> 33) as i64) as i32,
+                        d: noise(&mut rng),
+                        e: rng.gen_bool(0.5).then(|| noise(&mut rng) as i16),
+                        f: rng.gen(),
+                    }
+                })
+                .collect::<Vec<_>>();
+
+            // Initialize the compressor
+            let mut compressor = TestRowCompressorImpl::new(rows.len());
+
+            // Compression
+            for row in &rows {
+                compressor.compress(*row);
+            }
+
+            // Finalize the compression
+            let finished_len = compressor.finished_len();
+            let bytes = compressor.finish();
+            assert_eq!(bytes.len(), finished_len);
+
+            // Initialize the decompressor
+            let mut decompressor = TestRowDecompressorImpl::new();
+
+            // Decompress the bit buffer
+            decompressor.decompress(&bytes).unwrap();
+
+            // Assert that the decompressed data matches the original
+            assert_eq!(decompressor.rows(), rows);
+            let iter_rows = TestRowDecompressorImpl::iter_rows(&bytes);
+            assert_eq!(iter_rows.collect::<Result<Vec<_>, _>>().unwrap(), rows);
+        }
+    }
+
+    #[test]
+    fn test_macro_compress_frame_of_reference_oscillating() {
+        let mut rng = rand::thread_rng();
+
+        // A column swinging between two levels, with noise
+        let rows = (0..10_000)
+            .map(|i| OscillatingRow {
+                a: if i % 2 == 0 { 1000 } else { -1000 } + rng.gen_range(-64..64),
+            })
+            .collect::<Vec<_>>();
+
+        // Initialize the compressor
+        let mut compressor = OscillatingRowCompressorImpl::new(rows.len());
+
+        // Compression
+        for row in &rows {
+            compressor.compress(*row);
+        }
+
+        // Finalize the compression
+        let finished_len = compressor.finished_len();
+        let bytes = compressor.finish();
+        assert_eq!(bytes.len(), finished_len);
+
+        // The values span 12 bits, where the zigzag deltas take 16-bit words
+        assert!(bytes.len() <= 10_000 * 12 / 8 + 16, "{} bytes", bytes.len());
+
+        // Initialize the decompressor
+        let mut decompressor = OscillatingRowDecompressorImpl::new();
+
+        // Decompress the bit buffer
+        decompressor.decompress(&bytes).unwrap();
+
+        // Assert that the decompressed data matches the original
+        assert_eq!(decompressor.rows(), rows);
+        let iter_rows = OscillatingRowDecompressorImpl::iter_rows(&bytes);
+        assert_eq!(iter_rows.collect::<Result<Vec<_>, _>>().unwrap(), rows);
+    }
+}
+
+mod test_seek {
+
+    use super::*;
+    use rand::Rng;
+
+    mod row {
+        use tsz_compress::prelude::*;
+        #[derive(Debug, Copy, Clone, PartialEq, CompressV2, DecompressV2)]
+        #[tsz(header, checksum = "crc16", seek = 100)]
+        pub struct TestRow {
+            #[tsz(timestamp(period = 1000))]
+            pub ts: i64,
+            #[tsz(timestamp(period = 10))]
+            pub tick: u32,
+            pub a: i16,
+            pub b: Option<u8>,
+            pub c: f64,
+            #[tsz(dictionary)]
+            pub d: u8,
+            pub e: i32,
+            pub f: u16,
+            pub g: i128,
+            pub h: Option<i32>,
+            pub i: bool,
+            #[tsz(delta = "i8")]
+            pub j: i32,
+            #[tsz(flags)]
+            pub k: u16,
+        }
+
+        pub use compress::TestRowCompressorImpl;
+        pub use decompress::TestRowDecompressorImpl;
+    }
+    use row::*;
+
+    mod every {
+        use tsz_compress::prelude::*;
+        #[derive(Debug, Copy, Clone, PartialEq, CompressV2, DecompressV2)]
+        #[tsz(seek = 1)]
+        pub struct EveryRow {
+            pub a: i64,
+            pub b: Option<f32>,
+        }
+
+        pub use compress::EveryRowCompressorImpl;
+        pub use decompress::EveryRowDecompressorImpl;
+    }
+    use every::*;
+
+    fn random_rows(rng: &mut rand::rngs::ThreadRng, len: usize) -> Vec<TestRow> {
+        // Columns of every encoding, with runs of repeated values between the checkpoints
+        let distinct = if rng.gen_bool(0.5) { 4 } else { 64 };
+        let (mut ts, mut a, mut c, mut f) = (rng.gen::<i64>(), 0i16, 20.0, rng.gen::<u16>());
+        let (mut j, mut flags) = (rng.gen::<i32>(), 0u16);
+        let mut run = 0;
+        (0..len)
+            .map(|k| {
+                if run == 0 {
+                    run = rng.gen_range(1..300);
+                    f = if rng.gen_bool(0.3) { rng.gen() } else { f };
+                }
+                run -= 1;
+                ts = ts.wrapping_add(
+                    1000 + if rng.gen_bool(0.05) {
+                        rng.gen_range(-3..=3)
+                    } else {
+                        0
+                    },
+                );
+                a = a.wrapping_add(rng.gen_range(-20..=20));
+                c += rng.gen_range(-0.5..0.5);
+                j = j.wrapping_add(rng.gen_range(-100..=100));
+                flags ^= 1 << rng.gen_range(0..16);
+                TestRow {
+                    ts,
+                    tick: (k as u32).wrapping_mul(10),
+                    a,
+                    b: if run % 7 < 3 { None } else { Some(rng.gen()) },
+                    c,
+                    d: rng.gen_range(0..distinct),
+                    e: rng.gen(),
+                    f,
+                    g: k as i128 * 1_000_000_000_000_000_000,
+                    h: None,
+                    i: run % 2 == 0,
+                    j,
+                    k: flags,
+                }
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_macro_compress_seek_round_trip() {
+        let mut rng = rand::thread_rng();
+
+        for _ in 0..50 {
+            let len = rng.gen_range(0..3000);
+            let rows = random_rows(&mut rng, len);
+
+            // Initialize the compressor
+            let mut compressor = TestRowCompressorImpl::new(rows.len());
+
+            // Compression
+            for row in &rows {
+                compressor.compress(*row);
+            }
+
+            // Finalize the compression
+            let finished_len = compressor.finished_len();
+            let bytes = compressor.finish();
+            assert_eq!(bytes.len(), finished_len);
+
+            // Initialize the decompressor
+            let mut decompressor = TestRowDecompressorImpl::new();
+
+            // Decompress the bit buffer
+            decompressor.decompress(&bytes).unwrap();
+
+            // Assert that the decompressed data matches the original
+            assert_eq!(decompressor.rows(), rows);
+            let iter_rows = TestRowDecompressorImpl::iter_rows(&bytes);
+            assert_eq!(iter_rows.collect::<Result<Vec<_>, _>>().unwrap(), rows);
+
+            // Ranges from the checkpoints, within the intervals and across them
+            let mut ranges = vec![0..rows.len(), rows.len()..rows.len()];
+            for _ in 0..20 {
+                let start = rng.gen_range(0..=rows.len());
+                let end = rng.gen_range(start..=rows.len());
+                ranges.push(start..end);
+                ranges.push(start / 100 * 100..end);
+            }
+            for range in ranges {
+                let iter_rows = TestRowDecompressorImpl::iter_rows_range(&bytes, range.clone());
+                assert_eq!(iter_rows.size_hint(), (range.len(), Some(range.len())));
+                assert_eq!(
+                    iter_rows.collect::<Result<Vec<_>, _>>().unwrap(),
+                    rows[range.clone()],
+                    "{:?}",
+                    range
+                );
+            }
+
+            // Rows past the end of the frame are invalid
+            let items = TestRowDecompressorImpl::iter_rows_range(&bytes, 0..rows.len() + 1)
+                .collect::<Vec<_>>();
+            assert!(
+                matches!(items[..], [Err(CodingError::InvalidRowCount(end))] if end == rows.len() + 1)
+            );
+        }
+    }
+
+    #[test]
+    fn test_macro_compress_seek_every_row() {
+        let mut rng = rand::thread_rng();
+        let rows = (0..500)
+            .map(|i| EveryRow {
+                a: if i % 50 < 20 {
+                    7
+                } else {
+                    rng.gen_range(-1000..1000)
+                },
+                b: if i % 3 == 0 { None } else { Some(rng.gen()) },
+            })
+            .collect::<Vec<_>>();
+
+        // Initialize the compressor
+        let mut compressor = EveryRowCompressorImpl::new(rows.len());
+
+        // Compression
+        for row in &rows {
+            compressor.compress(*row);
+        }
+
+        // Finalize the compression
+        let bytes = compressor.finish();
+
+        // Initialize the decompressor
+        let mut decompressor = EveryRowDecompressorImpl::new();
+
+        // Decompress the bit buffer
+        decompressor.decompress(&bytes).unwrap();
+
+        // Assert that the decompressed data matches the original
+        assert_eq!(decompressor.rows(), rows);
+
+        // Every row starts at its own checkpoint
+        for (start, row) in rows.iter().enumerate() {
+            let range = EveryRowDecompressorImpl::iter_rows_range(&bytes, start..start + 1)
+                .collect::<Result<Vec<_>, _>>()
+                .unwrap();
+            assert_eq!(range, [*row]);
+        }
+    }
+
+    #[test]
+    fn test_macro_compress_seek_invalid_index() {
+        let mut rng = rand::thread_rng();
+        let rows = (0..1000)
+            .map(|i| EveryRow {
+                a: i,
+                b: Some(rng.gen()),
+            })
+            .collect::<Vec<_>>();
+
+        // Initialize the compressor
+        let mut compressor = EveryRowCompressorImpl::new(rows.len());
+
+        // Compression
+        for row in &rows {
+            compressor.compress(*row);
+        }
+
+        // Finalize the compression
+        let bytes = compressor.finish();
+
+        // Initialize the decompressor
+        let mut decompressor = EveryRowDecompressorImpl::new();
+
+        // A truncated index leaves the trailer of no index
+        let truncated = &bytes[..bytes.len() - 1];
+        assert!(matches!(
+            decompressor.decompress(truncated),
+            Err(CodingError::InvalidBits)
+        ));
+        let items = EveryRowDecompressorImpl::iter_rows_range(truncated, 0..10).collect::<Vec<_>>();
+        assert!(matches!(items[..], [Err(CodingError::InvalidBits)]));
+
+        // An index without checkpoints still trails the columns, but cannot seek
+        let index_len = u32::from_be_bytes(bytes[bytes.len() - 4..].try_into().unwrap());
+        let mut unindexed = bytes[..bytes.len() - index_len as usize].to_vec();
+        unindexed.extend(SeekIndexWriter::new(1, CheckpointWidths::new(0, 0)).finish());
+        decompressor.decompress(&unindexed).unwrap();
+        assert_eq!(decompressor.rows(), rows);
+        let items =
+            EveryRowDecompressorImpl::iter_rows_range(&unindexed, 5..10).collect::<Vec<_>>();
+        assert!(matches!(items[..], [Err(CodingError::InvalidRowCount(5))]));
+    }
+}
+
+mod test_time_range {
+
+    use super::*;
+    use rand::Rng;
+
+    mod row {
+        use tsz_compress::prelude::*;
+
+        #[derive(Debug, Copy, Clone, PartialEq, ColumnEnum)]
+        #[repr(u8)]
+        pub enum Mode {
+            Idle,
+            Walking,
+            Running,
+        }
+
+        #[derive(Debug, Copy, Clone, PartialEq, CompressV2, DecompressV2)]
+        #[tsz(header)]
+        pub struct TestRow {
+            #[tsz(timestamp)]
+            pub ts: i64,
+            pub a: i16,
+            pub b: Option<u8>,
+            #[tsz(scale = 100, as = "i32")]
+            pub temp: f32,
+            #[tsz(repr = "u8")]
+            pub mode: Mode,
+            #[tsz(dictionary)]
+            pub gain: u8,
+        }
+
+        pub use compress::TestRowCompressorImpl;
+        pub use decompress::TestRowDecompressorImpl;
+    }
+    use row::*;
+
+    mod grid {
+        use tsz_compress::prelude::*;
+        #[derive(Debug, Copy, Clone, PartialEq, CompressV2, DecompressV2)]
+        pub struct GridRow {
+            pub value: i32,
+            #[tsz(timestamp(period = 1000))]
+            pub ts: u64,
+        }
+
+        pub use compress::GridRowCompressorImpl;
+        pub use decompress::GridRowDecompressorImpl;
+    }
+    use grid::*;
+
+    /// The rows from the first at or after `t0` to the next at or after `t1`.
+    fn span<T: Copy>(rows: &[T], ts: impl Fn(&T) -> i64, t0: i64, t1: i64) -> Vec<T> {
+        let start = rows
+            .iter()
+            .position(|row| ts(row) >= t0)
+            .unwrap_or(rows.len());
+        let end = rows[start..]
+            .iter()
+            .position(|row| ts(row) >= t1)
+            .map_or(rows.len(), |end| start + end);
+        rows[start..end].to_vec()
+    }
+
+    #[test]
+    fn test_macro_compress_time_range_round_trip() {
+        let mut rng = rand::thread_rng();
+
+        for _ in 0..50 {
+            // Timestamps that may repeat or jump
+            let mut ts = rng.gen_range(-1_000_000..1_000_000);
+            let mut a = 0i16;
+            let rows = (0..rng.gen_range(0..3000))
+                .map(|i| {
+                    ts += if rng.gen_bool(0.01) {
+                        rng.gen_range(0..100_000)
+                    } else {
+                        rng.gen_range(0..2000)
+                    };
+                    a = a.wrapping_add(rng.gen_range(-5..=5));
+                    TestRow {
+                        ts,
+                        a,
+                        b: if i % 10 < 4 { None } else { Some(rng.gen()) },
+                        temp: rng.gen_range(-40.0..40.0),
+                        mode: [Mode::Idle, Mode::Walking, Mode::Running][i / 100 % 3],
+                        gain: [1, 2, 4, 8][rng.gen_range(0..4)],
+                    }
+                })
+                .collect::<Vec<_>>();
+
+            // Initialize the compressor
+            let mut compressor = TestRowCompressorImpl::new(rows.len());
+
+            // Compression
+            for row in &rows {
+                compressor.compress(*row);
+            }
+
+            // Finalize the compression
+            let bytes = compressor.finish();
+
+            // Initialize the decompressor
+            let mut decompressor = TestRowDecompressorImpl::new();
+
+            // Decompress the bit buffer
+            decompressor.decompress(&bytes).unwrap();
+            let decompressed = decompressor.rows();
+            assert_eq!(decompressed.len(), rows.len());
+
+            // Assert that the decompressed rows of each range match the span of the rows
+            let (first, last) = (ts - 10_000_000, ts + 1000);
+            for _ in 0..20 {
+                let t0 = rng.gen_range(first..last);
+                let t1 = if rng.gen_bool(0.1) {
+                    t0 - rng.gen_range(0..1000)
+                } else {
+                    t0 + rng.gen_range(0..1_000_000)
+                };
+                let mut decompressor = TestRowDecompressorImpl::new();
+                decompressor.decompress_range(&bytes, t0..t1).unwrap();
+                let expected = span(&decompressed, |row| row.ts, t0, t1);
+                assert_eq!(decompressor.rows(), expected);
+                assert_eq!(decompressor.col_temp_raw().len(), expected.len());
+
+                // Ranges extend the columns like frames
+                decompressor.decompress_range(&bytes, t0..t1).unwrap();
+                assert_eq!(decompressor.rows().len(), 2 * expected.len());
+            }
+
+            // A range covering every timestamp holds every row
+            let mut decompressor = TestRowDecompressorImpl::new();
+            decompressor
+                .decompress_range(&bytes, i64::MIN..i64::MAX)
+                .unwrap();
+            assert_eq!(decompressor.rows(), decompressed);
+        }
+    }
+
+    #[test]
+    fn test_macro_compress_time_range_grid() {
+        let rows = (0..10_000)
+            .map(|i| GridRow {
+                value: (i % 17) as i32,
+                ts: 1_700_000_000_000 + i * 1000,
+            })
+            .collect::<Vec<_>>();
+
+        // Initialize the compressor
+        let mut compressor = GridRowCompressorImpl::new(rows.len());
+
+        // Compression
+        for row in &rows {
+            compressor.compress(*row);
+        }
+
+        // Finalize the compression
+        let bytes = compressor.finish();
+
+        // Initialize the decompressor
+        let mut decompressor = GridRowDecompressorImpl::new();
+
+        // Decompress the bit buffer
+        decompressor
+            .decompress_range(&bytes, 1_700_000_500_000..1_700_000_600_500)
+            .unwrap();
+
+        // Assert that the decompressed data matches the original
+        assert_eq!(decompressor.rows(), rows[500..601]);
+
+        // A range between two timestamps is empty
+        decompressor.clear();
+        decompressor
+            .decompress_range(&bytes, 1_700_000_000_001..1_700_000_000_999)
+            .unwrap();
+        assert!(decompressor.rows().is_empty());
+
+        // An invalid frame is an error
+        assert!(decompressor
+            .decompress_range(&bytes[..bytes.len() / 2], 0..u64::MAX)
+            .is_err());
+    }
+}
+
+mod test_column_table {
+
+    use super::*;
+    use rand::seq::SliceRandom;
+    use rand::Rng;
+
+    mod row {
+        use tsz_compress::prelude::*;
+
+        #[derive(Debug, Copy, Clone, PartialEq, ColumnEnum)]
+        #[repr(u8)]
+        pub enum Mode {
+            Idle,
+            Walking,
+            Running,
+        }
+
+        #[derive(Debug, Copy, Clone, PartialEq, CompressV2, DecompressV2)]
+        #[tsz(header, column_table)]
+        pub struct TestRow {
+            #[tsz(timestamp)]
+            pub ts: i64,
+            pub a: i16,
+            pub b: Option<u8>,
+            #[tsz(scale = 100, as = "i32")]
+            pub temp: f32,
+            #[tsz(repr = "u8")]
+            pub mode: Mode,
+            #[tsz(dictionary)]
+            pub gain: u8,
+            pub missing: Option<i32>,
+            pub noise_level: u64,
+        }
+
+        pub use compress::TestRowCompressorImpl;
+        pub use decompress::{TestRowColumn, TestRowDecompressorImpl};
+    }
+    use row::*;
+
+    mod grid {
+        use tsz_compress::prelude::*;
+        #[derive(Debug, Copy, Clone, PartialEq, CompressV2, DecompressV2)]
+        #[tsz(checksum = "crc16", seek = 100, column_table)]
+        pub struct GridRow {
+            #[tsz(timestamp(period = 1000))]
+            pub ts: u64,
+            pub value: i32,
+            pub flag: Option<bool>,
+        }
+
+        pub use compress::GridRowCompressorImpl;
+        pub use decompress::{GridRowColumn, GridRowDecompressorImpl};
+    }
+    use grid::*;
+
+    mod pair {
+        use tsz_compress::prelude::*;
+        #[derive(Debug, Copy, Clone, PartialEq, CompressV2, DecompressV2)]
+        #[tsz(column_table)]
+        pub struct PairRow {
+            pub a: i64,
+            pub b: i64,
+        }
+
+        pub use compress::PairRowCompressorImpl;
+        pub use decompress::{PairRowColumn, PairRowDecompressorImpl};
+    }
+    use pair::*;
+
+    const COLUMNS: [TestRowColumn; 8] = [
+        TestRowColumn::Ts,
+        TestRowColumn::A,
+        TestRowColumn::B,
+        TestRowColumn::Temp,
+        TestRowColumn::Mode,
+        TestRowColumn::Gain,
+        TestRowColumn::Missing,
+        TestRowColumn::NoiseLevel,
+    ];
+
+    fn random_rows(rng: &mut rand::rngs::ThreadRng, len: usize) -> Vec<TestRow> {
+        let mut ts = rng.gen_range(-1_000_000..1_000_000);
+        let mut a = 0i16;
+        (0..len)
+            .map(|i| {
+                ts += rng.gen_range(0..2000);
+                a = a.wrapping_add(rng.gen_range(-5..=5));
+                TestRow {
+                    ts,
+                    a,
+                    b: if i % 10 < 4 { None } else { Some(rng.gen()) },
+                    temp: rng.gen_range(-4000..4000) as f32 / 100.0,
+                    mode: [Mode::Idle, Mode::Walking, Mode::Running][i / 100 % 3],
+                    gain: [1, 2, 4, 8][rng.gen_range(0..4)],
+                    missing: None,
+                    noise_level: rng.gen(),
+                }
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_macro_compress_column_table_round_trip() {
+        let mut rng = rand::thread_rng();
+
+        for _ in 0..50 {
+            let len = rng.gen_range(1..3000);
+            let rows = random_rows(&mut rng, len);
+
+            // Initialize the compressor
+            let mut compressor = TestRowCompressorImpl::new(rows.len());
+
+            // Compression
+            for row in &rows {
+                compressor.compress(*row);
+            }
+
+            // Finalize the compression
+            let finished_len = compressor.finished_len();
+            let bytes = compressor.finish();
+            assert_eq!(bytes.len(), finished_len);
+
+            // Initialize the decompressor
+            let mut decompressor = TestRowDecompressorImpl::new();
+
+            // Decompress the bit buffer
+            decompressor.decompress(&bytes).unwrap();
+
+            // Assert that the decompressed data matches the original
+            assert_eq!(decompressor.rows(), rows);
+            let iter_rows = TestRowDecompressorImpl::iter_rows(&bytes);
+            assert_eq!(iter_rows.collect::<Result<Vec<_>, _>>().unwrap(), rows);
+
+            // Every column decompresses alone into the same values
+            let mut projection = TestRowDecompressorImpl::new();
+            projection.decompress_columns(&bytes, &COLUMNS).unwrap();
+            assert_eq!(projection.rows(), rows);
+
+            // The selected columns match and the other columns stay empty
+            let count = rng.gen_range(0..=COLUMNS.len());
+            let columns = COLUMNS
+                .choose_multiple(&mut rng, count)
+                .copied()
+                .collect::<Vec<_>>();
+            let mut projection = TestRowDecompressorImpl::new();
+            projection.decompress_columns(&bytes, &columns).unwrap();
+            let selected = |column| columns.contains(&column);
+            let len = |column| if selected(column) { rows.len() } else { 0 };
+            assert_eq!(
+                projection.col_ts(),
+                &decompressor.col_ts()[..len(TestRowColumn::Ts)]
+            );
+            assert_eq!(
+                projection.col_a(),
+                &decompressor.col_a()[..len(TestRowColumn::A)]
+            );
+            assert_eq!(
+                projection.col_b(),
+                &decompressor.col_b()[..len(TestRowColumn::B)]
+            );
+            assert_eq!(
+                projection.col_temp(),
+                &decompressor.col_temp()[..len(TestRowColumn::Temp)]
+            );
+            assert_eq!(
+                projection.col_temp_raw(),
+                &decompressor.col_temp_raw()[..len(TestRowColumn::Temp)]
+            );
+            assert_eq!(
+                projection.col_mode(),
+                &decompressor.col_mode()[..len(TestRowColumn::Mode)]
+            );
+            assert_eq!(
+                projection.col_gain(),
+                &decompressor.col_gain()[..len(TestRowColumn::Gain)]
+            );
+            assert_eq!(
+                projection.col_missing(),
+                &decompressor.col_missing()[..len(TestRowColumn::Missing)]
+            );
+            assert_eq!(
+                projection.col_noise_level(),
+                &decompressor.col_noise_level()[..len(TestRowColumn::NoiseLevel)]
+            );
+
+            // Rows are only whole when every column is selected
+            if columns.len() < COLUMNS.len() {
+                assert!(projection.rows().is_empty());
+            }
+
+            // Projections extend the columns like frames
+            projection.decompress_columns(&bytes, &columns).unwrap();
+            if selected(TestRowColumn::A) {
+                assert_eq!(projection.col_a().len(), 2 * rows.len());
+            }
+        }
+    }
+
+    #[test]
+    fn test_macro_compress_column_table_grid() {
+        let mut rng = rand::thread_rng();
+        let rows = (0..5000)
+            .map(|i| GridRow {
+                ts: 1_700_000_000_000 + i * 1000,
+                value: rng.gen_range(-100..100),
+                flag: if i % 3 == 0 { None } else { Some(i % 2 == 0) },
+            })
+            .collect::<Vec<_>>();
+
+        // Initialize the compressor
+        let mut compressor = GridRowCompressorImpl::new(rows.len());
+
+        // Compression
+        for row in &rows {
+            compressor.compress(*row);
+        }
+
+        // Finalize the compression
+        let bytes = compressor.finish();
+
+        // Initialize the decompressor
+        let mut decompressor = GridRowDecompressorImpl::new();
+
+        // Decompress the bit buffer
+        decompressor
+            .decompress_columns(&bytes, &[GridRowColumn::Ts, GridRowColumn::Flag])
+            .unwrap();
+
+        // Assert that the decompressed data matches the original
+        let ts = rows.iter().map(|row| row.ts).collect::<Vec<_>>();
+        let flag = rows.iter().map(|row| row.flag).collect::<Vec<_>>();
+        assert_eq!(decompressor.col_ts(), ts);
+        assert!(decompressor.col_value().is_empty());
+        assert_eq!(decompressor.col_flag(), flag);
+
+        // The seek index and time ranges skip the column table
+        let range = GridRowDecompressorImpl::iter_rows_range(&bytes, 150..1234)
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        assert_eq!(range, rows[150..1234]);
+        let mut decompressor = GridRowDecompressorImpl::new();
+        decompressor
+            .decompress_range(&bytes, 1_700_000_500_000..1_700_000_600_500)
+            .unwrap();
+        assert_eq!(decompressor.rows(), rows[500..601]);
+    }
+
+    #[test]
+    fn test_macro_compress_column_table_invalid() {
+        let rows = (0..1000)
+            .map(|i| PairRow { a: i * i, b: -i })
+            .collect::<Vec<_>>();
+
+        // Initialize the compressor
+        let mut compressor = PairRowCompressorImpl::new(rows.len());
+
+        // Compression
+        for row in &rows {
+            compressor.compress(*row);
+        }
+
+        // Finalize the compression
+        let bytes = compressor.finish();
+
+        // Initialize the decompressor
+        let mut decompressor = PairRowDecompressorImpl::new();
+
+        // Decompress the bit buffer
+        decompressor
+            .decompress_columns(&bytes, &[PairRowColumn::B])
+            .unwrap();
+
+        // Assert that the decompressed data matches the original
+        let b = rows.iter().map(|row| row.b).collect::<Vec<_>>();
+        assert_eq!(decompressor.col_b(), b);
+        assert!(decompressor.col_a().is_empty());
+
+        // A column of no length after the row count is an error
+        let mut corrupted = bytes.clone();
+        corrupted[4] = 0x00;
+        decompressor.clear();
+        assert!(matches!(
+            decompressor.decompress_columns(&corrupted, &[PairRowColumn::A]),
+            Err(CodingError::InvalidColumnTag)
+        ));
+        assert!(decompressor.col_a().is_empty());
+
+        // A truncated frame ends before the column
+        assert!(decompressor
+            .decompress_columns(&bytes[..bytes.len() / 2], &[PairRowColumn::B])
+            .is_err());
+        assert!(decompressor.col_b().is_empty());
+    }
+}
+
+mod test_lz {
+
+    use super::*;
+    use rand::Rng;
+
+    mod row {
+        use tsz_compress::prelude::*;
+        #[derive(Debug, Copy, Clone, PartialEq, CompressV2, DecompressV2)]
+        #[tsz(lz)]
+        pub struct TestRow {
+            pub ts: i64,
+            pub a: i16,
+            pub b: Option<u8>,
+        }
+
+        pub use compress::TestRowCompressorImpl;
+        pub use decompress::TestRowDecompressorImpl;
+    }
+    use row::*;
+
+    mod framed {
+        use tsz_compress::prelude::*;
+        #[derive(Debug, Copy, Clone, PartialEq, CompressV2, DecompressV2)]
+        #[tsz(header, checksum = "crc32", lz)]
+        pub struct TestRow {
+            pub ts: i64,
+            pub a: i16,
+        }
+
+        pub use compress::TestRowCompressorImpl;
+        pub use decompress::TestRowDecompressorImpl;
+    }
+
+    #[test]
+    fn test_macro_compress_lz_packed() {
+        // A waveform repeating every 16 rows repeats its words
+        let rows = (0..10_000)
+            .map(|i| TestRow {
+                ts: 1_700_000_000_000 + i * 4,
+                a: [0, 3, 9, 20, 35, 20, 9, 3, 0, -3, -9, -20, -35, -20, -9, -3][i as usize % 16],
+                b: if i % 5 == 0 { None } else { Some(i as u8 % 4) },
+            })
+            .collect::<Vec<_>>();
+
+        // Initialize the compressor
+        let mut compressor = TestRowCompressorImpl::new(rows.len());
+
+        // Compression
+        for row in &rows {
+            compressor.compress(*row);
+        }
+
+        // Finalize the compression
+        let finished_len = compressor.finished_len();
+        let bytes = compressor.finish();
+
+        // The packed body is a fraction of the body it replaces
+        assert_eq!(bytes[0], consts::frame::PACKED);
+        assert!(bytes.len() * 4 < finished_len, "{} bytes", bytes.len());
+
+        // Initialize the decompressor
+        let mut decompressor = TestRowDecompressorImpl::new();
+
+        // Decompress the bit buffer
+        decompressor.decompress(&bytes).unwrap();
+
+        // Assert that the decompressed data matches the original
+        assert_eq!(decompressor.rows(), rows);
+        let iter_rows = TestRowDecompressorImpl::iter_rows(&bytes);
+        assert_eq!(iter_rows.size_hint(), (rows.len(), Some(rows.len())));
+        assert_eq!(iter_rows.collect::<Result<Vec<_>, _>>().unwrap(), rows);
+    }
+
+    #[test]
+    fn test_macro_compress_lz_raw() {
+        let mut rng = rand::thread_rng();
+
+        for len in [0, 1, 2, 3, 100] {
+            let rows = (0..len)
+                .map(|_| TestRow {
+                    ts: rng.gen(),
+                    a: rng.gen(),
+                    b: rng.gen(),
+                })
+                .collect::<Vec<_>>();
+
+            // Initialize the compressor
+            let mut compressor = TestRowCompressorImpl::new(rows.len());
+
+            // Compression
+            for row in &rows {
+                compressor.compress(*row);
+            }
+
+            // Finalize the compression
+            let finished_len = compressor.finished_len();
+            let bytes = compressor.finish();
+
+            // Random values do not pack, so the body is stored as is
+            assert_eq!(bytes[0], consts::frame::RAW);
+            assert_eq!(bytes.len(), finished_len);
+
+            // Initialize the decompressor
+            let mut decompressor = TestRowDecompressorImpl::new();
+
+            // Decompress the bit buffer
+            decompressor.decompress(&bytes).unwrap();
+
+            // Assert that the decompressed data matches the original
+            assert_eq!(decompressor.rows(), rows);
+            let iter_rows = TestRowDecompressorImpl::iter_rows(&bytes);
+            assert_eq!(iter_rows.collect::<Result<Vec<_>, _>>().unwrap(), rows);
+        }
+    }
+
+    #[test]
+    fn test_macro_compress_lz_framed() {
+        let rows = (0..1000)
+            .map(|i| framed::TestRow {
+                ts: i * 1000,
+                a: (i % 10) as i16,
+            })
+            .collect::<Vec<_>>();
+
+        // Initialize the compressor
+        let mut compressor = framed::TestRowCompressorImpl::new(rows.len());
+
+        // Compression
+        for row in &rows {
+            compressor.compress(*row);
+        }
+
+        // Finalize the compression
+        let mut bytes = compressor.finish();
+
+        // The flag follows the frame header, and the checksum covers the packed body
+        let header_len = consts::frame::PREFIX_LEN + 2 + consts::frame::SCHEMA_HASH_LEN;
+        assert_eq!(&bytes[..2], &consts::frame::MAGIC);
+        assert_eq!(bytes[header_len], consts::frame::PACKED);
+
+        // Initialize the decompressor
+        let mut decompressor = framed::TestRowDecompressorImpl::new();
+
+        // Decompress the bit buffer
+        decompressor.decompress(&bytes).unwrap();
+
+        // Assert that the decompressed data matches the original
+        assert_eq!(decompressor.rows(), rows);
+
+        // A corrupted packed body fails the checksum before it is unpacked
+        bytes[header_len + 3] ^= 0x40;
+        decompressor.clear();
+        assert!(matches!(
+            decompressor.decompress(&bytes),
+            Err(CodingError::ChecksumMismatch)
+        ));
+        let items = framed::TestRowDecompressorImpl::iter_rows(&bytes).collect::<Vec<_>>();
+        assert!(matches!(items[..], [Err(CodingError::ChecksumMismatch)]));
+    }
+
+    #[test]
+    fn test_macro_compress_lz_invalid_flag() {
+        // Initialize the compressor
+        let mut compressor = TestRowCompressorImpl::new(1);
+
+        // Compression
+        compressor.compress(TestRow {
+            ts: 0,
+            a: 0,
+            b: None,
+        });
+
+        // Finalize the compression
+        let mut bytes = compressor.finish();
+        bytes[0] = 0x7f;
+
+        // Initialize the decompressor
+        let mut decompressor = TestRowDecompressorImpl::new();
+
+        // An unknown flag is invalid
+        assert!(matches!(
+            decompressor.decompress(&bytes),
+            Err(CodingError::InvalidBits)
+        ));
+        let items = TestRowDecompressorImpl::iter_rows(&bytes).collect::<Vec<_>>();
+        assert!(matches!(items[..], [Err(CodingError::InvalidBits)]));
+    }
+}
@@ -485,7 +485,7 @@ pub fn derive_decompressible(item: TokenStream) -> TokenStream {
     .into()
 }
 
-fn get_fields_of_struct(input: syn::DeriveInput) -> Vec<(syn::Ident, syn::Type, Option<String>)> {
+fn get_fields_of_struct(input: syn::DeriveInput) -> Vec<(syn::Ident, syn::Type, FieldAttributes)> {
     let fields = match input.data {
         syn::Data::Struct(syn::DataStruct { fields, .. }) => fields,
         _ => panic!("Expected fields in derive(Builder) struct"),
@@ -495,61 +495,917 @@ fn get_fields_of_struct(input: syn::DeriveInput) -> Vec<(syn::Ident, syn::Type,
         _ => panic!("Expected named fields in derive(Builder) struct"),
     };
 
-    // Get the tsz attributes for each field attribute
-    let delta_attributes = named_fields
-        .iter()
-        .map(|field| {
-            let filtered_attrs: Vec<_> = field
-                .attrs
-                .iter()
-                .filter(|attr| attr.path().is_ident("tsz"))
-                .collect();
-            Option::from(filtered_attrs).filter(|v| !v.is_empty())
+    named_fields
+        .into_iter()
+        .map(|f| {
+            let attrs = get_field_attributes(&f);
+            (f.ident.unwrap(), f.ty, attrs)
         })
-        .collect::<Vec<_>>();
+        .collect::<Vec<_>>() // (ident, ty, attributes)
+}
+
+///
+/// Options of the `#[tsz(...)]` attribute on a field of a struct deriving CompressV2/DecompressV2.
+///
+#[derive(Default, Clone)]
+struct FieldAttributes {
+    /// The bit-width of the deltas, such as "\"i32\""
+    delta: Option<String>,
+    /// The factor quantizing a float field to an integer column
+    scale: Option<proc_macro2::Literal>,
+    /// The integer column type of a scaled float field, such as "i16"
+    as_ty: Option<syn::Type>,
+    /// The largest error of a reconstructed value, trading accuracy for smaller deltas
+    max_error: Option<proc_macro2::Literal>,
+    /// Encode the bits toggled from the previous value rather than the difference
+    flags: bool,
+    /// The integer discriminant type of a fieldless enum field, such as "u8"
+    repr: Option<syn::Type>,
+    /// Mark the field as the timestamp of the row, queried by `decompress_range`
+    timestamp: bool,
+    /// The nominal period of a timestamp field, encoding only the residuals against the grid
+    period: Option<u64>,
+    /// Encode the indices into a dictionary of the distinct values of the frame
+    dictionary: bool,
+}
 
-    // Get delta column types from each tsz field attribute
-    let mut delta_user_col_tys: Vec<Option<String>> = Vec::new();
-    for delta_attribute in delta_attributes {
-        if let Some(delta_attr) = delta_attribute {
-            for attr in delta_attr {
-                // There should only be one tsz attribute per field: delta
-                if let Meta::List(meta_list) = attr.meta.clone() {
-                    let tokens = meta_list.tokens.into_iter().peekable();
-                    let mut identifier = String::new();
-                    let mut punct = String::new();
-                    let mut literal = String::new();
-
-                    for token in tokens {
-                        if let TokenTree::Ident(ident) = &token {
-                            identifier = ident.to_string();
-                        } else if let TokenTree::Punct(p) = &token {
-                            punct = p.to_string();
-                        } else if let TokenTree::Literal(lit) = &token {
-                            literal = lit.to_string();
+fn get_field_attributes(field: &syn::Field) -> FieldAttributes {
+    let mut attributes = FieldAttributes::default();
+    for attr in field
+        .attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("tsz"))
+    {
+        if let Meta::List(meta_list) = attr.meta.clone() {
+            let mut tokens = meta_list.tokens.into_iter();
+            while let Some(token) = tokens.next() {
+                let ident = match token {
+                    TokenTree::Ident(ident) if ident == "flags" => {
+                        attributes.flags = true;
+                        continue;
+                    }
+                    TokenTree::Ident(ident) if ident == "dictionary" => {
+                        attributes.dictionary = true;
+                        continue;
+                    }
+                    TokenTree::Ident(ident) if ident == "timestamp" => {
+                        attributes.timestamp = true;
+                        attributes.period = get_timestamp_period(&mut tokens);
+                        continue;
+                    }
+                    TokenTree::Ident(ident) => ident.to_string(),
+                    TokenTree::Punct(p) if p.as_char() == ',' => continue,
+                    _ => panic!("Unexpected field attribute"),
+                };
+                let literal = match (tokens.next(), tokens.next()) {
+                    (Some(TokenTree::Punct(p)), Some(TokenTree::Literal(lit)))
+                        if p.as_char() == '=' =>
+                    {
+                        lit
+                    }
+                    // A negative literal is a minus sign then the literal
+                    (Some(TokenTree::Punct(p)), Some(TokenTree::Punct(minus)))
+                        if p.as_char() == '=' && minus.as_char() == '-' =>
+                    {
+                        match tokens.next() {
+                            Some(TokenTree::Literal(lit)) => {
+                                let mut negative: proc_macro2::Literal = format!("-{lit}")
+                                    .parse()
+                                    .expect("Unexpected negative literal");
+                                negative.set_span(minus.span());
+                                negative
+                            }
+                            _ => panic!("Unexpected field operator"),
                         }
                     }
+                    _ => panic!("Unexpected field operator"),
+                };
+                match ident.as_str() {
+                    "delta" => attributes.delta = Some(literal.to_string()),
+                    "scale" => attributes.scale = Some(literal),
+                    "max_error" => attributes.max_error = Some(literal),
+                    "as" => {
+                        let ty = literal.to_string().trim_matches('"').to_string();
+                        attributes.as_ty = Some(syn::parse_str(&ty).expect("Unexpected as type"));
+                    }
+                    "repr" => {
+                        let ty = literal.to_string().trim_matches('"').to_string();
+                        if !["i8", "i16", "i32", "i64", "u8", "u16", "u32", "u64"]
+                            .contains(&ty.as_str())
+                        {
+                            panic!("An enum field requires an integer repr of at most 64 bits");
+                        }
+                        attributes.repr = Some(syn::parse_str(&ty).expect("Unexpected repr type"));
+                    }
+                    _ => panic!("Unexpected field attribute"),
+                }
+            }
+        }
+    }
+
+    match (&attributes.scale, &attributes.as_ty) {
+        (Some(_), None) => panic!("A scaled field requires an integer column type: as = \"i32\""),
+        (None, Some(_)) => panic!("An integer column type requires a scale"),
+        _ => (),
+    }
+    if attributes.repr.is_some()
+        && (attributes.scale.is_some() || attributes.max_error.is_some() || attributes.flags)
+    {
+        panic!("An enum field does not take a scale, max_error or flags");
+    }
+    if attributes.timestamp
+        && (attributes.scale.is_some()
+            || attributes.max_error.is_some()
+            || attributes.flags
+            || attributes.repr.is_some())
+    {
+        panic!("A timestamp field does not take a scale, max_error, flags or repr");
+    }
+    if attributes.dictionary
+        && (attributes.scale.is_some()
+            || attributes.max_error.is_some()
+            || attributes.flags
+            || attributes.repr.is_some()
+            || attributes.timestamp)
+    {
+        panic!("A dictionary field does not take a scale, max_error, flags, repr or timestamp");
+    }
+    attributes
+}
+
+///
+/// Parses the optional `(period = N)` of a `timestamp` field attribute.
+///
+fn get_timestamp_period(tokens: &mut proc_macro2::token_stream::IntoIter) -> Option<u64> {
+    let Some(TokenTree::Group(group)) = tokens.clone().next() else {
+        return None;
+    };
+    tokens.next();
+    let mut tokens = group.stream().into_iter();
+    match (tokens.next(), tokens.next(), tokens.next(), tokens.next()) {
+        (
+            Some(TokenTree::Ident(ident)),
+            Some(TokenTree::Punct(p)),
+            Some(TokenTree::Literal(lit)),
+            None,
+        ) if ident == "period" && p.as_char() == '=' => match lit.to_string().parse() {
+            Ok(period) if period > 0 => Some(period),
+            _ => panic!("Expected a positive timestamp period"),
+        },
+        _ => panic!("Expected a timestamp period: timestamp(period = 1000)"),
+    }
+}
+
+///
+/// Options of the `#[tsz(...)]` attribute on a struct deriving CompressV2/DecompressV2.
+///
+#[derive(Default)]
+struct StructAttributes {
+    /// Emit and validate a frame header describing the columns
+    header: bool,
+    /// Append and verify a checksum trailer: "crc16" or "crc32"
+    checksum: Option<String>,
+    /// Generate a C interface with functions named by this prefix
+    ffi: Option<String>,
+    /// Append a seek index with a checkpoint every this many rows
+    seek: Option<usize>,
+    /// Prefix the columns with a table of their lengths, so that a decoder may decode only some columns
+    column_table: bool,
+    /// Pack the body of the frame into an LZ4 block when it is shorter
+    lz: bool,
+}
 
-                    match (identifier.as_str(), punct.as_str()) {
-                        ("delta", "=") => delta_user_col_tys.push(Some(literal)),
-                        ("delta", _) => panic!("Unexpected field operator"),
-                        _ => panic!("Unexpected delta bit-width attribute"),
+fn get_struct_attributes(input: &syn::DeriveInput) -> StructAttributes {
+    let mut attributes = StructAttributes::default();
+    for attr in input
+        .attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("tsz"))
+    {
+        if let Meta::List(meta_list) = attr.meta.clone() {
+            let mut tokens = meta_list.tokens.into_iter();
+            while let Some(token) = tokens.next() {
+                match token {
+                    TokenTree::Ident(ident) if ident == "header" => attributes.header = true,
+                    TokenTree::Ident(ident) if ident == "lz" => attributes.lz = true,
+                    TokenTree::Ident(ident) if ident == "column_table" => {
+                        attributes.column_table = true
+                    }
+                    TokenTree::Ident(ident) if ident == "checksum" => {
+                        match (tokens.next(), tokens.next()) {
+                            (Some(TokenTree::Punct(p)), Some(TokenTree::Literal(lit)))
+                                if p.as_char() == '=' =>
+                            {
+                                match lit.to_string().as_str() {
+                                    "\"crc16\"" => attributes.checksum = Some("crc16".into()),
+                                    "\"crc32\"" => attributes.checksum = Some("crc32".into()),
+                                    _ => panic!(
+                                        "Unexpected checksum, expected \"crc16\" or \"crc32\""
+                                    ),
+                                }
+                            }
+                            _ => panic!("Unexpected checksum attribute"),
+                        }
                     }
+                    TokenTree::Ident(ident) if ident == "ffi" => {
+                        // The prefix defaults to tsz_ and the snake case name of the struct
+                        let mut prefix = format!("tsz_{}", snake_case(&input.ident.to_string()));
+                        if let Some(TokenTree::Punct(p)) = tokens.clone().next() {
+                            if p.as_char() == '=' {
+                                tokens.next();
+                                match tokens.next() {
+                                    Some(TokenTree::Literal(lit)) => {
+                                        prefix = lit.to_string().trim_matches('"').to_string()
+                                    }
+                                    _ => panic!("Unexpected ffi attribute"),
+                                }
+                            }
+                        }
+                        attributes.ffi = Some(prefix);
+                    }
+                    TokenTree::Ident(ident) if ident == "seek" => {
+                        match (tokens.next(), tokens.next()) {
+                            (Some(TokenTree::Punct(p)), Some(TokenTree::Literal(lit)))
+                                if p.as_char() == '=' =>
+                            {
+                                match lit.to_string().replace('_', "").parse() {
+                                    Ok(every) if every > 0 && every <= u32::MAX as usize => {
+                                        attributes.seek = Some(every)
+                                    }
+                                    _ => panic!("Expected a positive seek interval"),
+                                }
+                            }
+                            _ => panic!("Unexpected seek attribute, expected seek = 1024"),
+                        }
+                    }
+                    TokenTree::Punct(p) if p.as_char() == ',' => (),
+                    _ => panic!("Unexpected struct attribute"),
                 }
             }
-        } else {
-            delta_user_col_tys.push(None);
         }
     }
+    attributes
+}
 
-    named_fields
+///
+/// Converts a CamelCase identifier to snake_case.
+///
+fn snake_case(ident: &str) -> String {
+    let mut snake = String::new();
+    let mut prev_lower = false;
+    for c in ident.chars() {
+        if c.is_uppercase() && prev_lower {
+            snake.push('_');
+        }
+        prev_lower = c.is_lowercase() || c.is_ascii_digit();
+        snake.extend(c.to_lowercase());
+    }
+    snake
+}
+
+///
+/// Converts a snake_case identifier to CamelCase.
+///
+fn camel_case(ident: &str) -> String {
+    ident
+        .split('_')
+        .map(|word| {
+            let mut chars = word.chars();
+            chars
+                .next()
+                .map_or(String::new(), |c| c.to_uppercase().chain(chars).collect())
+        })
+        .collect()
+}
+
+///
+/// Skips the table of column lengths of a `#[tsz(column_table)]` struct.
+///
+fn skip_column_table(column_table: bool, num_columns: usize) -> proc_macro2::TokenStream {
+    if !column_table {
+        return quote! {};
+    }
+    quote! {
+        // Skip the table of column lengths
+        ::tsz_compress::prelude::read_column_table::<#num_columns>(&mut iter)?;
+    }
+}
+
+///
+/// The column type of each field and whether the field is an `Option` of the column type.
+///
+fn column_types(field_tys: &[syn::Type]) -> (Vec<syn::Type>, Vec<bool>) {
+    field_tys
+        .iter()
+        .map(|ty| {
+            if let syn::Type::Path(syn::TypePath { path, .. }) = ty {
+                let segment = path.segments.last().unwrap();
+                if segment.ident == "Option" {
+                    if let syn::PathArguments::AngleBracketed(args) = &segment.arguments {
+                        if let Some(syn::GenericArgument::Type(inner)) = args.args.first() {
+                            return (inner.clone(), true);
+                        }
+                    }
+                    panic!("Unsupported type");
+                }
+            }
+            (ty.clone(), false)
+        })
+        .unzip()
+}
+
+///
+/// Replaces the column type of each scaled float field with its integer column type,
+/// returning the expressions converting a `value` of the field to the column and back.
+///
+/// Quantizing rounds half away from zero and saturates at the bounds of the column,
+/// and rescaling divides the column by the scale.
+///
+fn scaled_column_types(
+    col_tys: Vec<syn::Type>,
+    col_attrs: &[FieldAttributes],
+) -> (
+    Vec<syn::Type>,
+    Vec<Option<(proc_macro2::TokenStream, proc_macro2::TokenStream)>>,
+) {
+    col_tys
         .into_iter()
-        .enumerate()
-        .map(|(i, f)| {
-            let attr = &delta_user_col_tys[i];
-            (f.ident.unwrap(), f.ty, attr.clone())
+        .zip(col_attrs)
+        .map(|(ty, attrs)| match (&attrs.scale, &attrs.as_ty) {
+            (Some(scale), Some(as_ty)) => {
+                if !is_float(&ty) {
+                    panic!("Only f32 and f64 fields may be scaled");
+                }
+                // The fraction of the truncated value is exact, unlike adding 0.5 before truncating
+                let quantize = quote! {
+                    {
+                        let scaled = value * (#scale as #ty);
+                        let truncated = scaled as #as_ty;
+                        let fraction = scaled - truncated as #ty;
+                        if fraction >= 0.5 {
+                            truncated.saturating_add(1)
+                        } else if fraction <= -0.5 {
+                            truncated.saturating_sub(1)
+                        } else {
+                            truncated
+                        }
+                    }
+                };
+                let rescale = quote! { value as #ty / (#scale as #ty) };
+                (as_ty.clone(), Some((quantize, rescale)))
+            }
+            _ => (ty, None),
         })
-        .collect::<Vec<_>>() // (ident, ty, delta_bit_width)
+        .unzip()
+}
+
+///
+/// Replaces the column type of each enum field with the integer type of its discriminant,
+/// returning the enum type of the field.
+///
+fn enum_column_types(
+    col_tys: Vec<syn::Type>,
+    col_attrs: &[FieldAttributes],
+) -> (Vec<syn::Type>, Vec<Option<syn::Type>>) {
+    col_tys
+        .into_iter()
+        .zip(col_attrs)
+        .map(|(ty, attrs)| match &attrs.repr {
+            Some(repr) => (repr.clone(), Some(ty)),
+            None => (ty, None),
+        })
+        .unzip()
+}
+
+///
+/// Whether the column is floating point, which is XOR encoded rather than delta encoded.
+///
+fn is_float(ty: &syn::Type) -> bool {
+    matches!(ty, syn::Type::Path(syn::TypePath { path, .. }) if path.is_ident("f32") || path.is_ident("f64"))
+}
+
+///
+/// The signed integer type of the same bit-width as an integer column.
+///
+fn signed_type(ty: &syn::Type) -> proc_macro2::TokenStream {
+    match quote! { #ty }.to_string().as_str() {
+        "i8" | "u8" => quote! { i8 },
+        "i16" | "u16" => quote! { i16 },
+        "i32" | "u32" => quote! { i32 },
+        "i64" | "u64" => quote! { i64 },
+        _ => panic!("Unsupported type"),
+    }
+}
+
+///
+/// Checks that the scale of each scaled field is a number greater than 0,
+/// reporting the first that is not on its literal.
+///
+fn check_scales(col_attrs: &[FieldAttributes]) -> syn::Result<()> {
+    for scale in col_attrs.iter().filter_map(|attrs| attrs.scale.as_ref()) {
+        let value = match syn::Lit::new(scale.clone()) {
+            syn::Lit::Int(lit) => lit.base10_parse::<f64>().ok(),
+            syn::Lit::Float(lit) => lit.base10_parse::<f64>().ok(),
+            _ => None,
+        };
+        if !value.is_some_and(|value| value > 0.0) {
+            return Err(syn::Error::new(
+                scale.span(),
+                "A scale must be a number greater than 0",
+            ));
+        }
+    }
+    Ok(())
+}
+
+///
+/// The max_error of each lossy column, which must be a positive integer that fits the column type,
+/// reporting the first that is not on its literal.
+///
+fn max_errors(
+    col_tys: &[syn::Type],
+    col_attrs: &[FieldAttributes],
+) -> syn::Result<Vec<Option<u64>>> {
+    let mut max_errors = Vec::new();
+    for (ty, attrs) in col_tys.iter().zip(col_attrs) {
+        let Some(literal) = &attrs.max_error else {
+            max_errors.push(None);
+            continue;
+        };
+        let ty = quote! { #ty }.to_string();
+        let max = match ty.as_str() {
+            "i8" => i8::MAX as u64,
+            "u8" => u8::MAX as u64,
+            "i16" => i16::MAX as u64,
+            "u16" => u16::MAX as u64,
+            "i32" => i32::MAX as u64,
+            "u32" => u32::MAX as u64,
+            "i64" => i64::MAX as u64,
+            // Other column types are rejected with the lossy columns
+            _ => u64::MAX,
+        };
+        let max_error = match syn::Lit::new(literal.clone()) {
+            syn::Lit::Int(lit) => lit.base10_parse::<u64>().ok(),
+            _ => None,
+        };
+        match max_error {
+            Some(max_error) if max_error > 0 && max_error <= max => {
+                max_errors.push(Some(max_error))
+            }
+            _ => {
+                return Err(syn::Error::new(
+                    literal.span(),
+                    format!("A max_error of a {ty} column must be an integer in 1..={max}"),
+                ))
+            }
+        }
+    }
+    Ok(max_errors)
+}
+
+///
+/// Whether each column encodes the bits toggled from the previous value: bool and `#[tsz(flags)]` columns.
+///
+fn transition_columns(col_tys: &[syn::Type], col_attrs: &[FieldAttributes]) -> Vec<bool> {
+    col_tys
+        .iter()
+        .zip(col_attrs)
+        .map(|(ty, attrs)| {
+            let bool_column =
+                matches!(ty, syn::Type::Path(syn::TypePath { path, .. }) if path.is_ident("bool"));
+            if attrs.flags {
+                let ty = quote! { #ty }.to_string();
+                if !["i8", "i16", "i32", "i64", "u8", "u16", "u32", "u64"].contains(&ty.as_str()) {
+                    panic!("Only integer fields of at most 64 bits may be flags");
+                }
+            }
+            if (bool_column || attrs.flags) && (attrs.delta.is_some() || attrs.max_error.is_some())
+            {
+                panic!("A bool or flags field does not take a delta bit-width or max_error");
+            }
+            bool_column || attrs.flags
+        })
+        .collect()
+}
+
+///
+/// The nominal period of each timestamp column on a grid.
+/// Every timestamp must be an integer column of at most 64 bits with a value in every row.
+///
+fn grid_periods(
+    col_tys: &[syn::Type],
+    col_nullable: &[bool],
+    col_attrs: &[FieldAttributes],
+) -> Vec<Option<u64>> {
+    col_tys
+        .iter()
+        .zip(col_nullable)
+        .zip(col_attrs)
+        .map(|((ty, nullable), attrs)| {
+            if !attrs.timestamp {
+                return None;
+            }
+            let ty = quote! { #ty }.to_string();
+            if !["i8", "i16", "i32", "i64", "u8", "u16", "u32", "u64"].contains(&ty.as_str()) {
+                panic!("Only integer fields of at most 64 bits may be timestamps");
+            }
+            if *nullable {
+                panic!("A timestamp field may not be an Option");
+            }
+            attrs.period
+        })
+        .collect()
+}
+
+///
+/// The column queried by `decompress_range`, the first field marked as a timestamp.
+///
+fn timestamp_column(col_attrs: &[FieldAttributes]) -> Option<usize> {
+    col_attrs.iter().position(|attrs| attrs.timestamp)
+}
+
+///
+/// Whether each column is dictionary encoded, which must be an integer column of at most 64 bits with a value in every row.
+///
+fn dictionary_columns(
+    col_tys: &[syn::Type],
+    col_nullable: &[bool],
+    col_attrs: &[FieldAttributes],
+) -> Vec<bool> {
+    col_tys
+        .iter()
+        .zip(col_nullable)
+        .zip(col_attrs)
+        .map(|((ty, nullable), attrs)| {
+            if !attrs.dictionary {
+                return false;
+            }
+            let ty = quote! { #ty }.to_string();
+            if !["i8", "i16", "i32", "i64", "u8", "u16", "u32", "u64"].contains(&ty.as_str()) {
+                panic!("Only integer fields of at most 64 bits may be dictionary encoded");
+            }
+            if *nullable {
+                panic!("A dictionary field may not be an Option");
+            }
+            true
+        })
+        .collect()
+}
+
+///
+/// The integer type of the bits of a floating point or bool column, or the column type.
+///
+fn column_bits_type(ty: &syn::Type) -> syn::Type {
+    match ty {
+        syn::Type::Path(syn::TypePath { path, .. }) if path.is_ident("bool") => {
+            syn::parse_quote! { u8 }
+        }
+        syn::Type::Path(syn::TypePath { path, .. }) if path.is_ident("f32") => {
+            syn::parse_quote! { u32 }
+        }
+        syn::Type::Path(syn::TypePath { path, .. }) if path.is_ident("f64") => {
+            syn::parse_quote! { u64 }
+        }
+        _ => ty.clone(),
+    }
+}
+
+///
+/// The C type of each column for the generated C header.
+///
+/// Enum fields are the C type of their discriminant.
+///
+fn column_c_types(field_tys: &[syn::Type], col_attrs: &[FieldAttributes]) -> Vec<&'static str> {
+    field_tys
+        .iter()
+        .zip(col_attrs)
+        .map(|(ty, attrs)| match ty {
+            syn::Type::Path(syn::TypePath { path, .. }) => {
+                let segment = path.segments.first().unwrap();
+                let ident = match &attrs.repr {
+                    Some(repr) if segment.ident != "Option" => quote! { #repr }.to_string(),
+                    _ => segment.ident.to_string(),
+                };
+                match ident.as_str() {
+                    "i8" => "int8_t",
+                    "i16" => "int16_t",
+                    "i32" => "int32_t",
+                    "i64" => "int64_t",
+                    "u8" => "uint8_t",
+                    "u16" => "uint16_t",
+                    "u32" => "uint32_t",
+                    "u64" => "uint64_t",
+                    "f32" => "float",
+                    "f64" => "double",
+                    "bool" => "bool",
+                    "i128" => panic!("i128 columns are not supported by the C interface"),
+                    "Option" => panic!("Option columns are not supported by the C interface"),
+                    _ => panic!("Unsupported type"),
+                }
+            }
+            _ => panic!("Unsupported type"),
+        })
+        .collect()
+}
+
+///
+/// The Rust type of each column in the C interface.
+///
+/// Enum fields are their discriminant, which is checked before it becomes a variant.
+///
+fn column_ffi_types(
+    field_tys: &[syn::Type],
+    col_tys: &[syn::Type],
+    col_enums: &[Option<syn::Type>],
+) -> Vec<syn::Type> {
+    field_tys
+        .iter()
+        .zip(col_tys)
+        .zip(col_enums)
+        .map(|((field_ty, col_ty), enum_ty)| match enum_ty {
+            Some(_) => col_ty.clone(),
+            None => field_ty.clone(),
+        })
+        .collect()
+}
+
+///
+/// The error codes of the C interface, one for each `CodingError`.
+///
+const C_ERROR_CODES: &str = "#ifndef TSZ_ERROR_CODES\n\
+                             #define TSZ_ERROR_CODES\n\
+                             #define TSZ_ERROR_EMPTY -1\n\
+                             #define TSZ_ERROR_NOT_ENOUGH_BITS -2\n\
+                             #define TSZ_ERROR_INVALID_BITS -3\n\
+                             #define TSZ_ERROR_INVALID_INITIAL_COLUMN_TAG -4\n\
+                             #define TSZ_ERROR_INVALID_COLUMN_TAG -5\n\
+                             #define TSZ_ERROR_COLUMN_LENGTH_MISMATCH -6\n\
+                             #define TSZ_ERROR_INVALID_ROW_COUNT -7\n\
+                             #define TSZ_ERROR_SCHEMA_MISMATCH -8\n\
+                             #define TSZ_ERROR_CHECKSUM_MISMATCH -9\n\
+                             #define TSZ_ERROR_INVALID_DISCRIMINANT -10\n\
+                             #endif /* TSZ_ERROR_CODES */\n";
+
+///
+/// The frame header type code of each column.
+///
+fn column_type_codes(
+    col_tys: &[syn::Type],
+    col_nullable: &[bool],
+    col_attrs: &[FieldAttributes],
+) -> Vec<proc_macro2::TokenStream> {
+    col_tys
+        .iter()
+        .map(|ty| match ty {
+            syn::Type::Path(syn::TypePath { path, .. }) => {
+                let segment = path.segments.first().unwrap();
+                let ident = segment.ident.clone();
+                match ident.to_string().as_str() {
+                    "i8" => quote! { ::tsz_compress::prelude::consts::frame::I8 },
+                    "i16" => quote! { ::tsz_compress::prelude::consts::frame::I16 },
+                    "i32" => quote! { ::tsz_compress::prelude::consts::frame::I32 },
+                    "i64" => quote! { ::tsz_compress::prelude::consts::frame::I64 },
+                    "i128" => quote! { ::tsz_compress::prelude::consts::frame::I128 },
+                    "u8" => quote! { ::tsz_compress::prelude::consts::frame::U8 },
+                    "u16" => quote! { ::tsz_compress::prelude::consts::frame::U16 },
+                    "u32" => quote! { ::tsz_compress::prelude::consts::frame::U32 },
+                    "u64" => quote! { ::tsz_compress::prelude::consts::frame::U64 },
+                    "f32" => quote! { ::tsz_compress::prelude::consts::frame::F32 },
+                    "f64" => quote! { ::tsz_compress::prelude::consts::frame::F64 },
+                    "bool" => quote! { ::tsz_compress::prelude::consts::frame::BOOL },
+                    _ => panic!("Unsupported type"),
+                }
+            }
+            _ => panic!("Unsupported type"),
+        })
+        .zip(col_attrs)
+        .map(|(code, attrs)| {
+            if attrs.flags {
+                quote! { #code | ::tsz_compress::prelude::consts::frame::FLAGS }
+            } else {
+                code
+            }
+        })
+        .zip(col_nullable)
+        .map(|(code, nullable)| {
+            if *nullable {
+                quote! { #code | ::tsz_compress::prelude::consts::frame::NULLABLE }
+            } else {
+                code
+            }
+        })
+        .collect()
+}
+
+///
+/// A 32-bit FNV-1a hash of the field names and types, in declaration order, then of the layout of the frame.
+///
+/// Scaled fields include the scale and the integer column type, enum fields include the
+/// discriminant type, timestamp fields include the period, and flags and dictionary fields are marked.
+/// The seek interval, column table, checksum kind and LZ flag byte follow the fields when the frame has them.
+///
+fn schema_hash(
+    col_idents: &[syn::Ident],
+    field_tys: &[syn::Type],
+    col_attrs: &[FieldAttributes],
+    struct_attrs: &StructAttributes,
+) -> u32 {
+    let mut layout = String::new();
+    if let Some(every) = struct_attrs.seek {
+        layout.push_str(&format!("#seek = {};", every));
+    }
+    if struct_attrs.column_table {
+        layout.push_str("#column_table;");
+    }
+    if let Some(checksum) = &struct_attrs.checksum {
+        layout.push_str(&format!("#checksum = {};", checksum));
+    }
+    if struct_attrs.lz {
+        layout.push_str("#lz;");
+    }
+
+    let mut hash: u32 = 0x811c_9dc5;
+    for ((ident, ty), attrs) in col_idents.iter().zip(field_tys).zip(col_attrs) {
+        let field = match (&attrs.scale, &attrs.as_ty, &attrs.repr, attrs.period) {
+            (Some(scale), Some(as_ty), _, _) => {
+                format!(
+                    "{}:{}*{} as {};",
+                    ident,
+                    quote! { #ty },
+                    scale,
+                    quote! { #as_ty }
+                )
+            }
+            (_, _, Some(repr), _) => {
+                format!("{}:{} as {};", ident, quote! { #ty }, quote! { #repr })
+            }
+            (_, _, _, Some(period)) => {
+                format!(
+                    "{}:{} timestamp(period = {});",
+                    ident,
+                    quote! { #ty },
+                    period
+                )
+            }
+            _ if attrs.flags => format!("{}:{} flags;", ident, quote! { #ty }),
+            _ if attrs.dictionary => format!("{}:{} dictionary;", ident, quote! { #ty }),
+            _ => format!("{}:{};", ident, quote! { #ty }),
+        };
+        for byte in field.bytes() {
+            hash ^= byte as u32;
+            hash = hash.wrapping_mul(0x0100_0193);
+        }
+    }
+    for byte in layout.bytes() {
+        hash ^= byte as u32;
+        hash = hash.wrapping_mul(0x0100_0193);
+    }
+    hash
+}
+
+///
+/// The number of bytes of the checksum trailer.
+///
+fn checksum_len(checksum: &Option<String>) -> usize {
+    match checksum.as_deref() {
+        Some("crc16") => 2,
+        Some("crc32") => 4,
+        _ => 0,
+    }
+}
+
+///
+/// Appends the checksum trailer over the bytes written since `start`.
+///
+fn write_checksum(checksum: &Option<String>) -> proc_macro2::TokenStream {
+    match checksum.as_deref() {
+        Some("crc16") => quote! {
+            // Append the checksum of the frame
+            let crc = ::tsz_compress::prelude::checksum::crc16(&output_bytes[start..]);
+            output_bytes.extend(crc.to_be_bytes());
+        },
+        Some("crc32") => quote! {
+            // Append the checksum of the frame
+            let crc = ::tsz_compress::prelude::checksum::crc32(&output_bytes[start..]);
+            output_bytes.extend(crc.to_be_bytes());
+        },
+        _ => quote! {},
+    }
+}
+
+///
+/// The bytes of the body flag, and the tokens to pack the body written after `body` when it is shorter.
+/// The body is only packed by a `#[tsz(lz)]` struct.
+fn lz_tokens(
+    lz: bool,
+) -> (
+    proc_macro2::TokenStream,
+    proc_macro2::TokenStream,
+    proc_macro2::TokenStream,
+) {
+    if !lz {
+        return (quote! { 0 }, quote! {}, quote! {});
+    }
+    (
+        quote! { 1 },
+        quote! {
+            // The body of the frame follows the header
+            let body = output_bytes.len();
+        },
+        quote! {
+            // Pack the body of the frame when it is shorter, flagging whether it is packed
+            let packed = ::tsz_compress::prelude::lz::pack(&output_bytes[body..]);
+            if packed.len() < output_bytes.len() - body {
+                output_bytes.truncate(body);
+                output_bytes.push(::tsz_compress::prelude::consts::frame::PACKED);
+                output_bytes.extend_from_slice(&packed);
+            } else {
+                output_bytes.insert(body, ::tsz_compress::prelude::consts::frame::RAW);
+            }
+        },
+    )
+}
+
+/// Verifies the checksum trailer, shadowing `bytes` with the bytes it covers.
+///
+fn read_checksum(checksum: &Option<String>) -> proc_macro2::TokenStream {
+    match checksum.as_deref() {
+        Some("crc16") => quote! {
+            // Verify the checksum of the frame
+            let bytes = ::tsz_compress::prelude::checksum::verify_crc16(bytes)?;
+        },
+        Some("crc32") => quote! {
+            // Verify the checksum of the frame
+            let bytes = ::tsz_compress::prelude::checksum::verify_crc32(bytes)?;
+        },
+        _ => quote! {},
+    }
+}
+
+///
+/// Reads whether each Option column has any values, and the condition that any column is in the frame.
+///
+fn presence_tokens(col_nullable: &[bool]) -> (proc_macro2::TokenStream, proc_macro2::TokenStream) {
+    // Option columns without values are omitted, so only columns with a value in every row bound the row count
+    let num_nullable = col_nullable.iter().filter(|nullable| **nullable).count();
+    let read_presence = if num_nullable > 0 {
+        quote! {
+            // Read whether each Option column has any values
+            let present = read_presence::<#num_nullable>(&mut iter)?;
+        }
+    } else {
+        quote! {}
+    };
+    let any_column = if num_nullable < col_nullable.len() {
+        quote! {}
+    } else {
+        quote! { present.contains(&true) && }
+    };
+    (read_presence, any_column)
+}
+
+///
+/// The type of the cursor of each column, borrowing the bytes for `'a`,
+/// and the statements placing each cursor at the start of its column.
+///
+fn column_cursors(
+    col_idents: &[syn::Ident],
+    col_tys: &[syn::Type],
+    col_nullable: &[bool],
+    col_periods: &[Option<u64>],
+    col_dictionary: &[bool],
+) -> (Vec<proc_macro2::TokenStream>, Vec<proc_macro2::TokenStream>) {
+    let mut nullable_index = 0;
+    let mut col_cursor_tys = Vec::new();
+    let mut col_cursor_blocks = Vec::new();
+    for i in 0..col_idents.len() {
+        let col_ident = &col_idents[i];
+        let col_ty = &col_tys[i];
+        if col_nullable[i] {
+            let present_index = syn::Index::from(nullable_index);
+            nullable_index += 1;
+            col_cursor_tys.push(quote! { ::tsz_compress::prelude::NullableCursor<'a, #col_ty> });
+            col_cursor_blocks.push(quote! {
+                let #col_ident = ::tsz_compress::prelude::NullableCursor::<#col_ty>::new(&mut iter, rows as usize, present[#present_index])?;
+            });
+        } else if col_dictionary[i] {
+            col_cursor_tys.push(quote! { ::tsz_compress::prelude::DictionaryCursor<'a, #col_ty> });
+            col_cursor_blocks.push(quote! {
+                let #col_ident = ::tsz_compress::prelude::DictionaryCursor::<#col_ty>::new(&mut iter)?;
+            });
+        } else if col_periods[i].is_some() {
+            col_cursor_tys.push(quote! { ::tsz_compress::prelude::GridCursor<'a, #col_ty> });
+            col_cursor_blocks.push(quote! {
+                let #col_ident = ::tsz_compress::prelude::GridCursor::<#col_ty>::new(&mut iter, rows as usize)?;
+            });
+        } else {
+            col_cursor_tys.push(quote! { ::tsz_compress::prelude::ColumnCursor<'a, #col_ty> });
+            col_cursor_blocks.push(quote! {
+                let #col_ident = ::tsz_compress::prelude::ColumnCursor::<#col_ty>::new(&mut iter)?;
+            });
+        }
+    }
+    (col_cursor_tys, col_cursor_blocks)
 }
 
 ///
@@ -566,8 +1422,43 @@ pub fn derive_compressv2(tokens: TokenStream) -> TokenStream {
     let compressor_ident = format_ident!("{}CompressorImpl", input.ident);
 
     // We will compress each of the fields as columns
+    let struct_attrs = get_struct_attributes(&input);
     let columns = get_fields_of_struct(input);
-    let (col_idents, col_tys, col_attrs): (Vec<_>, Vec<_>, Vec<_>) = multiunzip(columns);
+    let (col_idents, field_tys, col_attrs): (Vec<_>, Vec<_>, Vec<_>) = multiunzip(columns);
+    let (col_tys, col_nullable) = column_types(&field_tys);
+    if let Err(e) = check_scales(&col_attrs) {
+        return e.to_compile_error().into();
+    }
+    let (col_tys, col_scaling) = scaled_column_types(col_tys, &col_attrs);
+    let (col_tys, col_enums) = enum_column_types(col_tys, &col_attrs);
+    let col_max_errors = match max_errors(&col_tys, &col_attrs) {
+        Ok(col_max_errors) => col_max_errors,
+        Err(e) => return e.to_compile_error().into(),
+    };
+    let col_periods = grid_periods(&col_tys, &col_nullable, &col_attrs);
+    let col_dictionary = dictionary_columns(&col_tys, &col_nullable, &col_attrs);
+
+    // Floating point columns are XOR encoded, sharing the tables of the unsigned integer of the same bit-width
+    let col_float = col_tys.iter().map(is_float).collect_vec();
+    let col_bits_tys = col_tys.iter().map(column_bits_type).collect_vec();
+    let col_transitions = transition_columns(&col_tys, &col_attrs);
+    let xor_encoder_idents = col_idents
+        .iter()
+        .zip(&col_float)
+        .filter(|(_, float)| **float)
+        .map(|(ident, _)| format_ident!("{}_xor_encoder", ident))
+        .collect_vec();
+    let xor_encoder_bits = col_tys
+        .iter()
+        .filter(|ty| is_float(ty))
+        .map(|ty| {
+            if quote! { #ty }.to_string() == "f32" {
+                32usize
+            } else {
+                64usize
+            }
+        })
+        .collect_vec();
     let col_delta_comp_queue_idents = col_idents
         .iter()
         .map(|ident| format_ident!("{}_delta_compressor_queue", ident))
@@ -589,8 +1480,8 @@ pub fn derive_compressv2(tokens: TokenStream) -> TokenStream {
     // Get the delta types for each column: If user specified, use that, otherwise use default
     let delta_col_tys = col_attrs
         .iter()
-        .zip(&col_tys)
-        .map(|(attr, ty)| match attr.as_ref() {
+        .zip(&col_bits_tys)
+        .map(|(attr, ty)| match attr.delta.as_ref() {
             Some(s) if s == "\"i8\"" => quote! { i8 },
             Some(s) if s == "\"i16\"" => quote! { i16 },
             Some(s) if s == "\"i32\"" => quote! { i32 },
@@ -605,6 +1496,11 @@ pub fn derive_compressv2(tokens: TokenStream) -> TokenStream {
                         "i16" => quote! { i32 },
                         "i32" => quote! { i64 },
                         "i64" => quote! { i64 },
+                        "u8" => quote! { i16 },
+                        "u16" => quote! { i32 },
+                        "u32" => quote! { i64 },
+                        "u64" => quote! { i64 },
+                        "i128" => quote! { i128 },
                         _ => panic!("Unsupported type"),
                     }
                 }
@@ -614,7 +1510,7 @@ pub fn derive_compressv2(tokens: TokenStream) -> TokenStream {
         })
         .collect::<Vec<_>>();
 
-    let double_col_tys = col_tys
+    let double_col_tys = col_bits_tys
         .iter()
         .map(|ty| match ty {
             syn::Type::Path(syn::TypePath { path, .. }) => {
@@ -624,48 +1520,12 @@ pub fn derive_compressv2(tokens: TokenStream) -> TokenStream {
                     "i8" => quote! { i16 },
                     "i16" => quote! { i32 },
                     "i32" => quote! { i64 },
-                    "i64" => quote! { i128 },
-                    "i128" => quote! { i128 }, // Note i128 is not doubled
-                    _ => panic!("Unsupported type"),
-                }
-            }
-            _ => panic!("Unsupported type"),
-        })
-        .collect::<Vec<_>>();
-
-    // todo, make choice based on macro attributes for field, default to delta-delta and delta
-    let col_delta_buf = col_tys
-        .iter()
-        .map(|ty| match ty {
-            syn::Type::Path(syn::TypePath { path, .. }) => {
-                let segment = path.segments.first().unwrap();
-                let ident = segment.ident.clone();
-                match ident.to_string().as_str() {
-                    "i8" => quote! { Some(::tsz_compress::prelude::halfvec::HalfVec::new(prealloc_rows)) },
-                    "i16" => quote! { Some(::tsz_compress::prelude::halfvec::HalfVec::new(prealloc_rows)) },
-                    "i32" => quote! { Some(::tsz_compress::prelude::halfvec::HalfVec::new(prealloc_rows)) },
-                    "i64" => quote! { Some(::tsz_compress::prelude::halfvec::HalfVec::new(prealloc_rows)) },
-                    // "i64" => quote! { None },
-                    "i128" => quote! { None },
-                    _ => panic!("Unsupported type"),
-                }
-            }
-            _ => panic!("Unsupported type"),
-        })
-        .collect::<Vec<_>>();
-    let col_delta_delta_buf = col_tys
-        .iter()
-        .map(|ty| match ty {
-            syn::Type::Path(syn::TypePath { path, .. }) => {
-                let segment = path.segments.first().unwrap();
-                let ident = segment.ident.clone();
-                match ident.to_string().as_str() {
-                    "i8" => quote! { None },
-                    "i16" => quote! { None },
-                    "i32" => quote! { None },
-                    "i64" => quote! { None },
-                    // "i64" => quote! { Some(::tsz_compress::prelude::halfvec::HalfVec::new(prealloc_rows)) },
-                    "i128" => quote! { Some(::tsz_compress::prelude::halfvec::HalfVec::new(prealloc_rows)) },
+                    "i64" => quote! { i128 },
+                    "i128" => quote! { i128 }, // Note i128 is not doubled
+                    "u8" => quote! { i16 },
+                    "u16" => quote! { i32 },
+                    "u32" => quote! { i64 },
+                    "u64" => quote! { i128 },
                     _ => panic!("Unsupported type"),
                 }
             }
@@ -673,42 +1533,73 @@ pub fn derive_compressv2(tokens: TokenStream) -> TokenStream {
         })
         .collect::<Vec<_>>();
 
-    let write_first = col_tys
+    // Both delta and delta-delta are raced for each column, keeping the shorter on finish
+    let col_delta_buf = col_bits_tys
         .iter()
         .map(|ty| match ty {
             syn::Type::Path(syn::TypePath { path, .. }) => {
                 let segment = path.segments.first().unwrap();
                 let ident = segment.ident.clone();
                 match ident.to_string().as_str() {
-                    "i8" => quote! { write_i8_bits },
-                    "i16" => quote! { write_i16_bits },
-                    "i32" => quote! { write_i32_bits },
-                    "i64" => quote! { write_i64_bits },
-                    "i128" => quote! { write_i128_bits },
+                    "i8" => quote! { Some(::tsz_compress::prelude::halfvec::HalfVec::new(prealloc_rows)) },
+                    "i16" => quote! { Some(::tsz_compress::prelude::halfvec::HalfVec::new(prealloc_rows)) },
+                    "i32" => quote! { Some(::tsz_compress::prelude::halfvec::HalfVec::new(prealloc_rows)) },
+                    "i64" => quote! { Some(::tsz_compress::prelude::halfvec::HalfVec::new(prealloc_rows)) },
+                    "u8" => quote! { Some(::tsz_compress::prelude::halfvec::HalfVec::new(prealloc_rows)) },
+                    "u16" => quote! { Some(::tsz_compress::prelude::halfvec::HalfVec::new(prealloc_rows)) },
+                    "u32" => quote! { Some(::tsz_compress::prelude::halfvec::HalfVec::new(prealloc_rows)) },
+                    "u64" => quote! { Some(::tsz_compress::prelude::halfvec::HalfVec::new(prealloc_rows)) },
+                    "i128" => quote! { None },
                     _ => panic!("Unsupported type"),
                 }
             }
             _ => panic!("Unsupported type"),
         })
         .collect::<Vec<_>>();
-    let write_second = col_tys
+    let col_delta_delta_buf = col_bits_tys
         .iter()
         .map(|ty| match ty {
             syn::Type::Path(syn::TypePath { path, .. }) => {
                 let segment = path.segments.first().unwrap();
                 let ident = segment.ident.clone();
                 match ident.to_string().as_str() {
-                    "i8" => quote! { write_i16_bits },
-                    "i16" => quote! { write_i32_bits },
-                    "i32" => quote! { write_i64_bits },
-                    "i64" => quote! { write_i128_bits },
-                    "i128" => quote! { write_i128_bits },
+                    "i8" => quote! { Some(::tsz_compress::prelude::halfvec::HalfVec::new(prealloc_rows)) },
+                    "i16" => quote! { Some(::tsz_compress::prelude::halfvec::HalfVec::new(prealloc_rows)) },
+                    "i32" => quote! { Some(::tsz_compress::prelude::halfvec::HalfVec::new(prealloc_rows)) },
+                    "i64" => quote! { Some(::tsz_compress::prelude::halfvec::HalfVec::new(prealloc_rows)) },
+                    "u8" => quote! { Some(::tsz_compress::prelude::halfvec::HalfVec::new(prealloc_rows)) },
+                    "u16" => quote! { Some(::tsz_compress::prelude::halfvec::HalfVec::new(prealloc_rows)) },
+                    "u32" => quote! { Some(::tsz_compress::prelude::halfvec::HalfVec::new(prealloc_rows)) },
+                    "u64" => quote! { Some(::tsz_compress::prelude::halfvec::HalfVec::new(prealloc_rows)) },
+                    "i128" => quote! { Some(::tsz_compress::prelude::halfvec::HalfVec::new(prealloc_rows)) },
                     _ => panic!("Unsupported type"),
                 }
             }
             _ => panic!("Unsupported type"),
         })
         .collect::<Vec<_>>();
+
+    // Floating point columns only use the first buffer
+    let col_delta_delta_buf = col_delta_delta_buf
+        .into_iter()
+        .zip(&col_float)
+        .map(|(buf, float)| {
+            if *float {
+                quote! { None }
+            } else {
+                buf
+            }
+        })
+        .collect::<Vec<_>>();
+
+    // The first value and first delta are written as signed VLQs of the two's complement bits
+    let first_tys = col_bits_tys
+        .iter()
+        .map(|ty| match quote! { #ty }.to_string().as_str() {
+            "i128" => quote! { i128 },
+            _ => signed_type(ty),
+        })
+        .collect::<Vec<_>>();
     let prev_col_idents = col_idents
         .iter()
         .map(|ident| format_ident!("prev_{}", ident))
@@ -723,37 +1614,13 @@ pub fn derive_compressv2(tokens: TokenStream) -> TokenStream {
         .collect_vec();
 
     // Do delta compression
-    let delta_comp_block = izip!(col_tys.iter(), col_delta_buf_idents.iter(), col_delta_comp_queue_idents.iter())
+    let delta_comp_block = izip!(col_bits_tys.iter(), col_delta_buf_idents.iter(), col_delta_comp_queue_idents.iter())
         .map(|(ty, col_delta_buf_idents,  col_delta_comp_queue_idents)|  match ty {
             syn::Type::Path(syn::TypePath { path, .. }) => {
                 let segment = path.segments.first().unwrap();
                 let ident = segment.ident.clone();
                 match ident.to_string().as_str() {
-                    "i8" => quote! {
-                        debug_assert!(self.#col_delta_buf_idents.is_some());
-                        let outbuf = unsafe { self.#col_delta_buf_idents.as_mut().unwrap_unchecked() };
-                        self.#col_delta_comp_queue_idents.push(delta);
-                        if self.#col_delta_comp_queue_idents.is_full() {
-                            self.#col_delta_comp_queue_idents.emit_delta_bits(outbuf);
-                        }
-                    },
-                    "i16" => quote! {
-                        debug_assert!(self.#col_delta_buf_idents.is_some());
-                        let outbuf = unsafe { self.#col_delta_buf_idents.as_mut().unwrap_unchecked() };
-                        self.#col_delta_comp_queue_idents.push(delta);
-                        if self.#col_delta_comp_queue_idents.is_full() {
-                            self.#col_delta_comp_queue_idents.emit_delta_bits(outbuf);
-                        }
-                    },
-                    "i32" => quote! {
-                        debug_assert!(self.#col_delta_buf_idents.is_some());
-                        let outbuf = unsafe { self.#col_delta_buf_idents.as_mut().unwrap_unchecked() };
-                        self.#col_delta_comp_queue_idents.push(delta);
-                        if self.#col_delta_comp_queue_idents.is_full() {
-                            self.#col_delta_comp_queue_idents.emit_delta_bits(outbuf);
-                        }
-                    },
-                    "i64" => quote! {
+                    "i8" | "i16" | "i32" | "i64" | "u8" | "u16" | "u32" | "u64" => quote! {
                         debug_assert!(self.#col_delta_buf_idents.is_some());
                         let outbuf = unsafe { self.#col_delta_buf_idents.as_mut().unwrap_unchecked() };
                         self.#col_delta_comp_queue_idents.push(delta);
@@ -769,26 +1636,52 @@ pub fn derive_compressv2(tokens: TokenStream) -> TokenStream {
         })
         .collect::<Vec<_>>();
 
+    // The delta-delta is computed in double the bit-width of the column, wrapping at 64 bits
+    let delta_delta_col_tys = col_bits_tys
+        .iter()
+        .map(|ty| match ty {
+            syn::Type::Path(syn::TypePath { path, .. }) => {
+                let segment = path.segments.first().unwrap();
+                let ident = segment.ident.clone();
+                match ident.to_string().as_str() {
+                    "i8" => quote! { i16 },
+                    "i16" => quote! { i32 },
+                    "i32" => quote! { i64 },
+                    "i64" => quote! { i64 },
+                    "i128" => quote! { i128 },
+                    "u8" => quote! { i16 },
+                    "u16" => quote! { i32 },
+                    "u32" => quote! { i64 },
+                    "u64" => quote! { i64 },
+                    _ => panic!("Unsupported type"),
+                }
+            }
+            _ => panic!("Unsupported type"),
+        })
+        .collect::<Vec<_>>();
+
     // Do delta-delta compression
-    let delta_delta_comp_block = izip!(col_tys.iter(), col_delta_delta_buf_idents.iter(), prev_delta_idents.iter(), col_delta_delta_comp_queue_idents.iter())
-        .map(|(ty, col_delta_delta_buf_idents, prev_delta_idents, col_delta_delta_comp_queue_idents)|  match ty {
+    let delta_delta_comp_block = izip!(col_bits_tys.iter(), col_delta_delta_buf_idents.iter(), prev_delta_idents.iter(), col_delta_delta_comp_queue_idents.iter(), delta_delta_col_tys.iter())
+        .map(|(ty, col_delta_delta_buf_idents, prev_delta_idents, col_delta_delta_comp_queue_idents, delta_delta_col_tys)|  match ty {
             syn::Type::Path(syn::TypePath { path, .. }) => {
                 let segment = path.segments.first().unwrap();
                 let ident = segment.ident.clone();
                 match ident.to_string().as_str() {
-                    "i8" => quote! { },
-                    "i16" => quote! { },
-                    "i32" => quote! { },
-                    "i64" => quote! { },
-                    "i128" => quote! {
+                    "i8" | "i16" | "i32" | "i64" | "u8" | "u16" | "u32" | "u64" => quote! {
                         debug_assert!(self.#col_delta_delta_buf_idents.is_some());
                         let outbuf = unsafe { self.#col_delta_delta_buf_idents.as_mut().unwrap_unchecked() };
-                        let delta_delta = delta - self.#prev_delta_idents;
+                        let delta_delta = (delta as #delta_delta_col_tys).wrapping_sub(self.#prev_delta_idents as #delta_delta_col_tys);
                         self.#col_delta_delta_comp_queue_idents.push(delta_delta);
                         if self.#col_delta_delta_comp_queue_idents.is_full() {
                             self.#col_delta_delta_comp_queue_idents.emit_delta_delta_bits(outbuf);
                         }
                     },
+                    "i128" => quote! {
+                        debug_assert!(self.#col_delta_delta_buf_idents.is_some());
+                        let outbuf = unsafe { self.#col_delta_delta_buf_idents.as_mut().unwrap_unchecked() };
+                        let delta_delta = delta.wrapping_sub(self.#prev_delta_idents);
+                        self.#col_delta_delta_comp_queue_idents.emit_wide_delta_delta_bits(delta_delta, outbuf);
+                    },
                     _ => panic!("Unsupported type"),
                 }
             }
@@ -796,6 +1689,873 @@ pub fn derive_compressv2(tokens: TokenStream) -> TokenStream {
         })
         .collect::<Vec<_>>();
 
+    let col_indices = (0..num_columns).map(syn::Index::from).collect_vec();
+
+    // Lossy columns track the reconstructed value in double the bit-width of the column
+    let reconstructed_idents = col_idents
+        .iter()
+        .zip(&col_attrs)
+        .filter(|(_, attrs)| attrs.max_error.is_some())
+        .map(|(ident, _)| format_ident!("{}_reconstructed", ident))
+        .collect_vec();
+    let reconstructed_tys = (0..num_columns)
+        .filter(|i| col_attrs[*i].max_error.is_some())
+        .map(|i| {
+            let bits_ty = &col_bits_tys[i];
+            if col_float[i] || quote! { #bits_ty }.to_string() == "i128" {
+                panic!("A max_error requires an integer column of at most 64 bits");
+            }
+            double_col_tys[i].clone()
+        })
+        .collect_vec();
+
+    // Option columns encode a validity stream and only the present values
+    let validity_idents = col_idents
+        .iter()
+        .zip(&col_nullable)
+        .filter(|(_, nullable)| **nullable)
+        .map(|(ident, _)| format_ident!("{}_validity", ident))
+        .collect_vec();
+    let presence_nibbles = validity_idents.len().div_ceil(4);
+
+    // Timestamp columns encode the grid once and the residuals of the timestamps against it
+    let grid_idents = col_idents
+        .iter()
+        .zip(&col_periods)
+        .filter(|(_, period)| period.is_some())
+        .map(|(ident, _)| format_ident!("{}_grid", ident))
+        .collect_vec();
+    let dictionary_idents = col_idents
+        .iter()
+        .zip(&col_dictionary)
+        .filter(|(_, dictionary)| **dictionary)
+        .map(|(ident, _)| format_ident!("{}_dictionary", ident))
+        .collect_vec();
+    let dictionary_bits = col_tys
+        .iter()
+        .zip(&col_dictionary)
+        .filter(|(_, dictionary)| **dictionary)
+        .map(|(ty, _)| quote! { core::mem::size_of::<#ty>() * 8 })
+        .collect_vec();
+
+    // Integer columns bit-pack their values against the frame minimum when that is shorter than the deltas
+    let col_reference = (0..num_columns)
+        .map(|i| {
+            let bits_ty = &col_bits_tys[i];
+            let bits_ty = quote! { #bits_ty }.to_string();
+            !col_float[i] && !col_dictionary[i] && bits_ty != "i128" && bits_ty != "u128"
+        })
+        .collect_vec();
+    let reference_idents = (0..num_columns)
+        .filter(|i| col_reference[*i])
+        .map(|i| format_ident!("{}_reference", col_idents[i]))
+        .collect_vec();
+    let reference_news = (0..num_columns)
+        .filter(|i| col_reference[*i])
+        .map(|i| {
+            let col_ty = &col_tys[i];
+            let start_of_column = !col_nullable[i] && col_periods[i].is_none();
+            quote! { ::tsz_compress::prelude::ReferenceColumn::new(core::mem::size_of::<#col_ty>() * 8, #start_of_column) }
+        })
+        .collect_vec();
+    let reference_flushes = (0..num_columns)
+        .filter(|i| col_reference[*i])
+        .map(|i| {
+            let col_index = &col_indices[i];
+            let col_ty = &col_tys[i];
+            let col_delta_buf_ident = &col_delta_buf_idents[i];
+            let col_delta_delta_buf_ident = &col_delta_delta_buf_idents[i];
+            let reference_ident = format_ident!("{}_reference", col_idents[i]);
+            quote! {
+                let outbuf = if use_delta_delta[#col_index] {
+                    self.#col_delta_delta_buf_ident.as_ref()
+                } else {
+                    self.#col_delta_buf_ident.as_ref()
+                };
+                if let Some(outbuf) = outbuf {
+                    self.#reference_ident.flush::<#col_ty>(outbuf);
+                }
+            }
+        })
+        .collect_vec();
+    let grid_news = col_tys
+        .iter()
+        .zip(&col_periods)
+        .filter_map(|(ty, period)| {
+            let period = proc_macro2::Literal::u64_unsuffixed((*period)?);
+            Some(quote! { ::tsz_compress::prelude::TimestampGrid::new(core::mem::size_of::<#ty>() * 8, #period) })
+        })
+        .collect_vec();
+    let write_presence = if validity_idents.is_empty() {
+        quote! {}
+    } else {
+        quote! {
+            // Write whether each Option column has any values
+            write_presence(&mut rows, &[ #( self.#validity_idents.present_count() > 0, )* ]);
+        }
+    };
+
+    // Compress each column, counting only the present values of Option columns
+    let col_compress_blocks = (0..num_columns)
+        .map(|i| {
+            let col_ident = &col_idents[i];
+            let col_delta_buf_ident = &col_delta_buf_idents[i];
+            let col_delta_delta_buf_ident = &col_delta_delta_buf_idents[i];
+            let delta_col_ty = &delta_col_tys[i];
+            let double_col_ty = &double_col_tys[i];
+            let prev_col_ident = &prev_col_idents[i];
+            let prev_double_col_ident = &prev_double_col_idents[i];
+            let prev_delta_ident = &prev_delta_idents[i];
+            let delta_comp_block = &delta_comp_block[i];
+            let delta_delta_comp_block = &delta_delta_comp_block[i];
+            let first_ty = &first_tys[i];
+
+            // Scaled float fields are quantized to the integer column, and enum fields take their discriminant
+            let quantize = match (&col_scaling[i], &col_enums[i]) {
+                (Some((quantize, _)), _) => quote! { let value = #quantize; },
+                (_, Some(_)) => {
+                    let col_ty = &col_tys[i];
+                    quote! { let value: #col_ty = ::tsz_compress::prelude::ColumnEnum::discriminant(value); }
+                }
+                _ => quote! {},
+            };
+
+            // Lossy columns move the reconstructed value as little as keeps it within the bound of the input,
+            // so the error never accumulates across deltas
+            let deadband = match col_max_errors[i] {
+                Some(max_error) => {
+                    let col_ty = &col_tys[i];
+                    let reconstructed_ident = format_ident!("{}_reconstructed", col_ident);
+                    let max_error = proc_macro2::Literal::u64_unsuffixed(max_error);
+                    quote! {
+                        let value = if rows == 1 {
+                            value
+                        } else {
+                            let error = value as #double_col_ty - self.#reconstructed_ident;
+                            (self.#reconstructed_ident + error - error.clamp(-#max_error, #max_error)) as #col_ty
+                        };
+                        self.#reconstructed_ident = value as #double_col_ty;
+                    }
+                }
+                None => quote! {},
+            };
+
+            // The start of an Option column is written with its validity stream, and of a timestamp column with its grid
+            let start_of_column = if col_nullable[i] || col_periods[i].is_some() {
+                quote! {}
+            } else {
+                quote! {
+                    outbuf.push(::tsz_compress::prelude::halfvec::HalfWord::Half(::tsz_compress::prelude::consts::headers::START_OF_COLUMN));
+                }
+            };
+
+            let compress_value = if col_float[i] {
+                let xor_encoder_ident = format_ident!("{}_xor_encoder", col_ident);
+                quote! {
+                    debug_assert!(self.#col_delta_buf_ident.is_some());
+                    let outbuf = unsafe { self.#col_delta_buf_ident.as_mut().unwrap_unchecked() };
+                    if rows == 1 {
+                        // Write out the full bits of the first value
+                        #start_of_column
+                        self.#xor_encoder_ident.emit_first_bits(value.to_bits() as u64, outbuf);
+                    } else {
+                        // XOR the bits with the previous value
+                        self.#xor_encoder_ident.emit_bits(value.to_bits() as u64, outbuf);
+                    }
+                }
+            } else if col_transitions[i] {
+                let bits_ty = &col_bits_tys[i];
+                let signed_ty = signed_type(bits_ty);
+                let delta_delta_col_ty = &delta_delta_col_tys[i];
+                let col_delta_comp_queue_ident = &col_delta_comp_queue_idents[i];
+                let col_delta_delta_comp_queue_ident = &col_delta_delta_comp_queue_idents[i];
+                quote! {
+                    debug_assert!(self.#col_delta_buf_ident.is_some());
+                    debug_assert!(self.#col_delta_delta_buf_ident.is_some());
+                    let outbuf = unsafe { self.#col_delta_buf_ident.as_mut().unwrap_unchecked() };
+                    let dd_outbuf = unsafe { self.#col_delta_delta_buf_ident.as_mut().unwrap_unchecked() };
+                    let bits = value as #bits_ty;
+                    if rows == 1 {
+                        // Write out the first value as a signed VLQ
+                        #start_of_column
+                        outbuf.push(::tsz_compress::prelude::halfvec::HalfWord::Half(::tsz_compress::prelude::consts::headers::TRANSITIONS_FIRST_ROW));
+                        ::tsz_compress::prelude::write_signed_vlq(outbuf, bits as #first_ty as i128);
+                        let outbuf = dd_outbuf;
+                        #start_of_column
+                        outbuf.push(::tsz_compress::prelude::halfvec::HalfWord::Half(::tsz_compress::prelude::consts::headers::DELTA_DELTA_TRANSITIONS_FIRST_ROW));
+                        ::tsz_compress::prelude::write_signed_vlq(outbuf, bits as #first_ty as i128);
+                    } else {
+                        // The bits toggled since the previous value take the place of the delta, and of the delta-delta
+                        let transitions = (bits ^ self.#prev_col_ident as #bits_ty) as #signed_ty;
+                        if rows == 2 {
+                            outbuf.push(::tsz_compress::prelude::halfvec::HalfWord::Half(::tsz_compress::prelude::consts::headers::SECOND_ROW));
+                            ::tsz_compress::prelude::write_signed_vlq(outbuf, transitions as #double_col_ty as i128);
+                            dd_outbuf.push(::tsz_compress::prelude::halfvec::HalfWord::Half(::tsz_compress::prelude::consts::headers::SECOND_ROW));
+                            ::tsz_compress::prelude::write_signed_vlq(dd_outbuf, transitions as #double_col_ty as i128);
+                        } else {
+                            self.#col_delta_comp_queue_ident.push(transitions);
+                            if self.#col_delta_comp_queue_ident.is_full() {
+                                self.#col_delta_comp_queue_ident.emit_delta_bits(outbuf);
+                            }
+                            self.#col_delta_delta_comp_queue_ident.push(transitions as #delta_delta_col_ty);
+                            if self.#col_delta_delta_comp_queue_ident.is_full() {
+                                self.#col_delta_delta_comp_queue_ident.emit_delta_delta_bits(dd_outbuf);
+                            }
+                        }
+                    }
+                    self.#prev_col_ident = bits as #delta_col_ty;
+                }
+            } else {
+                // A checkpoint of the seek index resumes from the last value in full, as a narrower delta may wrap it
+                let track_value = if struct_attrs.seek.is_some() {
+                    quote! { self.#prev_double_col_ident = value as #double_col_ty; }
+                } else {
+                    quote! {}
+                };
+                quote! {
+                if rows > 2 {
+                    // The new delta  and delta-delta
+                    let col = value as #delta_col_ty;
+                    let delta = col.wrapping_sub(self.#prev_col_ident);
+
+                    // Do delta compression if configured
+                    #delta_comp_block
+
+                    // Do delta-delta compression if configured
+                    #delta_delta_comp_block
+
+                    // Update the previous values
+                    self.#prev_col_ident = col;
+                    self.#prev_delta_ident = delta;
+                } else if rows == 1 {
+                    // Write out the first value as a signed VLQ
+                    if let Some(outbuf) = self.#col_delta_buf_ident.as_mut() {
+                        #start_of_column
+                        outbuf.push(::tsz_compress::prelude::halfvec::HalfWord::Half(::tsz_compress::prelude::consts::headers::FIRST_ROW));
+                        ::tsz_compress::prelude::write_signed_vlq(outbuf, value as #first_ty as i128);
+                    }
+                    if let Some(outbuf) = self.#col_delta_delta_buf_ident.as_mut() {
+                        #start_of_column
+                        outbuf.push(::tsz_compress::prelude::halfvec::HalfWord::Half(::tsz_compress::prelude::consts::headers::DELTA_DELTA_FIRST_ROW));
+                        ::tsz_compress::prelude::write_signed_vlq(outbuf, value as #first_ty as i128);
+                    }
+                    self.#prev_double_col_ident = value as #double_col_ty;
+                } else if rows == 2 {
+                    // Write out the first delta as a signed VLQ, regardless of chosen delta bit-width.
+                    // Up cast to double bit-width always for the first delta
+                    let col = value as #double_col_ty;
+                    let delta = col.wrapping_sub(self.#prev_double_col_ident);
+                    if let Some(outbuf) = self.#col_delta_buf_ident.as_mut() {
+                        outbuf.push(::tsz_compress::prelude::halfvec::HalfWord::Half(::tsz_compress::prelude::consts::headers::SECOND_ROW));
+                        ::tsz_compress::prelude::write_signed_vlq(outbuf, delta as i128);
+                    }
+                    if let Some(outbuf) = self.#col_delta_delta_buf_ident.as_mut() {
+                        outbuf.push(::tsz_compress::prelude::halfvec::HalfWord::Half(::tsz_compress::prelude::consts::headers::SECOND_ROW));
+                        ::tsz_compress::prelude::write_signed_vlq(outbuf, delta as i128);
+                    }
+
+                    // Use choice of bit-width for delta/delta-delta compression
+                    self.#prev_delta_ident = delta as #delta_col_ty;
+                    self.#prev_col_ident = col as #delta_col_ty;
+                }
+                #track_value
+                }
+            };
+
+            let compress_value = if col_reference[i] {
+                let reference_ident = format_ident!("{}_reference", col_ident);
+                quote! {
+                    self.#reference_ident.push(value as i128);
+                    #compress_value
+                }
+            } else {
+                compress_value
+            };
+
+            if col_nullable[i] {
+                let validity_ident = format_ident!("{}_validity", col_ident);
+                quote! {
+                    // Deltas are only taken across the present values
+                    self.#validity_ident.push(row.#col_ident.is_some());
+                    if let Some(value) = row.#col_ident {
+                        #quantize
+                        let rows = self.#validity_ident.present_count();
+                        #deadband
+                        #compress_value
+                    }
+                }
+            } else if col_periods[i].is_some() {
+                let grid_ident = format_ident!("{}_grid", col_ident);
+                let col_ty = &col_tys[i];
+                quote! {
+                    // The residuals against the grid follow the first timestamp
+                    if let Some(residual) = self.#grid_ident.push(row.#col_ident as i128) {
+                        let value = residual as #col_ty;
+                        let rows = self.rows - 1;
+                        #deadband
+                        #compress_value
+                    }
+                }
+            } else if col_dictionary[i] {
+                let dictionary_ident = format_ident!("{}_dictionary", col_ident);
+                quote! {
+                    {
+                        let value = row.#col_ident;
+                        let rows = self.rows;
+                        self.#dictionary_ident.push(value as i128);
+                        #compress_value
+                    }
+                }
+            } else {
+                quote! {
+                    {
+                        let value = row.#col_ident;
+                        #quantize
+                        let rows = self.rows;
+                        #deadband
+                        #compress_value
+                    }
+                }
+            }
+        })
+        .collect_vec();
+
+    // Guarantee that at least the column start nibble is emitted for columns that are always in the frame
+    let col_start_blocks = (0..num_columns)
+        .filter(|i| !col_nullable[*i] && col_periods[*i].is_none())
+        .map(|i| {
+            let col_delta_buf_ident = &col_delta_buf_idents[i];
+            let col_delta_delta_buf_ident = &col_delta_delta_buf_idents[i];
+            quote! {
+                if let Some(outbuf) = self.#col_delta_buf_ident.as_mut() {
+                    if outbuf.is_empty() {
+                        outbuf.push(::tsz_compress::prelude::halfvec::HalfWord::Half(::tsz_compress::prelude::consts::headers::START_OF_COLUMN));
+                    }
+                }
+                if let Some(outbuf) = self.#col_delta_delta_buf_ident.as_mut() {
+                    if outbuf.is_empty() {
+                        outbuf.push(::tsz_compress::prelude::halfvec::HalfWord::Half(::tsz_compress::prelude::consts::headers::START_OF_COLUMN));
+                    }
+                }
+            }
+        })
+        .collect_vec();
+
+    // The words of each column in the frame, omitting Option columns without values
+    let col_words = (0..num_columns)
+        .map(|i| {
+            let col_index = &col_indices[i];
+            let col_delta_buf_ident = &col_delta_buf_idents[i];
+            let col_delta_delta_buf_ident = &col_delta_delta_buf_idents[i];
+            let outbuf = quote! {
+                if use_delta_delta[#col_index] {
+                    self.#col_delta_delta_buf_ident.as_ref()
+                } else {
+                    self.#col_delta_buf_ident.as_ref()
+                }
+            };
+            let outbuf = if col_reference[i] {
+                // The packed values replace the deltas when they are shorter
+                let reference_ident = format_ident!("{}_reference", col_idents[i]);
+                quote! {
+                    {
+                        let outbuf = #outbuf;
+                        if self.#reference_ident.is_shorter(outbuf.map_or(0, |outbuf| outbuf.len())) {
+                            Some(self.#reference_ident.buffer())
+                        } else {
+                            outbuf
+                        }
+                    }
+                }
+            } else {
+                outbuf
+            };
+            if col_nullable[i] {
+                let validity_ident = format_ident!("{}_validity", col_idents[i]);
+                quote! {
+                    Some(self.#validity_ident.buffer()).filter(|_| self.#validity_ident.present_count() > 0).into_iter(),
+                    if self.#validity_ident.present_count() == 0 {
+                        None
+                    } else {
+                        #outbuf
+                    }.into_iter(),
+                }
+            } else if col_periods[i].is_some() {
+                let grid_ident = format_ident!("{}_grid", col_idents[i]);
+                quote! {
+                    Some(self.#grid_ident.buffer()).into_iter(),
+                    if self.#grid_ident.is_exact() {
+                        None
+                    } else {
+                        #outbuf
+                    }.into_iter(),
+                }
+            } else if col_dictionary[i] {
+                let dictionary_ident = format_ident!("{}_dictionary", col_idents[i]);
+                quote! {
+                    {
+                        let outbuf = #outbuf;
+                        if self.#dictionary_ident.is_shorter(outbuf.map_or(0, |outbuf| outbuf.len())) {
+                            Some(self.#dictionary_ident.buffer())
+                        } else {
+                            outbuf
+                        }.into_iter()
+                    },
+                }
+            } else {
+                quote! {
+                    #outbuf.into_iter(),
+                }
+            }
+        })
+        .collect_vec();
+
+    // The exact number of nibbles of each column once flushed
+    let col_finished_nibbles = (0..num_columns)
+        .map(|i| {
+            let col_delta_buf_ident = &col_delta_buf_idents[i];
+            let col_delta_delta_buf_ident = &col_delta_delta_buf_idents[i];
+            let col_delta_comp_queue_ident = &col_delta_comp_queue_idents[i];
+            let col_delta_delta_comp_queue_ident = &col_delta_delta_comp_queue_idents[i];
+            let choose_nibbles = quote! {
+                match (delta_nibbles, delta_delta_nibbles) {
+                    (Some(delta_nibbles), Some(delta_delta_nibbles)) => delta_nibbles.min(delta_delta_nibbles),
+                    (Some(nibbles), None) | (None, Some(nibbles)) => nibbles,
+                    (None, None) => 0,
+                }
+            };
+            let choose_nibbles = if col_reference[i] {
+                // The packed values replace the deltas when they are shorter
+                let reference_ident = format_ident!("{}_reference", col_idents[i]);
+                quote! {
+                    {
+                        let column_nibbles = #choose_nibbles;
+                        self.#reference_ident.finished_nibbles().filter(|_| self.#reference_ident.is_shorter(column_nibbles)).unwrap_or(column_nibbles)
+                    }
+                }
+            } else {
+                choose_nibbles
+            };
+            if col_nullable[i] {
+                let validity_ident = format_ident!("{}_validity", col_idents[i]);
+                quote! {
+                    // Option columns without values are omitted
+                    if self.#validity_ident.present_count() > 0 {
+                        let delta_nibbles = self.#col_delta_buf_ident.as_ref().map(|outbuf| outbuf.len() + self.#col_delta_comp_queue_ident.flushed_delta_nibbles());
+                        let delta_delta_nibbles = self.#col_delta_delta_buf_ident.as_ref().map(|outbuf| outbuf.len() + self.#col_delta_delta_comp_queue_ident.flushed_delta_delta_nibbles());
+                        nibbles += self.#validity_ident.finished_nibbles() + #choose_nibbles;
+                    }
+                }
+            } else if col_periods[i].is_some() {
+                let grid_ident = format_ident!("{}_grid", col_idents[i]);
+                quote! {
+                    // The residuals are omitted when every timestamp is on the grid
+                    nibbles += self.#grid_ident.finished_nibbles();
+                    if !self.#grid_ident.is_exact() {
+                        let delta_nibbles = self.#col_delta_buf_ident.as_ref().map(|outbuf| outbuf.len() + self.#col_delta_comp_queue_ident.flushed_delta_nibbles());
+                        let delta_delta_nibbles = self.#col_delta_delta_buf_ident.as_ref().map(|outbuf| outbuf.len() + self.#col_delta_delta_comp_queue_ident.flushed_delta_delta_nibbles());
+                        nibbles += #choose_nibbles;
+                    }
+                }
+            } else if col_dictionary[i] {
+                let dictionary_ident = format_ident!("{}_dictionary", col_idents[i]);
+                quote! {
+                    // The dictionary replaces the deltas when it is shorter
+                    let delta_nibbles = self.#col_delta_buf_ident.as_ref().map(|outbuf| outbuf.len().max(1) + self.#col_delta_comp_queue_ident.flushed_delta_nibbles());
+                    let delta_delta_nibbles = self.#col_delta_delta_buf_ident.as_ref().map(|outbuf| outbuf.len().max(1) + self.#col_delta_delta_comp_queue_ident.flushed_delta_delta_nibbles());
+                    let column_nibbles = #choose_nibbles;
+                    nibbles += self.#dictionary_ident.finished_nibbles().filter(|_| self.#dictionary_ident.is_shorter(column_nibbles)).unwrap_or(column_nibbles);
+                }
+            } else {
+                quote! {
+                    // At least the column start nibble is emitted for each column
+                    let delta_nibbles = self.#col_delta_buf_ident.as_ref().map(|outbuf| outbuf.len().max(1) + self.#col_delta_comp_queue_ident.flushed_delta_nibbles());
+                    let delta_delta_nibbles = self.#col_delta_delta_buf_ident.as_ref().map(|outbuf| outbuf.len().max(1) + self.#col_delta_delta_comp_queue_ident.flushed_delta_delta_nibbles());
+                    nibbles += #choose_nibbles;
+                }
+            }
+        })
+        .collect_vec();
+
+    // Optionally prefix the columns with the length of each column
+    let (col_finished_nibbles, write_column_table) = if struct_attrs.column_table {
+        let col_finished_nibbles = col_finished_nibbles
+            .into_iter()
+            .map(|finished_nibbles| {
+                quote! {
+                    {
+                        let column_start = nibbles;
+                        #finished_nibbles
+                        nibbles += ::tsz_compress::prelude::column_table_nibbles(nibbles - column_start);
+                    }
+                }
+            })
+            .collect_vec();
+        let write_column_table = quote! {
+            // Write the length of each column, so that a decoder may jump to any column
+            let column_lens = [ #( [ #col_words ].into_iter().flatten().map(|words| words.len()).sum::<usize>(), )* ];
+            ::tsz_compress::prelude::write_column_table(&mut rows, &column_lens);
+        };
+        (col_finished_nibbles, write_column_table)
+    } else {
+        (col_finished_nibbles, quote! {})
+    };
+
+    // Describe the columns in an optional frame header
+    let col_type_codes = column_type_codes(&col_tys, &col_nullable, &col_attrs);
+    let schema_hash = schema_hash(&col_idents, &field_tys, &col_attrs, &struct_attrs);
+    if struct_attrs.header && num_columns > u8::MAX as usize {
+        panic!("The frame header supports at most 255 columns");
+    }
+    let header_len = if struct_attrs.header {
+        quote! { ::tsz_compress::prelude::consts::frame::PREFIX_LEN + #num_columns + ::tsz_compress::prelude::consts::frame::SCHEMA_HASH_LEN }
+    } else {
+        quote! { 0 }
+    };
+    let checksum_len = checksum_len(&struct_attrs.checksum);
+    let write_checksum = write_checksum(&struct_attrs.checksum);
+    let (lz_len, lz_start, write_lz) = lz_tokens(struct_attrs.lz);
+    let checksum_start = if struct_attrs.checksum.is_some() {
+        quote! {
+            // The checksum covers the bytes of this frame
+            let start = output_bytes.len();
+        }
+    } else {
+        quote! {}
+    };
+
+    // Optionally generate a C interface to the compressor
+    let (ffi_fns, c_header) = match struct_attrs.ffi.as_ref() {
+        Some(prefix) => {
+            let c_types = column_c_types(&field_tys, &col_attrs);
+            let ffi_tys = column_ffi_types(&field_tys, &col_tys, &col_enums);
+
+            // Enum fields are passed as their discriminant, rejecting discriminants of no variant
+            let ffi_fields = col_idents
+                .iter()
+                .zip(&col_enums)
+                .filter_map(|(col_ident, enum_ty)| {
+                    let enum_ty = enum_ty.as_ref()?;
+                    Some(quote! {
+                        let Some(#col_ident) = <#enum_ty as ::tsz_compress::prelude::ColumnEnum>::from_discriminant(#col_ident) else {
+                            return ::tsz_compress::prelude::CodingError::InvalidDiscriminant.code();
+                        };
+                    })
+                })
+                .collect_vec();
+            let new_fn = format_ident!("{}_compressor_new", prefix);
+            let compress_fn = format_ident!("{}_compress", prefix);
+            let finished_len_fn = format_ident!("{}_finished_len", prefix);
+            let finish_fn = format_ident!("{}_finish", prefix);
+            let free_fn = format_ident!("{}_compressor_free", prefix);
+            let c_ident = format!("{}Compressor", ident);
+            let guard = format!("{}_COMPRESS_H", prefix.to_uppercase());
+            let c_params = col_idents
+                .iter()
+                .zip(&c_types)
+                .map(|(col, ty)| format!(", {} {}", ty, col))
+                .collect::<String>();
+            let c_header = format!(
+                "#ifndef {guard}\n\
+                 #define {guard}\n\
+                 \n\
+                 #include <stdbool.h>\n\
+                 #include <stddef.h>\n\
+                 #include <stdint.h>\n\
+                 \n\
+                 {C_ERROR_CODES}\
+                 \n\
+                 typedef struct {c_ident} {c_ident};\n\
+                 \n\
+                 /* Creates a compressor, reserving space for prealloc_rows rows. */\n\
+                 {c_ident} *{new_fn}(size_t prealloc_rows);\n\
+                 \n\
+                 /* Compresses a row. Returns 0 or a negative TSZ_ERROR code, leaving the compressor unchanged. */\n\
+                 int32_t {compress_fn}({c_ident} *compressor{c_params});\n\
+                 \n\
+                 /* The exact number of bytes that finishing the compressor would write. */\n\
+                 size_t {finished_len_fn}(const {c_ident} *compressor);\n\
+                 \n\
+                 /* Writes the compressed frame to out, returning the number of bytes written,\n\
+                 \x20  or -1 without consuming the rows if capacity is less than the finished length. */\n\
+                 ptrdiff_t {finish_fn}({c_ident} *compressor, uint8_t *out, size_t capacity);\n\
+                 \n\
+                 /* Frees a compressor. */\n\
+                 void {free_fn}({c_ident} *compressor);\n\
+                 \n\
+                 #endif /* {guard} */\n"
+            );
+            let ffi_fns = quote! {
+                ///
+                /// Creates a compressor for the C interface, reserving space for `prealloc_rows` rows.
+                ///
+                #[no_mangle]
+                pub extern "C" fn #new_fn(prealloc_rows: usize) -> *mut #compressor_ident {
+                    ::alloc::boxed::Box::into_raw(::alloc::boxed::Box::new(<#compressor_ident as TszCompressV2>::new(prealloc_rows)))
+                }
+
+                ///
+                /// Compresses a row given as one argument per column. Returns 0 or a negative error code,
+                /// leaving the compressor unchanged.
+                ///
+                /// # Safety
+                /// The compressor must be null or created by the C interface and not yet freed.
+                ///
+                #[no_mangle]
+                pub unsafe extern "C" fn #compress_fn(tsz_compressor: *mut #compressor_ident, #( #col_idents: #ffi_tys ),*) -> i32 {
+                    let Some(tsz_compressor) = tsz_compressor.as_mut() else {
+                        return ::tsz_compress::prelude::CodingError::Empty.code();
+                    };
+                    #( #ffi_fields )*
+                    tsz_compressor.compress(#ident { #( #col_idents, )* });
+                    0
+                }
+
+                ///
+                /// The exact number of bytes that finishing the compressor would write.
+                ///
+                /// # Safety
+                /// The compressor must be null or created by the C interface and not yet freed.
+                ///
+                #[no_mangle]
+                pub unsafe extern "C" fn #finished_len_fn(tsz_compressor: *const #compressor_ident) -> usize {
+                    tsz_compressor.as_ref().map_or(0, |tsz_compressor| tsz_compressor.finished_len())
+                }
+
+                ///
+                /// Writes the compressed frame to `out`, returning the number of bytes written,
+                /// or -1 without consuming the rows if `capacity` is less than the finished length.
+                ///
+                /// # Safety
+                /// The compressor must be null or created by the C interface and not yet freed.
+                /// `out` must be null or valid for writes of `capacity` bytes.
+                ///
+                #[no_mangle]
+                pub unsafe extern "C" fn #finish_fn(tsz_compressor: *mut #compressor_ident, out: *mut u8, capacity: usize) -> isize {
+                    let Some(tsz_compressor) = tsz_compressor.as_mut() else {
+                        return -1;
+                    };
+                    let len = tsz_compressor.finished_len();
+                    if out.is_null() || capacity < len {
+                        return -1;
+                    }
+                    let mut bytes = ::alloc::vec::Vec::with_capacity(len);
+                    tsz_compressor.finish_into(&mut bytes);
+                    core::ptr::copy_nonoverlapping(bytes.as_ptr(), out, bytes.len());
+                    bytes.len() as isize
+                }
+
+                ///
+                /// Frees a compressor created by the C interface.
+                ///
+                /// # Safety
+                /// The compressor must be null or created by the C interface and not yet freed.
+                ///
+                #[no_mangle]
+                pub unsafe extern "C" fn #free_fn(tsz_compressor: *mut #compressor_ident) {
+                    if !tsz_compressor.is_null() {
+                        drop(::alloc::boxed::Box::from_raw(tsz_compressor));
+                    }
+                }
+            };
+            let c_header = quote! {
+                pub use private::{#new_fn, #compress_fn, #finished_len_fn, #finish_fn, #free_fn};
+
+                /// The C header declaring the C interface to the compressor.
+                pub const C_HEADER: &str = #c_header;
+            };
+            (ffi_fns, c_header)
+        }
+        None => (quote! {}, quote! {}),
+    };
+    // Optionally index the rows of the frame for seeking, recording the state of each column at the rows of the index
+    let (seek_field, seek_new, seek_clear, seek_row, write_seek, seek_fn, seek_len) = match struct_attrs.seek {
+        Some(every) => {
+            let mut col_checkpoints = Vec::new();
+            let mut col_seek_blocks = Vec::new();
+            let mut col_checkpoint_nibbles = Vec::new();
+            for i in 0..num_columns {
+                let col_ident = &col_idents[i];
+                let col_ty = &col_tys[i];
+                let col_index = &col_indices[i];
+                let col_delta_buf_ident = &col_delta_buf_idents[i];
+                let col_delta_delta_buf_ident = &col_delta_delta_buf_idents[i];
+                let prev_col_ident = &prev_col_idents[i];
+
+                // The last value, and the value before it or the window of the last XOR word
+                let (value, prev) = if col_float[i] {
+                    let xor_encoder_ident = format_ident!("{}_xor_encoder", col_ident);
+                    (
+                        quote! { self.#xor_encoder_ident.prev() as i128 },
+                        quote! { self.#xor_encoder_ident.window() as i128 },
+                    )
+                } else if col_transitions[i] {
+                    (quote! { self.#prev_col_ident as i128 }, quote! { 0 })
+                } else {
+                    let prev_double_col_ident = &prev_double_col_idents[i];
+                    let prev_delta_ident = &prev_delta_idents[i];
+                    let double_col_ty = &double_col_tys[i];
+                    (
+                        quote! { self.#prev_double_col_ident as i128 },
+                        quote! { self.#prev_double_col_ident.wrapping_sub(self.#prev_delta_ident as #double_col_ty) as i128 },
+                    )
+                };
+                let (taken, validity) = if col_nullable[i] {
+                    let validity_ident = format_ident!("{}_validity", col_ident);
+                    (
+                        quote! { self.#validity_ident.present_count() },
+                        quote! {
+                            let present = row.#col_ident.is_some();
+                            let (validity, consumed) = self.#validity_ident.checkpoint(present);
+                        },
+                    )
+                } else if col_periods[i].is_some() && !col_dictionary[i] {
+                    // The first timestamp has no residual
+                    (quote! { self.rows.saturating_sub(1) }, quote! { let (validity, consumed, present) = (0, 0, true); })
+                } else {
+                    (quote! { self.rows }, quote! { let (validity, consumed, present) = (0, 0, true); })
+                };
+                col_checkpoints.push(quote! {
+                    {
+                        #validity
+                        ::tsz_compress::prelude::ColumnCheckpoint {
+                            taken: #taken,
+                            delta: self.#col_delta_buf_ident.as_ref().map_or(0, |outbuf| outbuf.len()),
+                            delta_delta: self.#col_delta_delta_buf_ident.as_ref().map_or(0, |outbuf| outbuf.len()),
+                            value: #value,
+                            prev: #prev,
+                            validity,
+                            consumed,
+                            present,
+                        }
+                    }
+                });
+
+                // The values of a column follow its start of column tag, or the validity runs or grid that carry the tag
+                let write_checkpoint = if col_nullable[i] {
+                    let validity_ident = format_ident!("{}_validity", col_ident);
+                    col_checkpoint_nibbles.push(quote! { widths.nullable::<#col_ty>() });
+                    quote! {
+                        let values = column_start + self.#validity_ident.buffer().len();
+                        let validity = Some(column_start).filter(|_| self.#validity_ident.present_count() > 0);
+                        index.nullable::<#col_ty>(validity, values, values + word, checkpoint);
+                    }
+                } else if col_dictionary[i] {
+                    col_checkpoint_nibbles.push(quote! { widths.prefixed::<#col_ty>() });
+                    quote! {
+                        index.prefixed::<#col_ty>(column_start + 1, column_start + 1, column_start + word, checkpoint);
+                    }
+                } else if col_periods[i].is_some() {
+                    let grid_ident = format_ident!("{}_grid", col_ident);
+                    col_checkpoint_nibbles.push(quote! { widths.prefixed::<#col_ty>() });
+                    quote! {
+                        let residuals = column_start + self.#grid_ident.buffer().len();
+                        index.prefixed::<#col_ty>(column_start + 1, residuals, residuals + word, checkpoint);
+                    }
+                } else {
+                    col_checkpoint_nibbles.push(quote! { widths.column::<#col_ty>() });
+                    quote! {
+                        index.column::<#col_ty>(column_start + 1, column_start + word, checkpoint);
+                    }
+                };
+                col_seek_blocks.push(quote! {
+                    {
+                        let checkpoint = &checkpoint[#col_index];
+                        let column_start = column_starts[#col_index];
+                        let word = if use_delta_delta[#col_index] {
+                            checkpoint.delta_delta
+                        } else {
+                            checkpoint.delta
+                        };
+                        #write_checkpoint
+                    }
+                });
+            }
+            (
+                quote! {
+                    checkpoints: ::alloc::vec::Vec<[::tsz_compress::prelude::ColumnCheckpoint; #num_columns]>,
+                },
+                quote! {
+                    checkpoints: ::alloc::vec::Vec::new(),
+                },
+                quote! {
+                    self.checkpoints.clear();
+                },
+                quote! {
+                    // Start a word of every column at the rows of the seek index
+                    if self.rows.is_multiple_of(#every) {
+                        self.checkpoint(&row);
+                    }
+                },
+                quote! {
+                    // Append the seek index of the row count and columns
+                    let row_nibbles = rows.as_ref().map_or(0, |rows| rows.len());
+                    output_bytes.extend(self.seek_index(use_delta_delta, row_nibbles));
+                },
+                quote! {
+                    impl #compressor_ident {
+                        ///
+                        /// Flushes the pending samples of every column, so that the next row starts a word,
+                        /// and records the state of each column before the row.
+                        ///
+                        fn checkpoint(&mut self, row: &#ident) {
+                            self.flush_queues();
+                            let checkpoint = [ #( #col_checkpoints, )* ];
+                            self.checkpoints.push(checkpoint);
+                        }
+
+                        ///
+                        /// Writes the seek index of the checkpoints of a frame, with the columns following
+                        /// the `row_nibbles` nibbles of the row count, checkpointing every #every rows.
+                        ///
+                        fn seek_index(&self, use_delta_delta: [bool; #num_columns], row_nibbles: usize) -> ::alloc::vec::Vec<u8> {
+                            // Each column follows the words of the columns before it, counting from the end of the row count
+                            let column_lens = [ #( [ #col_words ].into_iter().flatten().map(|words| words.len()).sum::<usize>(), )* ];
+                            let mut column_starts = [row_nibbles - 8; #num_columns];
+                            for i in 1..#num_columns {
+                                column_starts[i] = column_starts[i - 1] + column_lens[i - 1];
+                            }
+
+                            // The offsets and counts are as wide as the row count and columns need
+                            let body_len = (row_nibbles + column_lens.iter().sum::<usize>()).div_ceil(2);
+                            let widths = ::tsz_compress::prelude::CheckpointWidths::new(body_len, self.rows as u32 as usize);
+                            let mut index = ::tsz_compress::prelude::SeekIndexWriter::new(#every, widths);
+                            for checkpoint in &self.checkpoints {
+                                index.start_checkpoint();
+                                #( #col_seek_blocks )*
+                            }
+                            index.finish()
+                        }
+                    }
+                },
+                quote! {
+                    + {
+                        // The seek index has a checkpoint of fixed width per column at every interval of rows
+                        let widths = ::tsz_compress::prelude::CheckpointWidths::new(body_len, self.rows as u32 as usize);
+                        ::tsz_compress::prelude::SeekIndexWriter::finished_len(self.checkpoints.len(), #( #col_checkpoint_nibbles )+*)
+                    }
+                },
+            )
+        }
+        None => (quote! {}, quote! {}, quote! {}, quote! {}, quote! {}, quote! {}, quote! {}),
+    };
+    let finished_len = quote! {
+        // The 32-bit row count and whether each Option column has any values
+        let mut nibbles = 8 + #presence_nibbles;
+        #( #col_finished_nibbles )*
+        let body_len = nibbles.div_ceil(2);
+        #header_len + #lz_len + body_len #seek_len + #checksum_len
+    };
+
+    let write_header = if struct_attrs.header {
+        quote! {
+            // Write the frame header describing the columns
+            write_frame_header(output_bytes, &Self::COLUMN_TYPES, Self::SCHEMA_HASH);
+        }
+    } else {
+        quote! {}
+    };
+
     let finish_into_thin = if cfg!(feature = "thin-vec") {
         quote! {
             ///
@@ -805,46 +2565,14 @@ pub fn derive_compressv2(tokens: TokenStream) -> TokenStream {
             /// Leaving the intermediate buffers in a reserved, cleared state.
             ///
             fn finish_into_thin(&mut self, output_bytes: &mut ::thin_vec::ThinVec<u8>) {
-                // Only use one encoding mechanism
-                #(
-                    if let (Some(delta_buffer), Some(delta_delta_buffer)) = (&self.#col_delta_buf_idents, &self.#col_delta_delta_buf_idents) {
-                        // Prefer delta on ties
-                        if delta_delta_buffer.len() >= delta_buffer.len() {
-                            self.#col_delta_delta_buf_idents = None;
-                        } else {
-                            self.#col_delta_buf_idents = None;
-                        }
-                    }
-                )*
+                // Flush the columns and choose one encoding mechanism per column
+                let use_delta_delta = self.flush_columns();
 
-                // Guarantee that at least the column start nibble is emitted
-                #(
-                    if let Some(outbuf) = self.#col_delta_buf_idents.as_mut() {
-                        if outbuf.is_empty() {
-                            outbuf.push(::tsz_compress::prelude::halfvec::HalfWord::Half(::tsz_compress::prelude::consts::headers::START_OF_COLUMN));
-                        }
-                    }
-                    if let Some(outbuf) = self.#col_delta_delta_buf_idents.as_mut() {
-                        if outbuf.is_empty() {
-                            outbuf.push(::tsz_compress::prelude::halfvec::HalfWord::Half(::tsz_compress::prelude::consts::headers::START_OF_COLUMN));
-                        }
-                    }
-                )*
+                #checksum_start
 
-                // Flush any pending samples in the queues
-                // All of the bits are concatenated with a 1001 tag indicating the start of a new column
-                #(
-                    self.#col_delta_buf_idents.as_mut().map(|outbuf| {
-                        while self.#col_delta_comp_queue_idents.len() > 0 {
-                            self.#col_delta_comp_queue_idents.flush_delta_bits(outbuf);
-                        }
-                        });
-                    self.#col_delta_delta_buf_idents.as_mut().map(|outbuf| {
-                        while self.#col_delta_delta_comp_queue_idents.len() > 0 {
-                            self.#col_delta_delta_comp_queue_idents.emit_delta_delta_bits(outbuf);
-                        }
-                    });
-                )*
+                #write_header
+
+                #lz_start
 
                 // Write the number of rows as a 32-bit integer
                 // The decompressor will read this value and reserve space for the rows
@@ -853,29 +2581,28 @@ pub fn derive_compressv2(tokens: TokenStream) -> TokenStream {
                 let mut rows = ::tsz_compress::prelude::halfvec::HalfVec::new(8);
                 write_i32_bits(&mut rows, self.rows as u32 as i32);
 
+                #write_presence
+
+                #write_column_table
+
                 // Create an iterator over the words to be written
                 let rows = Some(rows);
                 let words = [
                     rows.as_ref().into_iter(),
-                    #(
-                        self.#col_delta_buf_idents.as_ref().into_iter(),
-                        self.#col_delta_delta_buf_idents.as_ref().into_iter(),
-                    )*
+                    #( #col_words )*
                 ].into_iter().flatten();
 
                 // Pack the words into nibbles
                 ::tsz_compress::prelude::halfvec::HalfVec::finish_thin(output_bytes, words);
 
+                #write_seek
+
+                #write_lz
+
+                #write_checksum
+
                 // Clear the buffers for re-use
-                #(
-                    self.#col_delta_buf_idents.as_mut().map(|outbuf| {
-                        outbuf.clear();
-                    });
-                    self.#col_delta_delta_buf_idents.as_mut().map(|outbuf| {
-                        outbuf.clear();
-                    });
-                    self.rows = 0;
-                )*
+                self.clear_columns();
             }
         }
     } else {
@@ -888,7 +2615,7 @@ pub fn derive_compressv2(tokens: TokenStream) -> TokenStream {
             mod private {
                 use super::*;
                 /// A Compressor type implementing TszCompressV2.
-                #[derive(Debug)]
+                #[derive(Debug, Clone)]
                 pub struct #compressor_ident {
                     #( #col_delta_comp_queue_idents: ::tsz_compress::prelude::CompressionQueue<10>,)*
                     #( #col_delta_delta_comp_queue_idents: ::tsz_compress::prelude::CompressionQueue<2>,)*
@@ -897,9 +2624,100 @@ pub fn derive_compressv2(tokens: TokenStream) -> TokenStream {
                     #( #prev_double_col_idents: #double_col_tys,)*
                     #( #prev_col_idents: #delta_col_tys,)*
                     #( #prev_delta_idents: #delta_col_tys,)*
+                    #( #validity_idents: ::tsz_compress::prelude::ValidityRuns,)*
+                    #( #grid_idents: ::tsz_compress::prelude::TimestampGrid,)*
+                    #( #dictionary_idents: ::tsz_compress::prelude::DictionaryColumn,)*
+                    #( #reference_idents: ::tsz_compress::prelude::ReferenceColumn,)*
+                    #( #xor_encoder_idents: ::tsz_compress::prelude::XorEncoder,)*
+                    #( #reconstructed_idents: #reconstructed_tys,)*
+                    #seek_field
                     rows: usize,
                 }
 
+                impl #compressor_ident {
+                    /// The type code of each column in the frame header.
+                    pub const COLUMN_TYPES: [u8; #num_columns] = [ #( #col_type_codes, )* ];
+
+                    /// The hash of the field names and types in the frame header.
+                    pub const SCHEMA_HASH: u32 = #schema_hash;
+
+                    ///
+                    /// Flushes the pending samples of every column, returning
+                    /// whether each column is shorter with delta-delta than delta encoding.
+                    ///
+                    fn flush_columns(&mut self) -> [bool; #num_columns] {
+                        // Guarantee that at least the column start nibble is emitted
+                        #( #col_start_blocks )*
+
+                        // Flush any pending samples in the queues
+                        // All of the bits are concatenated with a 1001 tag indicating the start of a new column
+                        self.flush_queues();
+
+                        // Finish the validity streams with the pending runs
+                        #( self.#validity_idents.flush(); )*
+
+                        // Finish the grids with the first timestamps
+                        #( self.#grid_idents.flush(); )*
+
+                        // Pack the indices of the dictionaries
+                        #( self.#dictionary_idents.flush(); )*
+
+                        // Only use one encoding mechanism, preferring delta on ties
+                        let use_delta_delta = [
+                            #(
+                                match (&self.#col_delta_buf_idents, &self.#col_delta_delta_buf_idents) {
+                                    (Some(delta_buffer), Some(delta_delta_buffer)) => delta_delta_buffer.len() < delta_buffer.len(),
+                                    (None, _) => true,
+                                    _ => false,
+                                },
+                            )*
+                        ];
+
+                        // Pack the values of the chosen encoding against the frame minimum when that is shorter
+                        #( { #reference_flushes } )*
+
+                        use_delta_delta
+                    }
+
+                    ///
+                    /// Flushes the pending samples in the queues of every column.
+                    ///
+                    fn flush_queues(&mut self) {
+                        #(
+                            if let Some(outbuf) = self.#col_delta_buf_idents.as_mut() {
+                                while self.#col_delta_comp_queue_idents.len() > 0 || self.#col_delta_comp_queue_idents.zero_run() > 0 {
+                                    self.#col_delta_comp_queue_idents.flush_delta_bits(outbuf);
+                                }
+                            }
+                            if let Some(outbuf) = self.#col_delta_delta_buf_idents.as_mut() {
+                                while self.#col_delta_delta_comp_queue_idents.len() > 0 || self.#col_delta_delta_comp_queue_idents.zero_run() > 0 {
+                                    self.#col_delta_delta_comp_queue_idents.emit_delta_delta_bits(outbuf);
+                                }
+                            }
+                        )*
+                    }
+
+                    ///
+                    /// Leaves the intermediate buffers in a reserved, cleared state.
+                    ///
+                    fn clear_columns(&mut self) {
+                        #(
+                            if let Some(outbuf) = self.#col_delta_buf_idents.as_mut() {
+                                outbuf.clear();
+                            }
+                            if let Some(outbuf) = self.#col_delta_delta_buf_idents.as_mut() {
+                                outbuf.clear();
+                            }
+                        )*
+                        #( self.#validity_idents.clear(); )*
+                        #( self.#grid_idents.clear(); )*
+                        #( self.#dictionary_idents.clear(); )*
+                        #( self.#reference_idents.clear(); )*
+                        #seek_clear
+                        self.rows = 0;
+                    }
+                }
+
                 impl TszCompressV2 for #compressor_ident {
                     type T = #ident;
 
@@ -915,6 +2733,13 @@ pub fn derive_compressv2(tokens: TokenStream) -> TokenStream {
                             #( #prev_double_col_idents: 0,)*
                             #( #prev_col_idents: 0,)*
                             #( #prev_delta_idents: 0,)*
+                            #( #validity_idents: ::tsz_compress::prelude::ValidityRuns::new(16),)*
+                            #( #grid_idents: #grid_news,)*
+                            #( #dictionary_idents: ::tsz_compress::prelude::DictionaryColumn::new(#dictionary_bits),)*
+                            #( #reference_idents: #reference_news,)*
+                            #( #xor_encoder_idents: ::tsz_compress::prelude::XorEncoder::new(#xor_encoder_bits),)*
+                            #( #reconstructed_idents: 0,)*
+                            #seek_new
                             rows: 0,
                         }
                     }
@@ -922,61 +2747,12 @@ pub fn derive_compressv2(tokens: TokenStream) -> TokenStream {
                     /// Performs compression using delta/delta-delta compression.
                     #[inline(always)]
                     fn compress(&mut self, row: Self::T) {
+                        #seek_row
+
                         // Enqueues delta and delta-delta values
                         self.rows += 1;
 
-                        if self.rows > 2 {
-                            #(
-                                // The new delta  and delta-delta
-                                let col = row.#col_idents as #delta_col_tys;
-                                let delta = col - self.#prev_col_idents;
-
-                                // Do delta compression if configured
-                                #delta_comp_block
-
-                                // Do delta-delta compression if configured
-                                #delta_delta_comp_block
-
-                                // Update the previous values
-                                self.#prev_col_idents = col;
-                                self.#prev_delta_idents = delta;
-                            )*
-                        } else if self.rows == 1 {
-                            /// Write out the full value in the exact bit-width of the column.
-                            #(
-                                if let Some(outbuf) = self.#col_delta_buf_idents.as_mut() {
-                                    outbuf.push(::tsz_compress::prelude::halfvec::HalfWord::Half(::tsz_compress::prelude::consts::headers::START_OF_COLUMN));
-                                    outbuf.push(::tsz_compress::prelude::halfvec::HalfWord::Half(::tsz_compress::prelude::consts::headers::FIRST_ROW));
-                                    #write_first(outbuf, row.#col_idents);
-                                }
-                                if let Some(outbuf) = self.#col_delta_delta_buf_idents.as_mut() {
-                                    outbuf.push(::tsz_compress::prelude::halfvec::HalfWord::Half(::tsz_compress::prelude::consts::headers::START_OF_COLUMN));
-                                    outbuf.push(::tsz_compress::prelude::halfvec::HalfWord::Half(::tsz_compress::prelude::consts::headers::FIRST_ROW));
-                                    #write_first(outbuf, row.#col_idents);
-                                }
-                                self.#prev_double_col_idents = row.#col_idents as #double_col_tys;
-                            )*
-                        } else if self.rows == 2 {
-                            /// Write out the full value in the next exact bit-width of the column, regardless of chosen delta bit-width.
-                            /// SAFETY: If the bit-width is configurable, then bits at rest will be uninterpretable.
-                            #(
-                                // Up cast to double bit-width always for the first delta
-                                let col = row.#col_idents as #double_col_tys;
-                                let delta = col - self.#prev_double_col_idents;
-                                if let Some(outbuf) = self.#col_delta_buf_idents.as_mut() {
-                                    outbuf.push(::tsz_compress::prelude::halfvec::HalfWord::Half(::tsz_compress::prelude::consts::headers::SECOND_ROW));
-                                    #write_second(outbuf, delta);
-                                }
-                                if let Some(outbuf) = self.#col_delta_delta_buf_idents.as_mut() {
-                                    outbuf.push(::tsz_compress::prelude::halfvec::HalfWord::Half(::tsz_compress::prelude::consts::headers::SECOND_ROW));
-                                    #write_second(outbuf, delta);
-                                }
-
-                                // Use choice of bit-width for delta/delta-delta compression
-                                self.#prev_delta_idents = delta as #delta_col_tys;
-                                self.#prev_col_idents = col as #delta_col_tys;
-                            )*;
-                        }
+                        #( #col_compress_blocks )*
                     }
 
 
@@ -993,6 +2769,8 @@ pub fn derive_compressv2(tokens: TokenStream) -> TokenStream {
                                 finished_nibble_count += delta_delta_buffer.len()
                             }
                         )*
+                        #( finished_nibble_count += self.#validity_idents.finished_nibbles(); )*
+                        #( finished_nibble_count += self.#grid_idents.finished_nibbles(); )*
                         let col_count_delta = (#( self.#col_delta_comp_queue_idents.len() )+*);
                         let col_count_delta_delta = (#( self.#col_delta_delta_comp_queue_idents.len() )+*);
                         let col_bit_rate = #num_columns * self.bit_rate();
@@ -1014,145 +2792,727 @@ pub fn derive_compressv2(tokens: TokenStream) -> TokenStream {
                                 finished_nibble_count += delta_delta_buffer.len()
                             }
                         )*
+                        #( finished_nibble_count += self.#validity_idents.finished_nibbles(); )*
+                        #( finished_nibble_count += self.#grid_idents.finished_nibbles(); )*
                         if self.rows == 0 {
                             return 0;
                         }
-                        4 * finished_nibble_count / self.rows
+                        4 * finished_nibble_count / self.rows
+                    }
+
+                    ///
+                    /// The number of rows that have been compressed.
+                    /// This is an exact answer for rows consumed including rows that may not have been emitted.
+                    ///
+                    #[inline(always)]
+                    fn row_count(&self) -> usize {
+                        self.rows
+                    }
+
+                    ///
+                    /// The exact number of bytes that `finish_into` would append,
+                    /// simulating the flush of the pending samples of every column.
+                    /// With `#[tsz(lz)]` this is an upper bound on a packed body.
+                    ///
+                    fn finished_len(&self) -> usize {
+                        #finished_len
+                    }
+
+                    ///
+                    /// Consumes the compressor state, appending compressed bytes
+                    /// to the provided buffer and reserving space if needed.
+                    ///
+                    /// Leaving the intermediate buffers in a reserved, cleared state.
+                    ///
+                    fn finish_into(&mut self, output_bytes: &mut ::alloc::vec::Vec<u8>) {
+                        // Flush the columns and choose one encoding mechanism per column
+                        let use_delta_delta = self.flush_columns();
+
+                        #checksum_start
+
+                        #write_header
+
+                        #lz_start
+
+                                // Write the number of rows as a 32-bit integer
+                        // The decompressor will read this value and reserve space for the rows
+                        // SAFETY: The number of rows may be more than 2^32, but the decompressor will
+                        //         reserve at most 2^32 rows.
+                        let mut rows = ::tsz_compress::prelude::halfvec::HalfVec::new(8);
+                        write_i32_bits(&mut rows, self.rows as u32 as i32);
+
+                        #write_presence
+
+                        #write_column_table
+
+                        // Create an iterator over the words to be written
+                        let rows = Some(rows);
+                        let words = [
+                            rows.as_ref().into_iter(),
+                            #( #col_words )*
+                        ].into_iter().flatten();
+
+                        // Pack the words into nibbles
+                        ::tsz_compress::prelude::halfvec::HalfVec::finish(output_bytes, words);
+
+                        #write_seek
+
+                        #write_lz
+
+                        #write_checksum
+
+                        // Clear the buffers for re-use
+                        self.clear_columns();
+                    }
+
+                    #finish_into_thin
+                }
+
+                #seek_fn
+
+                #ffi_fns
+            }
+
+            pub use private::#compressor_ident;
+
+            #c_header
+        }
+    };
+
+    compressor_struct.into()
+}
+
+#[proc_macro_derive(DecompressV2, attributes(tsz))]
+pub fn derive_decompressv2(tokens: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(tokens as syn::DeriveInput);
+
+    // We will define a struct by this name
+    let ident = input.ident.clone();
+    let decompressor_ident = format_ident!("{}DecompressorImpl", ident);
+    let decompress_iter_ident = format_ident!("{}DecompressIter", ident);
+
+    let struct_attrs = get_struct_attributes(&input);
+    let columns = get_fields_of_struct(input);
+    let (col_idents, field_tys, col_attrs): (Vec<_>, Vec<_>, Vec<_>) = multiunzip(columns);
+    let (col_tys, col_nullable) = column_types(&field_tys);
+    if let Err(e) = check_scales(&col_attrs) {
+        return e.to_compile_error().into();
+    }
+    let (col_tys, col_scaling) = scaled_column_types(col_tys, &col_attrs);
+    let (col_tys, col_enums) = enum_column_types(col_tys, &col_attrs);
+    let col_periods = grid_periods(&col_tys, &col_nullable, &col_attrs);
+    let col_dictionary = dictionary_columns(&col_tys, &col_nullable, &col_attrs);
+    let num_columns = col_idents.len();
+    let col_indices = (0..num_columns).map(syn::Index::from).collect_vec();
+
+    // Describe the columns in an optional frame header
+    let col_type_codes = column_type_codes(&col_tys, &col_nullable, &col_attrs);
+    let schema_hash = schema_hash(&col_idents, &field_tys, &col_attrs, &struct_attrs);
+    let col_vec_idents = col_idents
+        .iter()
+        .map(|ident| format_ident!("col_{}", ident))
+        .collect_vec();
+    let first_col_vec_ident = &col_vec_idents[0];
+
+    let read_checksum = read_checksum(&struct_attrs.checksum);
+    let (read_lz, open_lz) = if struct_attrs.lz {
+        (
+            quote! {
+                // Unpack the body of the frame if it is packed
+                let mut unpacked = ::alloc::vec::Vec::new();
+                let bytes = ::tsz_compress::prelude::lz::unpack_frame(bytes, &mut unpacked)?;
+            },
+            quote! {
+                // Cursors borrow the bytes, so a packed body is decoded eagerly instead
+                let bytes = match bytes.split_first() {
+                    Some((&::tsz_compress::prelude::consts::frame::RAW, body)) => body,
+                    Some((&::tsz_compress::prelude::consts::frame::PACKED, _)) => return Ok(None),
+                    Some(_) => return Err(CodingError::InvalidBits),
+                    None => return Err(CodingError::Empty),
+                };
+            },
+        )
+    } else {
+        (quote! {}, quote! {})
+    };
+
+    // Optionally generate a C interface to the decompressor
+    let (ffi_fns, c_header) = match struct_attrs.ffi.as_ref() {
+        Some(prefix) => {
+            let c_types = column_c_types(&field_tys, &col_attrs);
+            let ffi_tys = column_ffi_types(&field_tys, &col_tys, &col_enums);
+
+            // The variants of an enum column are read as their discriminant, the integer #[repr] of the enum
+            let col_ptrs = col_vec_idents
+                .iter()
+                .zip(&col_enums)
+                .zip(&ffi_tys)
+                .map(|((col_vec_ident, enum_ty), ffi_ty)| match enum_ty {
+                    Some(_) => {
+                        quote! { tsz_decompressor.#col_vec_ident.as_ptr() as *const #ffi_ty }
+                    }
+                    None => quote! { tsz_decompressor.#col_vec_ident.as_ptr() },
+                })
+                .collect_vec();
+            let new_fn = format_ident!("{}_decompressor_new", prefix);
+            let decompress_fn = format_ident!("{}_decompress", prefix);
+            let rows_fn = format_ident!("{}_rows", prefix);
+            let col_fns = col_idents
+                .iter()
+                .map(|col| format_ident!("{}_col_{}", prefix, col))
+                .collect_vec();
+            let clear_fn = format_ident!("{}_decompressor_clear", prefix);
+            let free_fn = format_ident!("{}_decompressor_free", prefix);
+            let c_ident = format!("{}Decompressor", ident);
+            let guard = format!("{}_DECOMPRESS_H", prefix.to_uppercase());
+            let c_col_fns = col_fns
+                .iter()
+                .zip(&c_types)
+                .map(|(col_fn, ty)| {
+                    format!("const {ty} *{col_fn}(const {c_ident} *decompressor);\n")
+                })
+                .collect::<String>();
+            let c_header = format!(
+                "#ifndef {guard}\n\
+                 #define {guard}\n\
+                 \n\
+                 #include <stdbool.h>\n\
+                 #include <stddef.h>\n\
+                 #include <stdint.h>\n\
+                 \n\
+                 {C_ERROR_CODES}\
+                 \n\
+                 typedef struct {c_ident} {c_ident};\n\
+                 \n\
+                 /* Creates a decompressor. */\n\
+                 {c_ident} *{new_fn}(void);\n\
+                 \n\
+                 /* Decompresses a frame, extending the columns. Returns 0 or a negative TSZ_ERROR code. */\n\
+                 int32_t {decompress_fn}({c_ident} *decompressor, const uint8_t *bytes, size_t len);\n\
+                 \n\
+                 /* The number of decompressed rows in each column. */\n\
+                 size_t {rows_fn}(const {c_ident} *decompressor);\n\
+                 \n\
+                 /* The decompressed values of each column, valid until the next call on the decompressor. */\n\
+                 {c_col_fns}\
+                 \n\
+                 /* Clears the decompressed columns. */\n\
+                 void {clear_fn}({c_ident} *decompressor);\n\
+                 \n\
+                 /* Frees a decompressor. */\n\
+                 void {free_fn}({c_ident} *decompressor);\n\
+                 \n\
+                 #endif /* {guard} */\n"
+            );
+            let first_col_vec_ident = &col_vec_idents[0];
+            let ffi_fns = quote! {
+                ///
+                /// Creates a decompressor for the C interface.
+                ///
+                #[no_mangle]
+                pub extern "C" fn #new_fn() -> *mut #decompressor_ident {
+                    ::alloc::boxed::Box::into_raw(::alloc::boxed::Box::new(<#decompressor_ident as TszDecompressV2>::new()))
+                }
+
+                ///
+                /// Decompresses a frame, extending the columns. Returns 0 or a negative error code.
+                ///
+                /// # Safety
+                /// The decompressor must be null or created by the C interface and not yet freed.
+                /// `bytes` must be valid for reads of `len` bytes.
+                ///
+                #[no_mangle]
+                pub unsafe extern "C" fn #decompress_fn(tsz_decompressor: *mut #decompressor_ident, bytes: *const u8, len: usize) -> i32 {
+                    let Some(tsz_decompressor) = tsz_decompressor.as_mut() else {
+                        return CodingError::Empty.code();
+                    };
+                    if bytes.is_null() {
+                        return CodingError::Empty.code();
+                    }
+                    match tsz_decompressor.decompress(core::slice::from_raw_parts(bytes, len)) {
+                        Ok(()) => 0,
+                        Err(e) => e.code(),
+                    }
+                }
+
+                ///
+                /// The number of decompressed rows in each column.
+                ///
+                /// # Safety
+                /// The decompressor must be null or created by the C interface and not yet freed.
+                ///
+                #[no_mangle]
+                pub unsafe extern "C" fn #rows_fn(tsz_decompressor: *const #decompressor_ident) -> usize {
+                    tsz_decompressor.as_ref().map_or(0, |tsz_decompressor| tsz_decompressor.#first_col_vec_ident.len())
+                }
+
+                #(
+                    ///
+                    /// The decompressed values of the column, valid until the next call on the decompressor.
+                    ///
+                    /// # Safety
+                    /// The decompressor must be null or created by the C interface and not yet freed.
+                    ///
+                    #[no_mangle]
+                    pub unsafe extern "C" fn #col_fns(tsz_decompressor: *const #decompressor_ident) -> *const #ffi_tys {
+                        tsz_decompressor.as_ref().map_or(core::ptr::null(), |tsz_decompressor| #col_ptrs)
+                    }
+                )*
+
+                ///
+                /// Clears the decompressed columns.
+                ///
+                /// # Safety
+                /// The decompressor must be null or created by the C interface and not yet freed.
+                ///
+                #[no_mangle]
+                pub unsafe extern "C" fn #clear_fn(tsz_decompressor: *mut #decompressor_ident) {
+                    if let Some(tsz_decompressor) = tsz_decompressor.as_mut() {
+                        tsz_decompressor.clear();
+                    }
+                }
+
+                ///
+                /// Frees a decompressor created by the C interface.
+                ///
+                /// # Safety
+                /// The decompressor must be null or created by the C interface and not yet freed.
+                ///
+                #[no_mangle]
+                pub unsafe extern "C" fn #free_fn(tsz_decompressor: *mut #decompressor_ident) {
+                    if !tsz_decompressor.is_null() {
+                        drop(::alloc::boxed::Box::from_raw(tsz_decompressor));
+                    }
+                }
+            };
+            let c_header = quote! {
+                pub use private::{#new_fn, #decompress_fn, #rows_fn, #( #col_fns, )* #clear_fn, #free_fn};
+
+                /// The C header declaring the C interface to the decompressor.
+                pub const C_HEADER: &str = #c_header;
+            };
+            (ffi_fns, c_header)
+        }
+        None => (quote! {}, quote! {}),
+    };
+    let read_header = if struct_attrs.header {
+        quote! {
+            // Validate the frame header describing the columns
+            let bytes = read_frame_header(bytes, &#decompressor_ident::COLUMN_TYPES, #decompressor_ident::SCHEMA_HASH)?;
+        }
+    } else {
+        quote! {}
+    };
+
+    let decode_idents = col_tys
+        .iter()
+        .zip(&col_enums)
+        .map(|(ty, enum_ty)| match (ty, enum_ty) {
+            // Enum fields decode the discriminants into variants
+            (_, Some(enum_ty)) => {
+                quote! { <#enum_ty as ::tsz_compress::prelude::ColumnEnum>::decode_column }
+            }
+            (syn::Type::Path(syn::TypePath { path, .. }), None) => {
+                let segment = path.segments.first().unwrap();
+                let ident = segment.ident.clone();
+                match ident.to_string().as_str() {
+                    "i8" => quote! { decode_i8_rows },
+                    "i16" => quote! { decode_i16_rows },
+                    "i32" => quote! { decode_i32_rows },
+                    "i64" => quote! { decode_i64_rows },
+                    "u8" => quote! { decode_u8_rows },
+                    "u16" => quote! { decode_u16_rows },
+                    "u32" => quote! { decode_u32_rows },
+                    "u64" => quote! { decode_u64_rows },
+                    "i128" => quote! { decode_i128_rows },
+                    "f32" => quote! { decode_f32_rows },
+                    "f64" => quote! { decode_f64_rows },
+                    "bool" => quote! { decode_bool_rows },
+                    _ => panic!("Unsupported type"),
+                }
+            }
+            _ => panic!("Unsupported type"),
+        })
+        .collect::<Vec<_>>();
+
+    let (read_presence, any_column) = presence_tokens(&col_nullable);
+    // Scaled float fields decode the quantized column, then rescale it
+    let mut col_raw_idents = Vec::new();
+    let mut col_raw_tys = Vec::new();
+    let mut col_rescale_blocks = Vec::new();
+    let mut col_row_values = Vec::new();
+    let mut col_span_blocks = Vec::new();
+    for i in 0..num_columns {
+        let col_ty = &col_tys[i];
+        let col_vec_ident = &col_vec_idents[i];
+        let col_index = &col_indices[i];
+        let next_value = quote! { cursors.#col_index.next().ok_or(CodingError::NotEnoughBits)?? };
+        if let Some(enum_ty) = &col_enums[i] {
+            // Enum fields read the discriminant, rejecting discriminants of no variant
+            let from_discriminant = quote! {
+                <#enum_ty as ::tsz_compress::prelude::ColumnEnum>::from_discriminant(value).ok_or(CodingError::InvalidDiscriminant)?
+            };
+            let row_value = if col_nullable[i] {
+                quote! {
+                    match #next_value {
+                        Some(value) => Some(#from_discriminant),
+                        None => None,
+                    }
+                }
+            } else {
+                quote! {
+                    {
+                        let value = #next_value;
+                        #from_discriminant
+                    }
+                }
+            };
+            col_span_blocks.push(quote! {
+                for _ in start..end {
+                    self.#col_vec_ident.push(#row_value);
+                }
+            });
+            col_row_values.push(row_value);
+            continue;
+        }
+        let Some((_, rescale)) = &col_scaling[i] else {
+            col_span_blocks.push(quote! {
+                for _ in start..end {
+                    self.#col_vec_ident.push(#next_value);
+                }
+            });
+            col_row_values.push(next_value);
+            continue;
+        };
+        let col_raw_ident = format_ident!("{}_raw", col_vec_ident);
+        let rescale = if col_nullable[i] {
+            col_raw_tys.push(quote! { Option<#col_ty> });
+            quote! { value.map(|value| #rescale) }
+        } else {
+            col_raw_tys.push(quote! { #col_ty });
+            rescale.clone()
+        };
+        col_rescale_blocks.push(quote! {
+            let start = self.#col_vec_ident.len();
+            self.#col_vec_ident.extend(self.#col_raw_ident[start..].iter().map(|&value| #rescale));
+        });
+        col_span_blocks.push(quote! {
+            for _ in start..end {
+                self.#col_raw_ident.push(#next_value);
+            }
+        });
+        col_row_values.push(quote! {
+            {
+                let value = #next_value;
+                #rescale
+            }
+        });
+        col_raw_idents.push(col_raw_ident);
+    }
+    let storage_idents = col_vec_idents.iter().chain(&col_raw_idents).collect_vec();
+
+    // Columns are decoded one after the other, or from their offset in the column table when projected
+    let mut rescale_blocks = col_rescale_blocks.iter();
+    let mut nullable_index = 0;
+    let mut decode_vec_idents = Vec::new();
+    let mut col_in_frame = Vec::new();
+    let mut col_decode_blocks = Vec::new();
+    let mut col_projection_blocks = Vec::new();
+    let mut col_fill_blocks = Vec::new();
+    let mut col_projection_fill_blocks = Vec::new();
+    for i in 0..num_columns {
+        let col_vec_ident = &if col_scaling[i].is_some() {
+            format_ident!("{}_raw", col_vec_idents[i])
+        } else {
+            col_vec_idents[i].clone()
+        };
+        let col_index = &col_indices[i];
+        let decode_ident = &decode_idents[i];
+        let rescale = match col_scaling[i] {
+            Some(_) => rescale_blocks.next().cloned(),
+            None => None,
+        };
+        let decode = if col_nullable[i] {
+            quote! { decode_nullable(&mut iter, rows as usize, &mut self.#col_vec_ident, #decode_ident)?; }
+        } else if col_dictionary[i] {
+            quote! { decode_dictionary(&mut iter, rows as usize, &mut self.#col_vec_ident, #decode_ident)?; }
+        } else if col_periods[i].is_some() {
+            quote! { decode_grid(&mut iter, rows as usize, &mut self.#col_vec_ident, #decode_ident)?; }
+        } else {
+            quote! { #decode_ident(&mut iter, rows as usize, &mut self.#col_vec_ident)?; }
+        };
+        let project = quote! {
+            let mut iter = ::tsz_compress::prelude::column_at(bytes, column_table[#col_index])?;
+            #decode
+        };
+        if col_nullable[i] {
+            // Option columns without values are omitted from the frame, and filled once the columns in the frame are checked
+            let present_index = syn::Index::from(nullable_index);
+            nullable_index += 1;
+            col_in_frame.push(quote! { present[#present_index] });
+            col_decode_blocks.push(quote! {
+                if present[#present_index] {
+                    #decode
+                }
+            });
+            col_projection_blocks.push(quote! {
+                if present[#present_index] {
+                    #project
+                }
+            });
+            col_fill_blocks.push(quote! {
+                if !present[#present_index] {
+                    extend_missing(&mut self.#col_vec_ident, rows as usize)?;
+                }
+            });
+            col_projection_fill_blocks.push(quote! {
+                if selected[#col_index] && !present[#present_index] {
+                    extend_missing(&mut self.#col_vec_ident, rows as usize)?;
+                }
+            });
+        } else {
+            col_in_frame.push(quote! { true });
+            col_decode_blocks.push(decode);
+            col_projection_blocks.push(project);
+        }
+        if let Some(rescale) = rescale {
+            col_projection_fill_blocks.push(quote! {
+                if selected[#col_index] {
+                    #rescale
+                }
+            });
+        }
+        decode_vec_idents.push(col_vec_ident.clone());
+    }
+    // Without a column in the frame there are no values to check the row count of an omitted column against
+    let check_omitted_rows = if col_nullable.contains(&true) {
+        quote! {
+            if !in_frame.contains(&true) && rows as usize > MAX_OMITTED_ROWS {
+                #( self.#storage_idents.clear(); )*
+                return Err(CodingError::InvalidRowCount(rows as usize));
+            }
+        }
+    } else {
+        quote! {}
+    };
+    let (col_cursor_tys, col_cursor_blocks) = column_cursors(
+        &col_idents,
+        &col_tys,
+        &col_nullable,
+        &col_periods,
+        &col_dictionary,
+    );
+
+    // Frames of a struct with a timestamp field may be decoded for a range of time
+    let decompress_range = match timestamp_column(&col_attrs) {
+        Some(ts) => {
+            let ts_ty = &field_tys[ts];
+            let ts_vec_ident = &col_vec_idents[ts];
+            let ts_index = &col_indices[ts];
+            quote! {
+                ///
+                /// Decompress the rows of tsz-compressed bytes with a timestamp in `range`, extending the columns.
+                ///
+                /// The timestamp column is decoded first to find the span from the first row at or after the
+                /// start of the range to the next row at or after its end, then the other columns skip to the
+                /// span and decode only its rows. Timestamps are expected to not decrease within a frame.
+                ///
+                pub fn decompress_range(&mut self, bytes: &[u8], range: core::ops::Range<#ts_ty>) -> Result<(), CodingError> {
+                    let Some((mut cursors, rows)) = #decompress_iter_ident::open(bytes)? else {
+                        // Decode the packed frame into columns and copy the rows of the span
+                        let mut decompressor = Self::new();
+                        decompressor.decompress(bytes)?;
+                        let timestamps = &decompressor.#ts_vec_ident;
+                        let start = timestamps.iter().position(|ts| *ts >= range.start).unwrap_or(timestamps.len());
+                        let end = start + timestamps[start..].iter().position(|ts| *ts >= range.end).unwrap_or(timestamps.len() - start);
+                        #( self.#storage_idents.extend_from_slice(&decompressor.#storage_idents[start..end]); )*
+                        return Ok(());
+                    };
+
+                    // Find the span of rows in the range from the timestamp column
+                    let mut start = None;
+                    let mut end = rows;
+                    for (row, ts) in cursors.#ts_index.clone().enumerate() {
+                        let ts = ts?;
+                        if start.is_none() && ts >= range.start {
+                            start = Some(row);
+                        }
+                        if start.is_some() && ts >= range.end {
+                            end = row;
+                            break;
+                        }
                     }
+                    let start = start.unwrap_or(rows);
 
-                    ///
-                    /// The number of rows that have been compressed.
-                    /// This is an exact answer for rows consumed including rows that may not have been emitted.
-                    ///
-                    #[inline(always)]
-                    fn row_count(&self) -> usize {
-                        self.rows
-                    }
+                    // Skip each column to the span, then decode the rows of the span
+                    #( cursors.#col_indices.skip_rows(start)?; )*
+                    #( #col_span_blocks )*
 
-                    ///
-                    /// Consumes the compressor state, appending compressed bytes
-                    /// to the provided buffer and reserving space if needed.
-                    ///
-                    /// Leaving the intermediate buffers in a reserved, cleared state.
-                    ///
-                    fn finish_into(&mut self, output_bytes: &mut ::alloc::vec::Vec<u8>) {
-                        // Only use one encoding mechanism
-                        #(
-                            if let (Some(delta_buffer), Some(delta_delta_buffer)) = (&self.#col_delta_buf_idents, &self.#col_delta_delta_buf_idents) {
-                                // Prefer delta on ties
-                                if delta_delta_buffer.len() >= delta_buffer.len() {
-                                    self.#col_delta_delta_buf_idents = None;
-                                } else {
-                                    self.#col_delta_buf_idents = None;
-                                }
-                            }
-                        )*
+                    // Rescale the integer columns of scaled fields
+                    #( #col_rescale_blocks )*
 
-                        // Guarantee that at least the column start nibble is emitted
-                        #(
-                            if let Some(outbuf) = self.#col_delta_buf_idents.as_mut() {
-                                if outbuf.is_empty() {
-                                    outbuf.push(::tsz_compress::prelude::halfvec::HalfWord::Half(::tsz_compress::prelude::consts::headers::START_OF_COLUMN));
-                                }
-                            }
-                            if let Some(outbuf) = self.#col_delta_delta_buf_idents.as_mut() {
-                                if outbuf.is_empty() {
-                                    outbuf.push(::tsz_compress::prelude::halfvec::HalfWord::Half(::tsz_compress::prelude::consts::headers::START_OF_COLUMN));
-                                }
-                            }
-                        )*
+                    Ok(())
+                }
+            }
+        }
+        None => quote! {},
+    };
 
-                        // Flush any pending samples in the queues
-                        // All of the bits are concatenated with a 1001 tag indicating the start of a new column
-                        #(
-                            self.#col_delta_buf_idents.as_mut().map(|outbuf| {
-                                while self.#col_delta_comp_queue_idents.len() > 0 {
-                                    self.#col_delta_comp_queue_idents.flush_delta_bits(outbuf);
-                                }
-                             });
-                            self.#col_delta_delta_buf_idents.as_mut().map(|outbuf| {
-                                while self.#col_delta_delta_comp_queue_idents.len() > 0 {
-                                    self.#col_delta_delta_comp_queue_idents.emit_delta_delta_bits(outbuf);
+    // Frames of a struct with a seek index may be decoded from a checkpoint before a range of rows
+    let (split_seek, iter_rows_range, seek_fn) = if struct_attrs.seek.is_some() {
+        (
+            quote! {
+                // The seek index trails the columns
+                let (bytes, _) = ::tsz_compress::prelude::split_seek_index(bytes)?;
+            },
+            quote! {
+                ///
+                /// Lazily decompress the rows in `range` of tsz-compressed bytes, placing a cursor into
+                /// each column from the checkpoint of the seek index before the first row.
+                ///
+                pub fn iter_rows_range(bytes: &[u8], range: core::ops::Range<usize>) -> #decompress_iter_ident<'_> {
+                    if range.is_empty() {
+                        return #decompress_iter_ident { cursors: None, rows: 0, unpacked: None, error: None };
+                    }
+                    match #decompress_iter_ident::seek(bytes, &range) {
+                        Ok(Some((cursors, rows))) => #decompress_iter_ident { cursors: Some(cursors), rows, unpacked: None, error: None },
+                        Ok(None) => {
+                            // Decode the packed frame into columns and rotate the rows of the range as they are taken
+                            let mut decompressor = Self::new();
+                            let error = match decompressor.decompress(bytes) {
+                                Ok(()) if range.end <= decompressor.#first_col_vec_ident.len() => {
+                                    return #decompress_iter_ident { cursors: None, rows: range.len(), unpacked: Some((decompressor, range.start)), error: None };
                                 }
-                            });
-                        )*
+                                Ok(()) => CodingError::InvalidRowCount(range.end),
+                                Err(e) => e,
+                            };
+                            #decompress_iter_ident { cursors: None, rows: 0, unpacked: None, error: Some(error) }
+                        }
+                        Err(e) => #decompress_iter_ident { cursors: None, rows: 0, unpacked: None, error: Some(e) },
+                    }
+                }
+            },
+            quote! {
+                ///
+                /// Validates the frame and places a cursor into each column at the first row of the range,
+                /// stepping from the checkpoint before it.
+                ///
+                fn seek(bytes: &'a [u8], range: &core::ops::Range<usize>) -> Result<Option<(( #( #col_cursor_tys, )* ), usize)>, CodingError> {
+                    #read_checksum
 
-                        // Write the number of rows as a 32-bit integer
-                        // The decompressor will read this value and reserve space for the rows
-                        // SAFETY: The number of rows may be more than 2^32, but the decompressor will
-                        //         reserve at most 2^32 rows.
-                        let mut rows = ::tsz_compress::prelude::halfvec::HalfVec::new(8);
-                        write_i32_bits(&mut rows, self.rows as u32 as i32);
+                    #read_header
 
-                        // Create an iterator over the words to be written
-                        let rows = Some(rows);
-                        let words = [
-                            rows.as_ref().into_iter(),
-                            #(
-                                self.#col_delta_buf_idents.as_ref().into_iter(),
-                                self.#col_delta_delta_buf_idents.as_ref().into_iter(),
-                            )*
-                        ].into_iter().flatten();
+                    #open_lz
 
-                        // Pack the words into nibbles
-                        ::tsz_compress::prelude::halfvec::HalfVec::finish(output_bytes, words);
+                    let (bytes, index) = ::tsz_compress::prelude::split_seek_index(bytes)?;
 
-                        // Clear the buffers for re-use
-                        #(
-                            self.#col_delta_buf_idents.as_mut().map(|outbuf| {
-                                outbuf.clear();
-                            });
-                            self.#col_delta_delta_buf_idents.as_mut().map(|outbuf| {
-                                outbuf.clear();
-                            });
-                            self.rows = 0;
-                        )*
+                    // Require at least the row count and 1 column
+                    if bytes.len() < core::mem::size_of::<i32>() + 1 {
+                        return Err(CodingError::Empty);
                     }
 
-                    #finish_into_thin
-                }
-            }
+                    // Read the row count
+                    let row_bytes: &[u8; 4] = bytes[..4].try_into().map_err(|_|CodingError::NotEnoughBits)?;
+                    let rows = read_full_i32(row_bytes) as u32 as usize;
+                    let widths = ::tsz_compress::prelude::CheckpointWidths::new(bytes.len(), rows);
+                    let bytes = &bytes[core::mem::size_of::<i32>()..];
+                    if range.end > rows {
+                        return Err(CodingError::InvalidRowCount(range.end));
+                    }
 
-            pub use private::#compressor_ident;
-        }
+                    // Place the cursors at the checkpoint before the first row, then skip to the first row
+                    let row = range.start / index.every() * index.every();
+                    let mut checkpoint = index.checkpoint(range.start / index.every())?;
+                    let mut cursors = ( #( <#col_cursor_tys as ::tsz_compress::prelude::SeekCursor<'a>>::read_checkpoint(bytes, &mut checkpoint, widths, row, rows)?, )* );
+                    #( cursors.#col_indices.skip_rows(range.start - row)?; )*
+
+                    Ok(Some((cursors, range.len())))
+                }
+            },
+        )
+    } else {
+        (quote! {}, quote! {}, quote! {})
     };
 
-    compressor_struct.into()
-}
+    // Frames of a struct with a column table may be decoded for some of the columns
+    let skip_column_table = skip_column_table(struct_attrs.column_table, num_columns);
+    let (column_enum, column_enum_use, decompress_columns) = if struct_attrs.column_table {
+        let column_ident = format_ident!("{}Column", ident);
+        let variant_idents = col_idents
+            .iter()
+            .map(|col_ident| format_ident!("{}", camel_case(&col_ident.to_string())))
+            .collect_vec();
+        (
+            quote! {
+                /// A column of a frame, to select the columns to decompress.
+                #[derive(Debug, Copy, Clone, PartialEq, Eq)]
+                pub enum #column_ident {
+                    #( #variant_idents, )*
+                }
+            },
+            quote! { pub use private::#column_ident; },
+            quote! {
+                ///
+                /// Decompress the selected columns of tsz-compressed bytes, extending only their vectors.
+                ///
+                /// Each selected column is decoded from its offset in the column table of the frame,
+                /// and the other columns are neither decoded nor extended.
+                ///
+                pub fn decompress_columns(&mut self, bytes: &[u8], columns: &[#column_ident]) -> Result<(), CodingError> {
+                    #read_checksum
+
+                    #read_header
+
+                    #read_lz
+
+                    #split_seek
+
+                    // Require at least the row count and 1 column
+                    if bytes.len() < core::mem::size_of::<i32>() + 1 {
+                        return Err(CodingError::Empty);
+                    }
 
-#[proc_macro_derive(DecompressV2)]
-pub fn derive_decompressv2(tokens: TokenStream) -> TokenStream {
-    let input = parse_macro_input!(tokens as syn::DeriveInput);
+                    // Read the row count
+                    let row_bytes: &[u8; 4] = bytes[..4].try_into().map_err(|_|CodingError::NotEnoughBits)?;
+                    let rows = read_full_i32(row_bytes) as u32;
+                    let bytes = &bytes[core::mem::size_of::<i32>()..];
 
-    // We will define a struct by this name
-    let ident = input.ident.clone();
-    let decompressor_ident = format_ident!("{}DecompressorImpl", ident);
+                    // Iterate over the bits
+                    let mut iter = HalfIter::new(bytes);
 
-    let columns = get_fields_of_struct(input);
-    let (col_idents, col_tys, _col_attrs): (Vec<_>, Vec<_>, Vec<_>) = multiunzip(columns);
+                    #read_presence
 
-    let col_vec_idents = col_idents
-        .iter()
-        .map(|ident| format_ident!("col_{}", ident))
-        .collect_vec();
+                    // Read the offset and length of each column
+                    let column_table = ::tsz_compress::prelude::read_column_table::<#num_columns>(&mut iter)?;
 
-    let decode_idents = col_tys
-        .iter()
-        .map(|ty| match ty {
-            syn::Type::Path(syn::TypePath { path, .. }) => {
-                let segment = path.segments.first().unwrap();
-                let ident = segment.ident.clone();
-                match ident.to_string().as_str() {
-                    "i8" => quote! { decode_i8 },
-                    "i16" => quote! { decode_i16 },
-                    "i32" => quote! { decode_i32 },
-                    "i64" => quote! { decode_i64 },
-                    _ => panic!("Unsupported type"),
+                    // Decode each selected column in the frame from its offset
+                    let selected = [ #( columns.contains(&#column_ident::#variant_idents), )* ];
+                    let before = [ #( self.#decode_vec_idents.len(), )* ];
+                    #(
+                        if selected[#col_indices] {
+                            #col_projection_blocks
+                        }
+                    )*
+
+                    // Make sure every selected column in the frame grew by the row count of the frame
+                    let in_frame = [ #( #col_in_frame && selected[#col_indices], )* ];
+                    let elems = [ #( self.#decode_vec_idents.len() - before[#col_indices], )* ];
+                    if elems.iter().zip(in_frame).any(|(elem, in_frame)| in_frame && *elem != rows as usize) {
+                        #( self.#storage_idents.clear(); )*
+                        return Err(CodingError::ColumnLengthMismatch(ColumnLengths { expected_rows: rows as usize, column_lengths: elems.to_vec() }));
+                    }
+                    #check_omitted_rows
+
+                    // Fill the selected Option columns omitted from the frame, and rescale the integer columns of scaled fields
+                    #( #col_projection_fill_blocks )*
+
+                    Ok(())
                 }
-            }
-            _ => panic!("Unsupported type"),
-        })
-        .collect::<Vec<_>>();
+            },
+        )
+    } else {
+        (quote! {}, quote! {}, quote! {})
+    };
 
     let decompressor_tokens = quote! {
         pub mod decompress {
@@ -1160,19 +3520,189 @@ pub fn derive_decompressv2(tokens: TokenStream) -> TokenStream {
             mod private {
                 use super::*;
 
+                #column_enum
+
                 /// A Decompressor type implementing TszDecompressV2.
                 #[derive(Debug)]
                 pub struct #decompressor_ident {
-                    #( #col_vec_idents: ::alloc::vec::Vec<#col_tys>, )*
+                    #( #col_vec_idents: ::alloc::vec::Vec<#field_tys>, )*
+                    #( #col_raw_idents: ::alloc::vec::Vec<#col_raw_tys>, )*
                 }
 
                 impl #decompressor_ident {
+                    /// The type code of each column in the frame header.
+                    pub const COLUMN_TYPES: [u8; #num_columns] = [ #( #col_type_codes, )* ];
+
+                    /// The hash of the field names and types in the frame header.
+                    pub const SCHEMA_HASH: u32 = #schema_hash;
+
                     #(
                         /// Decompressed values for the column
-                        pub fn #col_vec_idents(&self) -> &[#col_tys] {
+                        pub fn #col_vec_idents(&self) -> &[#field_tys] {
                             &self.#col_vec_idents
                         }
                     )*
+
+                    #(
+                        /// Decompressed values of the integer column of the scaled field
+                        pub fn #col_raw_idents(&self) -> &[#col_raw_tys] {
+                            &self.#col_raw_idents
+                        }
+                    )*
+                }
+
+                impl #decompressor_ident {
+                    ///
+                    /// Lazily decompress tsz-compressed bytes into an iterator over the rows,
+                    /// decoding one row at a time with a cursor into each column.
+                    ///
+                    pub fn iter_rows(bytes: &[u8]) -> #decompress_iter_ident<'_> {
+                        match #decompress_iter_ident::open(bytes) {
+                            Ok(Some((cursors, rows))) => #decompress_iter_ident { cursors: Some(cursors), rows, unpacked: None, error: None },
+                            Ok(None) => {
+                                // Decode the packed frame into columns and rotate them as rows are taken
+                                let mut decompressor = Self::new();
+                                match decompressor.decompress(bytes) {
+                                    Ok(()) => {
+                                        let rows = decompressor.#first_col_vec_ident.len();
+                                        #decompress_iter_ident { cursors: None, rows, unpacked: Some((decompressor, 0)), error: None }
+                                    }
+                                    Err(e) => #decompress_iter_ident { cursors: None, rows: 0, unpacked: None, error: Some(e) },
+                                }
+                            }
+                            Err(e) => #decompress_iter_ident { cursors: None, rows: 0, unpacked: None, error: Some(e) },
+                        }
+                    }
+
+                    #iter_rows_range
+
+                    #decompress_range
+
+                    #decompress_columns
+                }
+
+                ///
+                /// An iterator over the rows of tsz-compressed bytes.
+                ///
+                pub struct #decompress_iter_ident<'a> {
+                    cursors: Option<( #( #col_cursor_tys, )* )>,
+                    rows: usize,
+                    unpacked: Option<(#decompressor_ident, usize)>,
+                    error: Option<CodingError>,
+                }
+
+                // Tuples of more than 12 cursors are not Debug, so the cursors are left out
+                impl core::fmt::Debug for #decompress_iter_ident<'_> {
+                    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+                        f.debug_struct(stringify!(#decompress_iter_ident))
+                            .field("rows", &self.rows)
+                            .field("unpacked", &self.unpacked)
+                            .field("error", &self.error)
+                            .finish_non_exhaustive()
+                    }
+                }
+
+                impl<'a> #decompress_iter_ident<'a> {
+                    ///
+                    /// Validates the frame and places a cursor at the start of each column.
+                    ///
+                    fn open(bytes: &'a [u8]) -> Result<Option<(( #( #col_cursor_tys, )* ), usize)>, CodingError> {
+                        #read_checksum
+
+                        #read_header
+
+                        #open_lz
+
+                        #split_seek
+
+                        // Require at least the row count and 1 column
+                        if bytes.len() < core::mem::size_of::<i32>() + 1 {
+                            return Err(CodingError::Empty);
+                        }
+
+                        // Read the row count
+                        let row_bytes: &[u8; 4] = bytes[..4].try_into().map_err(|_|CodingError::NotEnoughBits)?;
+                        let rows = read_full_i32(row_bytes) as u32;
+                        let bytes = &bytes[core::mem::size_of::<i32>()..];
+
+                        // Iterate over the bits
+                        let mut iter = HalfIter::new(bytes);
+
+                        #read_presence
+
+                        #skip_column_table
+
+                        // Expect a headers::START_OF_COLUMN tag indicating the start of a new column
+                        if #any_column iter.next() != Some(::tsz_compress::prelude::consts::headers::START_OF_COLUMN) {
+                            return Err(CodingError::InvalidInitialColumnTag);
+                        }
+
+                        // Scan each column to place a cursor at its start
+                        #( #col_cursor_blocks )*
+
+                        // Pad nibbles to byte-alignment
+                        match iter.next() {
+                            Some(::tsz_compress::prelude::consts::headers::START_OF_COLUMN) | None => (),
+                            Some(_) => return Err(CodingError::InvalidColumnTag),
+                        }
+
+                        // Make sure every column has the row count of the frame
+                        let elems = [ #( #col_idents.1, )* ];
+                        if elems.iter().any(|elem| *elem != rows as usize) {
+                            return Err(CodingError::ColumnLengthMismatch(ColumnLengths { expected_rows: rows as usize, column_lengths: elems.to_vec() }));
+                        }
+
+                        Ok(Some((( #( #col_idents.0, )* ), elems[0])))
+                    }
+
+                    #seek_fn
+                }
+
+                impl<'a> Iterator for #decompress_iter_ident<'a> {
+                    type Item = Result<#ident, CodingError>;
+
+                    fn next(&mut self) -> Option<Self::Item> {
+                        if let Some(e) = self.error.take() {
+                            return Some(Err(e));
+                        }
+
+                        // Take the next row of a decoded packed frame
+                        if let Some((decompressor, i)) = self.unpacked.as_mut() {
+                            if self.rows == 0 {
+                                self.unpacked = None;
+                                return None;
+                            }
+                            self.rows -= 1;
+                            let row = #ident {
+                                #( #col_idents: decompressor.#col_vec_idents[*i], )*
+                            };
+                            *i += 1;
+                            return Some(Ok(row));
+                        }
+
+                        let cursors = self.cursors.as_mut()?;
+                        if self.rows == 0 {
+                            self.cursors = None;
+                            return None;
+                        }
+                        self.rows -= 1;
+
+                        // Decode the next value of each column
+                        let row = (|| {
+                            Ok(#ident {
+                                #( #col_idents: #col_row_values, )*
+                            })
+                        })();
+                        if row.is_err() {
+                            self.cursors = None;
+                        }
+                        Some(row)
+                    }
+
+                    fn size_hint(&self) -> (usize, Option<usize>) {
+                        let rows = self.rows + self.error.is_some() as usize;
+                        (rows, Some(rows))
+                    }
                 }
 
                 impl TszDecompressV2 for #decompressor_ident {
@@ -1181,12 +3711,20 @@ pub fn derive_decompressv2(tokens: TokenStream) -> TokenStream {
                     /// Initialize a decompressor with a vector for each column.
                     fn new() -> Self {
                         #decompressor_ident {
-                            #( #col_vec_idents: ::alloc::vec::Vec::new(), )*
+                            #( #storage_idents: ::alloc::vec::Vec::new(), )*
                         }
                     }
 
                     /// Decompress tsz-compressed bytes, extending the columns with the decompressed values.
                     fn decompress(&mut self, bytes: &[u8]) -> Result<(), CodingError> {
+                        #read_checksum
+
+                        #read_header
+
+                        #read_lz
+
+                        #split_seek
+
                         // Require at least the row count and 1 column
                         if bytes.len() < core::mem::size_of::<i32>() + 1 {
                             return Err(CodingError::Empty);
@@ -1198,31 +3736,32 @@ pub fn derive_decompressv2(tokens: TokenStream) -> TokenStream {
                         let rows = read_full_i32(row_bytes) as u32;
                         let bytes = &bytes[core::mem::size_of::<i32>()..];
 
-                        // At best we can emit 3 bits per row not counting any metadata for one column
-                        if rows as usize > bytes.len() * 8 / 3 {
-                            return Err(CodingError::InvalidRowCount(rows as usize));
-                        }
-
                         // Reserve space for the rows if there is enough remaining capacity
+                        // Runs of zeros or missing values may cover any number of rows, so reserve at most 3 bits per row
                         #(
-                            let remaining = (self.#col_vec_idents.capacity() - self.#col_vec_idents.len()) as isize;
-                            let reservation = rows as isize - remaining;
+                            let remaining = (self.#storage_idents.capacity() - self.#storage_idents.len()) as isize;
+                            let reservation = (rows as usize).min(bytes.len() * 8 / 3) as isize - remaining;
                             if reservation > 0 {
-                                self.#col_vec_idents.reserve(reservation as usize);
+                                self.#storage_idents.reserve(reservation as usize);
                             }
                         )*
 
                         // Iterate over the bits
                         let mut iter = HalfIter::new(bytes);
 
+                        #read_presence
+
+                        #skip_column_table
+
                         // Expect a headers::START_OF_COLUMN tag indicating the start of a new column
-                        if iter.next() != Some(::tsz_compress::prelude::consts::headers::START_OF_COLUMN) {
-                            #( self.#col_vec_idents.clear(); )*
+                        if #any_column iter.next() != Some(::tsz_compress::prelude::consts::headers::START_OF_COLUMN) {
+                            #( self.#storage_idents.clear(); )*
                             return Err(CodingError::InvalidInitialColumnTag);
                         }
 
                         // Read the column bytes into a vector one after the other
-                        #( #decode_idents(&mut iter, &mut self.#col_vec_idents)?; )*
+                        let before = [ #( self.#decode_vec_idents.len(), )* ];
+                        #( #col_decode_blocks )*
 
                         // Pad nibbles to byte-alignment
                         match iter.next() {
@@ -1230,21 +3769,29 @@ pub fn derive_decompressv2(tokens: TokenStream) -> TokenStream {
                             Some(_) => return Err(CodingError::InvalidColumnTag),
                         }
 
-                        // Make sure all the columns are the same length
-                        let elems = [ #( self.#col_vec_idents.len(), )* ];
-                        if !elems.iter().all(|elem| *elem == elems[0]) {
-                            #( self.#col_vec_idents.clear(); )*
+                        // Make sure every column in the frame grew by the row count of the frame
+                        let in_frame = [ #( #col_in_frame, )* ];
+                        let elems = [ #( self.#decode_vec_idents.len() - before[#col_indices], )* ];
+                        if elems.iter().zip(in_frame).any(|(elem, in_frame)| in_frame && *elem != rows as usize) {
+                            #( self.#storage_idents.clear(); )*
                             return Err(CodingError::ColumnLengthMismatch(ColumnLengths { expected_rows: rows as usize, column_lengths: elems.to_vec() }));
                         }
+                        #check_omitted_rows
+
+                        // Fill the Option columns omitted from the frame
+                        #( #col_fill_blocks )*
+
+                        // Rescale the integer columns of scaled fields
+                        #( #col_rescale_blocks )*
 
                         Ok(())
                     }
 
                     /// Rotate the columns into rows
                     fn rows(&self) -> ::alloc::vec::Vec<Self::T> {
-                        // Create the rows from columns
+                        // Create the rows from columns, of which only some may have been decompressed
                         let elems = [ #( self.#col_vec_idents.len(), )* ];
-                        let len = elems[0];
+                        let len = elems.into_iter().min().unwrap_or(0);
                         let mut rows = ::alloc::vec::Vec::with_capacity(len);
                         for i in 0..len {
                             rows.push(#ident {
@@ -1256,13 +3803,148 @@ pub fn derive_decompressv2(tokens: TokenStream) -> TokenStream {
 
                     /// Clear the internal state
                     fn clear(&mut self) {
-                        #( self.#col_vec_idents.clear(); )*
+                        #( self.#storage_idents.clear(); )*
                     }
                 }
+
+                #ffi_fns
             }
-            pub use private::#decompressor_ident;
+            pub use private::{#decompressor_ident, #decompress_iter_ident};
+            #column_enum_use
+
+            #c_header
         }
 
     };
     decompressor_tokens.into()
 }
+
+///
+/// ColumnEnum is a procedural macro that will implement ColumnEnum for a fieldless enum
+/// with an integer `#[repr]`, so it may be a field of a struct deriving CompressV2/DecompressV2.
+///
+#[proc_macro_derive(ColumnEnum)]
+pub fn derive_column_enum(tokens: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(tokens as syn::DeriveInput);
+    let ident = input.ident.clone();
+
+    // The discriminant type is the integer repr of the enum
+    let repr = input
+        .attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("repr"))
+        .find_map(|attr| attr.parse_args::<syn::Type>().ok())
+        .expect("Expected an integer #[repr] on the enum");
+    let decode_ident = match quote! { #repr }.to_string().as_str() {
+        "i8" => quote! { decode_i8_rows },
+        "i16" => quote! { decode_i16_rows },
+        "i32" => quote! { decode_i32_rows },
+        "i64" => quote! { decode_i64_rows },
+        "u8" => quote! { decode_u8_rows },
+        "u16" => quote! { decode_u16_rows },
+        "u32" => quote! { decode_u32_rows },
+        "u64" => quote! { decode_u64_rows },
+        _ => panic!("Expected an integer #[repr] of at most 64 bits"),
+    };
+
+    let variants = match input.data {
+        syn::Data::Enum(syn::DataEnum { variants, .. }) => variants,
+        _ => panic!("Expected a fieldless enum"),
+    };
+    let variant_idents = variants
+        .into_iter()
+        .map(|variant| match variant.fields {
+            syn::Fields::Unit => variant.ident,
+            _ => panic!("Expected a fieldless enum"),
+        })
+        .collect_vec();
+
+    quote! {
+        impl ::tsz_compress::prelude::ColumnEnum for #ident {
+            type Repr = #repr;
+
+            #[inline(always)]
+            fn discriminant(self) -> #repr {
+                self as #repr
+            }
+
+            fn from_discriminant(discriminant: #repr) -> Option<Self> {
+                #(
+                    if discriminant == #ident::#variant_idents as #repr {
+                        return Some(#ident::#variant_idents);
+                    }
+                )*
+                None
+            }
+
+            fn decode_column(
+                iter: &mut ::tsz_compress::prelude::HalfIter<'_>,
+                rows: usize,
+                output: &mut ::alloc::vec::Vec<Self>,
+            ) -> Result<(), ::tsz_compress::prelude::CodingError> {
+                ::tsz_compress::prelude::decode_enum(iter, rows, output, ::tsz_compress::prelude::#decode_ident)
+            }
+        }
+    }
+    .into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lossy(max_error: &str) -> FieldAttributes {
+        FieldAttributes {
+            max_error: Some(max_error.parse().unwrap()),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn max_error_range() {
+        let i8_ty: [syn::Type; 1] = [syn::parse_quote! { i8 }];
+        let u64_ty: [syn::Type; 1] = [syn::parse_quote! { u64 }];
+        assert_eq!(max_errors(&i8_ty, &[lossy("1")]).unwrap(), [Some(1)]);
+        assert_eq!(max_errors(&i8_ty, &[lossy("127")]).unwrap(), [Some(127)]);
+        assert_eq!(max_errors(&i8_ty, &[lossy("3u8")]).unwrap(), [Some(3)]);
+        assert_eq!(max_errors(&i8_ty, &[lossy("0x10")]).unwrap(), [Some(16)]);
+        assert_eq!(
+            max_errors(&u64_ty, &[lossy("18446744073709551615")]).unwrap(),
+            [Some(u64::MAX)]
+        );
+        assert_eq!(
+            max_errors(&i8_ty, &[FieldAttributes::default()]).unwrap(),
+            [None]
+        );
+
+        // Zero, negative, non-integer and values past the column type are rejected
+        let e = max_errors(&i8_ty, &[lossy("128")]).unwrap_err();
+        assert_eq!(
+            e.to_string(),
+            "A max_error of a i8 column must be an integer in 1..=127"
+        );
+        for max_error in ["0", "-3", "1.5", "\"3\""] {
+            assert!(
+                max_errors(&u64_ty, &[lossy(max_error)]).is_err(),
+                "{max_error}"
+            );
+        }
+    }
+
+    #[test]
+    fn scale_range() {
+        let scaled = |scale: &str| FieldAttributes {
+            scale: Some(scale.parse().unwrap()),
+            ..Default::default()
+        };
+        for scale in ["100", "0.25", "1e3", "0x10", "100f32"] {
+            assert!(check_scales(&[scaled(scale)]).is_ok(), "{scale}");
+        }
+
+        // Zero, negative and non-numeric scales are rejected
+        for scale in ["0", "0.0", "-100", "-0.5", "\"100\""] {
+            let e = check_scales(&[scaled(scale)]).unwrap_err();
+            assert_eq!(e.to_string(), "A scale must be a number greater than 0");
+        }
+    }
+}